@@ -0,0 +1,232 @@
+use crate::file_header::FileHeader;
+use crate::page::index_page::records::Row;
+use crate::tablespace::table::{cmp_key, Index};
+use crate::tablespace::TableSpace;
+use console::style;
+use std::collections::BTreeMap;
+use std::io;
+
+/// 唯一确定一条记录的列值
+type Key = Vec<Option<Vec<u8>>>;
+
+/// 一条记录的快照，列值为None表示NULL。
+/// 溢出列只保存记录内的前缀和溢出页指针，溢出页中的内容(LOB)不读取、不比较
+#[derive(Debug, Eq, PartialEq)]
+pub struct RecordImage {
+    pub delete_mask: u8,
+    /// 记录内的列值，不含溢出页指针
+    pub values: Vec<Option<Vec<u8>>>,
+    /// 按列类型解码后的列值
+    pub texts: Vec<String>,
+    /// 溢出列的20字节溢出页指针
+    pub overflow: Vec<Option<Vec<u8>>>,
+}
+
+impl RecordImage {
+    pub fn new(row: &Row<bytes::Bytes>) -> Self {
+        let n_cols = row.col_info().len();
+        let mut values = Vec::with_capacity(n_cols);
+        let mut overflow = Vec::with_capacity(n_cols);
+        for index in 0..n_cols {
+            if row.is_null(index) {
+                values.push(None);
+                overflow.push(None);
+            } else {
+                let (data, ptr) = row.col(index);
+                values.push(Some(data.to_vec()));
+                overflow.push(ptr.map(|ptr| ptr.buf.to_vec()));
+            }
+        }
+        Self {
+            delete_mask: row.delete_mask(),
+            values,
+            texts: (0..n_cols).map(|index| row.value(index)).collect(),
+            overflow,
+        }
+    }
+}
+
+/// 比较两个表空间文件，先比较页，再按主键比较索引中的记录
+pub struct TableSpaceDiff<'a> {
+    old: &'a TableSpace,
+    new: &'a TableSpace,
+}
+
+impl<'a> TableSpaceDiff<'a> {
+    pub fn new(old: &'a TableSpace, new: &'a TableSpace) -> Self {
+        Self { old, new }
+    }
+
+    pub fn print(&self) -> io::Result<()> {
+        self.print_pages()?;
+        let mut indexes: Vec<&Index> = self.old.table_info.indexes.values().collect();
+        indexes.sort_by_key(|index| (!index.is_primary, index.index_id));
+        for index in indexes {
+            match self.new.index(index.index_id) {
+                Some(new_index) => self.print_index(index, new_index)?,
+                None => println!(
+                    "{} {} not found in {}",
+                    style("index").green(),
+                    index.name,
+                    self.new.idb_file_path
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    fn print_pages(&self) -> io::Result<()> {
+        let old_size = self.old.fsp_page()?.fsp_header.size();
+        let new_size = self.new.fsp_page()?.fsp_header.size();
+        println!("{}", style("pages").green());
+        println!(" size:{} -> {}", old_size, new_size);
+        for page_num in 0..old_size.max(new_size) {
+            if page_num >= old_size {
+                println!(" page {} only in {}", page_num, self.new.idb_file_path);
+                continue;
+            }
+            if page_num >= new_size {
+                println!(" page {} only in {}", page_num, self.old.idb_file_path);
+                continue;
+            }
+            let old_buf = self.old.read_page(page_num)?;
+            let new_buf = self.new.read_page(page_num)?;
+            if old_buf == new_buf {
+                continue;
+            }
+            let old_fil = FileHeader::new(old_buf.slice(..38));
+            let new_fil = FileHeader::new(new_buf.slice(..38));
+            if old_fil.lsn() != new_fil.lsn() {
+                println!(
+                    " page {} {:?} -> {:?} lsn:{} -> {}",
+                    page_num,
+                    old_fil.page_type(),
+                    new_fil.page_type(),
+                    old_fil.lsn(),
+                    new_fil.lsn()
+                );
+            } else {
+                println!(
+                    " page {} {:?} -> {:?} content changed, lsn:{}",
+                    page_num,
+                    old_fil.page_type(),
+                    new_fil.page_type(),
+                    old_fil.lsn()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn print_index(&self, old_index: &Index, new_index: &Index) -> io::Result<()> {
+        println!("{} {}", style("index").green(), old_index.name);
+        let key_len = key_len(old_index);
+        let old_records = records(self.old, old_index, key_len)?;
+        let new_records = records(self.new, new_index, key_len)?;
+        let columns = &old_index.elements;
+        let key_name = columns[..key_len]
+            .iter()
+            .map(|col| col.name.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        // 按索引顺序输出
+        let mut old_keys: Vec<&Key> = old_records.keys().collect();
+        old_keys.sort_by(|a, b| cmp_key(&columns[..key_len], a, b));
+        for key in old_keys {
+            let old = &old_records[key];
+            let key_str = format_key(&key_name, &old.texts[..key_len]);
+            match new_records.get(key) {
+                None => println!(" {} deleted {}", style("-").red(), key_str),
+                Some(new) if old != new => {
+                    if old.delete_mask != new.delete_mask {
+                        println!(
+                            " {} delete_mask {} -> {} {}",
+                            style("~").yellow(),
+                            old.delete_mask,
+                            new.delete_mask,
+                            key_str
+                        );
+                    }
+                    let changed: Vec<usize> = (0..columns.len())
+                        .filter(|&index| old.values.get(index) != new.values.get(index))
+                        .collect();
+                    // 溢出页指针不同，溢出页中的内容可能改变，但不读取比较
+                    let not_compared: Vec<usize> = (0..columns.len())
+                        .filter(|index| !changed.contains(index))
+                        .filter(|&index| old.overflow.get(index) != new.overflow.get(index))
+                        .collect();
+                    if !changed.is_empty() || !not_compared.is_empty() {
+                        println!(" {} updated {}", style("~").yellow(), key_str);
+                    }
+                    for index in changed {
+                        println!(
+                            "     {}: {} -> {}",
+                            style(&columns[index].name).yellow(),
+                            format_value(old, index),
+                            format_value(new, index)
+                        );
+                    }
+                    for index in not_compared {
+                        println!(
+                            "     {}: overflow pointer changed, off-page content not compared",
+                            style(&columns[index].name).yellow()
+                        );
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        let mut new_keys: Vec<&Key> = new_records.keys().collect();
+        new_keys.sort_by(|a, b| cmp_key(&columns[..key_len], a, b));
+        for key in new_keys {
+            if !old_records.contains_key(key) {
+                println!(
+                    " {} inserted {}",
+                    style("+").green(),
+                    format_key(&key_name, &new_records[key].texts[..key_len])
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 唯一确定一条记录的列数，聚簇索引为主键列，二级索引为全部列(索引列+主键列)
+fn key_len(index: &Index) -> usize {
+    if index.is_primary {
+        index.indexes.len() - 1
+    } else {
+        index.elements.len()
+    }
+}
+
+fn records(
+    tablespace: &TableSpace,
+    index: &Index,
+    key_len: usize,
+) -> io::Result<BTreeMap<Key, RecordImage>> {
+    let mut map = BTreeMap::new();
+    for page_num in tablespace.leaf_pages(index)? {
+        let page = tablespace.page(page_num)?;
+        if let Some(index_page) = page.index_page() {
+            for row in &index_page.user_records {
+                let image = RecordImage::new(row);
+                map.insert(image.values[..key_len].to_vec(), image);
+            }
+        }
+    }
+    Ok(map)
+}
+
+fn format_key(key_name: &str, key: &[String]) -> String {
+    format!("({})=({})", key_name, key.join(","))
+}
+
+/// 列值的文本形式，列不存在时为 -
+fn format_value(image: &RecordImage, index: usize) -> String {
+    image
+        .texts
+        .get(index)
+        .cloned()
+        .unwrap_or_else(|| String::from("-"))
+}
@@ -1,6 +1,6 @@
+use console::style;
 use std::fmt;
 use std::fmt::Formatter;
-use console::style;
 
 use crate::file_header::page_type::PageType;
 
@@ -9,13 +9,13 @@ pub mod page_type;
 /// 名称   |长度|描述
 /// ------|:------|:------
 /// FIL_PAGE_SPACE_OR_CHKSUM     | 4 |页的校验和 (checksum)
-/// FIL_PAGE_OFFSET	|4|	页号 (每个页的都不同)
-/// FIL_PAGE_PREV	|4|	上一个页的页号
-/// FIL_PAGE_NEXT	|4|	下一个页的页号
-/// FIL_PAGE_LSN	|8|	页面被最后修改时对应的日志序列位置 (Log Sequence Number)
-/// FIL_PAGE_TYPE	|2|	该页的类型
-/// FIL_PAGE_FILE_FLUSH_LSN	|8|	仅在系统表空间的一个页中定义，代表文件至少被刷新到了对应的 LSN 值
-/// FIL_PAGE_ARCH_LOG_NO_OR_SPACE_ID	|4|	页属于哪个表空间
+/// FIL_PAGE_OFFSET |4| 页号 (每个页的都不同)
+/// FIL_PAGE_PREV |4| 上一个页的页号
+/// FIL_PAGE_NEXT |4| 下一个页的页号
+/// FIL_PAGE_LSN |8| 页面被最后修改时对应的日志序列位置 (Log Sequence Number)
+/// FIL_PAGE_TYPE |2| 该页的类型
+/// FIL_PAGE_FILE_FLUSH_LSN |8| 仅在系统表空间的一个页中定义，代表文件至少被刷新到了对应的 LSN 值
+/// FIL_PAGE_ARCH_LOG_NO_OR_SPACE_ID |4| 页属于哪个表空间
 ///
 pub struct FileHeader<B> {
     buffer: B,
@@ -71,7 +71,7 @@ impl<B: AsRef<[u8]>> FileHeader<B> {
 
 impl<B: AsRef<[u8]>> fmt::Display for FileHeader<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}",style("FileHeader").green())?;
+        writeln!(f, "{}", style("FileHeader").green())?;
         writeln!(
             f,
             " check_sum:{} ({})",
@@ -1,4 +1,6 @@
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+use num_enum::FromPrimitive;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, FromPrimitive)]
 #[repr(u16)]
 pub enum PageType {
     /// 最新分配，还没使用
@@ -24,6 +26,7 @@ pub enum PageType {
     FilPageTypeBlob = 10,
     FilPageTypeZblob = 11,
     FilPageTypeZblob2 = 12,
+    #[num_enum(default)]
     FilPageTypeUnknown = 13,
     FilPageCompressed = 14,
     FilPageEncrypted = 15,
@@ -46,9 +49,3 @@ pub enum PageType {
     /// B+树的节点
     FilPageIndex = 17855,
 }
-
-impl From<u16> for PageType {
-    fn from(value: u16) -> Self {
-        unsafe { std::mem::transmute(value) }
-    }
-}
@@ -1,5 +1,5 @@
-use std::fmt;
 use console::style;
+use std::fmt;
 
 pub struct FileTrailer<B> {
     buffer: B,
@@ -29,7 +29,7 @@ impl<B: AsRef<[u8]>> FileTrailer<B> {
 }
 impl<B: AsRef<[u8]>> fmt::Display for FileTrailer<B> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}",style("FileTrailer").green())?;
+        writeln!(f, "{}", style("FileTrailer").green())?;
         writeln!(
             f,
             " check_sum:{} ({})",
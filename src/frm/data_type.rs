@@ -1,3 +1,4 @@
+/// .frm 中的字段类型，即 enum_field_types
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[repr(u8)]
 pub enum DataType {
@@ -44,6 +45,62 @@ pub enum DataType {
 
 impl From<u8> for DataType {
     fn from(value: u8) -> Self {
-        unsafe { std::mem::transmute(value) }
+        match value {
+            0 => DataType::MysqlTypeDecimal,
+            1 => DataType::MysqlTypeTiny,
+            2 => DataType::MysqlTypeShort,
+            3 => DataType::MysqlTypeLong,
+            4 => DataType::MysqlTypeFloat,
+            5 => DataType::MysqlTypeDouble,
+            6 => DataType::MysqlTypeNull,
+            7 => DataType::MysqlTypeTimestamp,
+            8 => DataType::MysqlTypeLongLong,
+            9 => DataType::MysqlTypeInt24,
+            10 => DataType::MysqlTypeDate,
+            11 => DataType::MysqlTypeTime,
+            12 => DataType::MysqlTypeDatetime,
+            13 => DataType::MysqlTypeYear,
+            14 => DataType::MysqlTypeNewDate,
+            15 => DataType::MysqlTypeVarchar,
+            16 => DataType::MysqlTypeBit,
+            17 => DataType::MysqlTypeTimestamp2,
+            18 => DataType::MysqlTypeDatetime2,
+            19 => DataType::MysqlTypeTime2,
+            20 => DataType::MysqlTypeTypedArray,
+            244 => DataType::MysqlTypeBool,
+            245 => DataType::MysqlTypeJson,
+            246 => DataType::MysqlTypeNewDecimal,
+            247 => DataType::MysqlTypeEnum,
+            248 => DataType::MysqlTypeSet,
+            249 => DataType::MysqlTypeTinyBlob,
+            250 => DataType::MysqlTypeMediumBlob,
+            251 => DataType::MysqlTypeLongBlob,
+            252 => DataType::MysqlTypeBlob,
+            253 => DataType::MysqlTypeVarString,
+            254 => DataType::MysqlTypeString,
+            255 => DataType::MysqlTypeGeometry,
+            _ => DataType::MysqlTypeInvalid,
+        }
     }
-}
\ No newline at end of file
+}
+
+impl DataType {
+    /// 对应数据字典(SDI)中的列类型编号，即 dd::enum_column_types
+    pub fn dd_type(&self) -> u8 {
+        match self {
+            DataType::MysqlTypeNewDecimal => 21,
+            DataType::MysqlTypeEnum => 22,
+            DataType::MysqlTypeSet => 23,
+            DataType::MysqlTypeTinyBlob => 24,
+            DataType::MysqlTypeMediumBlob => 25,
+            DataType::MysqlTypeLongBlob => 26,
+            DataType::MysqlTypeBlob => 27,
+            DataType::MysqlTypeVarString => 28,
+            DataType::MysqlTypeString => 29,
+            DataType::MysqlTypeGeometry => 30,
+            DataType::MysqlTypeJson => 31,
+            DataType::MysqlTypeBool | DataType::MysqlTypeInvalid => 0,
+            other => *other as u8 + 1,
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use std::io;
 
+/// form section 固定288字节，紧跟其后依次为 screen section、字段定义(每个17字节)、
+/// 字段名、ENUM/SET 取值、字段注释
 pub struct FormSection<B> {
     buffer: B,
 }
 
 impl<B: AsRef<[u8]>> FormSection<B> {
     pub fn new(buffer: B) -> io::Result<FormSection<B>> {
-        if buffer.as_ref().len() != 286 {
-            Err(io::Error::new(io::ErrorKind::Other, format!("FormSection len {}!=286", buffer.as_ref().len())))
+        if buffer.as_ref().len() != 288 {
+            Err(io::Error::other(format!(
+                "FormSection len {}!=288",
+                buffer.as_ref().len()
+            )))
         } else {
             Ok(Self { buffer })
         }
@@ -15,16 +20,39 @@ impl<B: AsRef<[u8]>> FormSection<B> {
 }
 
 impl<B: AsRef<[u8]>> FormSection<B> {
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([
+            self.buffer.as_ref()[offset],
+            self.buffer.as_ref()[offset + 1],
+        ])
+    }
+    /// 表注释，最多60字节
+    pub fn comment(&self) -> &[u8] {
+        let len = (self.buffer.as_ref()[46] as usize).min(60);
+        &self.buffer.as_ref()[47..47 + len]
+    }
     /// 表中字段数
     pub fn column_count(&self) -> u16 {
-        u16::from_be_bytes([self.buffer.as_ref()[258], self.buffer.as_ref()[259]])
+        self.u16_at(258)
     }
     /// screen section 长度
     pub fn screens_length(&self) -> u16 {
-        u16::from_be_bytes([self.buffer.as_ref()[260], self.buffer.as_ref()[261]])
+        self.u16_at(260)
+    }
+    /// 字段名总长度
+    pub fn names_length(&self) -> u16 {
+        self.u16_at(268)
     }
-    /// 允许为 null 的字段数量
-    pub fn null_columns(&self) -> u16 {
-        u16::from_be_bytes([self.buffer.as_ref()[282], self.buffer.as_ref()[283]])
+    /// ENUM/SET 取值列表的数量
+    pub fn interval_count(&self) -> u16 {
+        self.u16_at(270)
     }
-}
\ No newline at end of file
+    /// ENUM/SET 取值总长度
+    pub fn intervals_length(&self) -> u16 {
+        self.u16_at(274)
+    }
+    /// 字段注释总长度
+    pub fn comments_length(&self) -> u16 {
+        self.u16_at(284)
+    }
+}
@@ -1,46 +1,130 @@
+/// 索引定义区
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// 索引数量、索引字段数量 |6| 索引数量超过127时第一个字节最高位为1
+/// 索引块 |8 * 索引数量 + 9 * 索引字段数量| 每个索引8字节，紧跟该索引的字段，每个字段9字节
+/// 索引名 |可变| 以 0xff 分隔，以 0x00 结尾
+/// 索引注释 |可变| 仅在索引标识含 HA_USES_COMMENT 时存在，2字节长度 + 内容
 pub struct IndexSection<B> {
     buffer: B,
 }
 
+/// 索引块及其字段
+pub type KeyBlock<'a> = (IndexBlock<&'a [u8]>, Vec<IndexBlockPart<&'a [u8]>>);
+
+/// 唯一索引，.frm中存储的值与该位异或
+pub const HA_NOSAME: u16 = 1;
+pub const HA_FULLTEXT: u16 = 128;
+pub const HA_SPATIAL: u16 = 1024;
+pub const HA_USES_COMMENT: u16 = 4096;
+
+impl<B: AsRef<[u8]>> IndexSection<B> {
+    pub fn new(buffer: B) -> IndexSection<B> {
+        Self { buffer }
+    }
+}
+
 impl<B: AsRef<[u8]>> IndexSection<B> {
     /// 索引数量
     pub fn key_count(&self) -> u16 {
-        if self.buffer.as_ref()[0] & 0x80 == 0x80 {
-            return (self.buffer.as_ref()[0] & 0x7f) as u16 + (self.buffer.as_ref()[1] as u16) << 7;
+        let buf = self.buffer.as_ref();
+        if buf[0] & 0x80 == 0x80 {
+            return ((buf[1] as u16) << 7) | (buf[0] & 0x7f) as u16;
         }
-        self.buffer.as_ref()[0] as u16
+        buf[0] as u16
     }
     /// 索引中字段数量
     pub fn key_parts(&self) -> u16 {
-        if self.buffer.as_ref()[0] & 0x80 == 0x80 {
-            return u16::from_be_bytes([self.buffer.as_ref()[2], self.buffer.as_ref()[3]]);
+        let buf = self.buffer.as_ref();
+        if buf[0] & 0x80 == 0x80 {
+            return u16::from_le_bytes([buf[2], buf[3]]);
         }
-        self.buffer.as_ref()[1] as u16
+        buf[1] as u16
     }
     /// 获取索引
-    pub fn keys(&self) {
-
+    pub fn keys(&self) -> Vec<KeyBlock<'_>> {
+        let buf = self.buffer.as_ref();
+        let mut keys = Vec::with_capacity(self.key_count() as usize);
+        let mut offset = 6;
+        for _ in 0..self.key_count() {
+            let block = IndexBlock::new(&buf[offset..offset + 8]);
+            offset += 8;
+            let mut parts = Vec::with_capacity(block.user_defined_key_parts() as usize);
+            for _ in 0..block.user_defined_key_parts() {
+                parts.push(IndexBlockPart::new(&buf[offset..offset + 9]));
+                offset += 9;
+            }
+            keys.push((block, parts));
+        }
+        keys
+    }
+    /// 索引名和索引注释
+    pub fn key_names(&self) -> Vec<(String, String)> {
+        let buf = self.buffer.as_ref();
+        let keys = self.keys();
+        let mut offset = 6 + keys.len() * 8 + self.key_parts() as usize * 9;
+        let start = offset;
+        while offset < buf.len() && buf[offset] != 0 {
+            offset += 1;
+        }
+        let mut names: Vec<String> = buf[start..offset]
+            .split(|x| *x == 0xFF)
+            .filter(|name| !name.is_empty())
+            .map(|name| String::from_utf8_lossy(name).to_string())
+            .collect();
+        names.resize(keys.len(), String::new());
+        offset += 1;
+        let mut result = Vec::with_capacity(keys.len());
+        for ((block, _), name) in keys.iter().zip(names) {
+            let mut comment = String::new();
+            if block.flag() & HA_USES_COMMENT != 0 && offset + 2 <= buf.len() {
+                let len = u16::from_le_bytes([buf[offset], buf[offset + 1]]) as usize;
+                let end = (offset + 2 + len).min(buf.len());
+                comment = String::from_utf8_lossy(&buf[offset + 2..end]).to_string();
+                offset = end;
+            }
+            result.push((name, comment));
+        }
+        result
     }
 }
 
 pub struct IndexBlock<B> {
     buffer: B,
 }
+
 impl<B: AsRef<[u8]>> IndexBlock<B> {
+    pub fn new(buffer: B) -> IndexBlock<B> {
+        Self { buffer }
+    }
     /// 索引标识
-    pub fn flag(&self)->u16{
-        u16::from_be_bytes([self.buffer.as_ref()[0], self.buffer.as_ref()[1]])
+    pub fn flag(&self) -> u16 {
+        u16::from_le_bytes([self.buffer.as_ref()[0], self.buffer.as_ref()[1]]) ^ HA_NOSAME
     }
     /// 索引中用户自定义字段数量
-    pub fn user_defined_key_parts(&self)->u8{
-        self.buffer.as_ref()[2]
+    pub fn user_defined_key_parts(&self) -> u8 {
+        self.buffer.as_ref()[4]
+    }
+    pub fn is_unique(&self) -> bool {
+        self.flag() & HA_NOSAME != 0
     }
-
 }
+
 pub struct IndexBlockPart<B> {
     buffer: B,
 }
-impl<B: AsRef<[u8]>> IndexBlockPart<B> {
 
+impl<B: AsRef<[u8]>> IndexBlockPart<B> {
+    pub fn new(buffer: B) -> IndexBlockPart<B> {
+        Self { buffer }
+    }
+    /// 字段序号，从1开始
+    pub fn field_number(&self) -> u16 {
+        u16::from_le_bytes([self.buffer.as_ref()[0], self.buffer.as_ref()[1]]) & 0x3FFF
+    }
+    /// 索引中该字段的字节长度，小于字段长度时为前缀索引
+    pub fn length(&self) -> u16 {
+        u16::from_le_bytes([self.buffer.as_ref()[7], self.buffer.as_ref()[8]])
+    }
 }
-
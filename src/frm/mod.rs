@@ -1,7 +1,7 @@
-/// 解析表结构文件
+/// 解析 MySQL 5.7 的 .frm 表结构文件
+pub mod data_type;
+pub mod form_section;
 pub mod frm_header;
 pub mod index_section;
-pub mod form_section;
 pub mod screen_section;
-pub mod data_type;
 pub mod table;
@@ -1,7 +1,13 @@
-pub struct ScreenSection<B>{
-    buffer:B
+/// screen section 为旧版本 unireg 的表单布局，解析时只需要跳过
+pub struct ScreenSection<B> {
+    buffer: B,
 }
-impl <B:AsRef<[u8]>> ScreenSection<B>{
 
+impl<B: AsRef<[u8]>> ScreenSection<B> {
+    pub fn new(buffer: B) -> ScreenSection<B> {
+        Self { buffer }
+    }
+    pub fn len(&self) -> usize {
+        self.buffer.as_ref().len()
+    }
 }
-
@@ -1,14 +1,505 @@
 use crate::frm::data_type::DataType;
+use crate::frm::form_section::FormSection;
+use crate::frm::frm_header::FrmHeader;
+use crate::frm::index_section::{IndexSection, HA_FULLTEXT, HA_SPATIAL};
+use crate::frm::screen_section::ScreenSection;
+use crate::tablespace::charset::Charset;
+use crate::tablespace::table::quote;
+use std::io;
+use std::path::Path;
+
+/// 字段定义中 pack_flag 的标识位
+const FIELDFLAG_DECIMAL: u16 = 1;
+const FIELDFLAG_NUMBER: u16 = 2;
+const FIELDFLAG_ZEROFILL: u16 = 4;
+const FIELDFLAG_TREAT_BIT_AS_CHAR: u16 = 4096;
+const FIELDFLAG_NO_DEFAULT: u16 = 16384;
+const FIELDFLAG_MAYBE_NULL: u16 = 32768;
+
+/// 字段定义中 unireg_type 的取值
+const NEXT_NUMBER: u8 = 15;
+const TIMESTAMP_DN_FIELD: u8 = 9;
+const TIMESTAMP_DNUN_FIELD: u8 = 11;
+
+const HA_OPTION_PACK_RECORD: u16 = 1;
+
+/// 每个字段定义17字节
+const FIELD_PACK_LENGTH: usize = 17;
 
 pub struct Column {
-    name: String,
-    data_type: DataType,
-    len: u16,
-    comment: String,
-    charset: u8,
-    is_null: bool,
+    pub name: String,
+    pub data_type: DataType,
+    /// 字段最大字节长度
+    pub len: u16,
+    pub comment: String,
+    pub charset: u16,
+    pub is_null: bool,
+    pub is_unsigned: bool,
+    pub is_zerofill: bool,
+    pub is_auto_increment: bool,
+    /// 小数位数，时间类型为秒的小数精度
+    pub decimals: u8,
+    /// BLOB/TEXT 长度字节数，1:TINY 2:普通 3:MEDIUM 4:LONG
+    pub pack_length: u8,
+    /// ENUM/SET 的取值
+    pub elements: Vec<String>,
+    /// 建表语句中的默认值，字面量已加引号，None 表示没有默认值
+    pub default: Option<String>,
 }
 
+impl Column {
+    /// 字符集单个字符的最大字节数
+    pub fn mbmaxlen(&self) -> u16 {
+        Charset::from_collation_id(self.charset as u32).map_or(1, |x| x.mbmaxlen() as u16)
+    }
+    /// 精度，DECIMAL 的字段长度包含了符号位和小数点
+    pub fn precision(&self) -> u16 {
+        let mut precision = self.len;
+        if self.decimals > 0 {
+            precision -= 1;
+        }
+        if !self.is_unsigned && precision > 0 {
+            precision -= 1;
+        }
+        precision
+    }
+    /// 建表语句中的字段类型
+    pub fn column_type(&self) -> String {
+        let chars = self.len / self.mbmaxlen().max(1);
+        let binary = self.charset == 63;
+        let mut column_type = match self.data_type {
+            DataType::MysqlTypeTiny => format!("tinyint({})", self.len),
+            DataType::MysqlTypeShort => format!("smallint({})", self.len),
+            DataType::MysqlTypeInt24 => format!("mediumint({})", self.len),
+            DataType::MysqlTypeLong => format!("int({})", self.len),
+            DataType::MysqlTypeLongLong => format!("bigint({})", self.len),
+            DataType::MysqlTypeFloat | DataType::MysqlTypeDouble => {
+                let name = if self.data_type == DataType::MysqlTypeFloat {
+                    "float"
+                } else {
+                    "double"
+                };
+                if self.decimals < 31 {
+                    format!("{}({},{})", name, self.len, self.decimals)
+                } else {
+                    name.to_string()
+                }
+            }
+            DataType::MysqlTypeDecimal | DataType::MysqlTypeNewDecimal => {
+                format!("decimal({},{})", self.precision(), self.decimals)
+            }
+            DataType::MysqlTypeYear => String::from("year(4)"),
+            DataType::MysqlTypeDate | DataType::MysqlTypeNewDate => String::from("date"),
+            DataType::MysqlTypeTime | DataType::MysqlTypeTime2 => temporal("time", self.decimals),
+            DataType::MysqlTypeDatetime | DataType::MysqlTypeDatetime2 => {
+                temporal("datetime", self.decimals)
+            }
+            DataType::MysqlTypeTimestamp | DataType::MysqlTypeTimestamp2 => {
+                temporal("timestamp", self.decimals)
+            }
+            DataType::MysqlTypeVarchar | DataType::MysqlTypeVarString if binary => {
+                format!("varbinary({})", self.len)
+            }
+            DataType::MysqlTypeVarchar | DataType::MysqlTypeVarString => {
+                format!("varchar({})", chars)
+            }
+            DataType::MysqlTypeString if binary => format!("binary({})", self.len),
+            DataType::MysqlTypeString => format!("char({})", chars),
+            DataType::MysqlTypeTinyBlob
+            | DataType::MysqlTypeMediumBlob
+            | DataType::MysqlTypeLongBlob
+            | DataType::MysqlTypeBlob => {
+                let prefix = match self.pack_length {
+                    1 => "tiny",
+                    3 => "medium",
+                    4 => "long",
+                    _ => "",
+                };
+                format!("{}{}", prefix, if binary { "blob" } else { "text" })
+            }
+            DataType::MysqlTypeJson => String::from("json"),
+            DataType::MysqlTypeGeometry => String::from("geometry"),
+            DataType::MysqlTypeBit => format!("bit({})", self.len),
+            DataType::MysqlTypeEnum | DataType::MysqlTypeSet => {
+                let values = self
+                    .elements
+                    .iter()
+                    .map(|x| format!("'{}'", x.replace('\'', "''")))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                if self.data_type == DataType::MysqlTypeEnum {
+                    format!("enum({})", values)
+                } else {
+                    format!("set({})", values)
+                }
+            }
+            other => format!("{:?}", other),
+        };
+        if self.is_unsigned {
+            column_type.push_str(" unsigned");
+        }
+        if self.is_zerofill {
+            column_type.push_str(" zerofill");
+        }
+        column_type
+    }
+    /// 字段在InnoDB记录中的字节长度，变长字段为字段最大字节长度
+    pub fn innodb_len(&self) -> usize {
+        match self.data_type {
+            DataType::MysqlTypeDecimal | DataType::MysqlTypeNewDecimal => {
+                crate::tablespace::data_type::decimal_bin_size(
+                    self.precision() as usize,
+                    self.decimals as usize,
+                )
+            }
+            DataType::MysqlTypeTimestamp2 => 4 + (self.decimals as usize).min(6).div_ceil(2),
+            DataType::MysqlTypeDatetime2 => 5 + (self.decimals as usize).min(6).div_ceil(2),
+            DataType::MysqlTypeTime2 => 3 + (self.decimals as usize).min(6).div_ceil(2),
+            DataType::MysqlTypeEnum => {
+                if self.elements.len() < 256 {
+                    1
+                } else {
+                    2
+                }
+            }
+            DataType::MysqlTypeSet => match self.elements.len().div_ceil(8) {
+                len @ 0..=4 => len,
+                _ => 8,
+            },
+            DataType::MysqlTypeBit => (self.len as usize).div_ceil(8),
+            // 5.6 之前的时间格式
+            DataType::MysqlTypeTimestamp => 4,
+            DataType::MysqlTypeTime => 3,
+            DataType::MysqlTypeDatetime => 8,
+            _ => self.len as usize,
+        }
+    }
+}
+
+fn temporal(name: &str, decimals: u8) -> String {
+    if decimals > 0 && decimals <= 6 {
+        format!("{}({})", name, decimals)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 索引定义
+pub struct Key {
+    pub name: String,
+    pub comment: String,
+    pub flag: u16,
+    pub is_unique: bool,
+    /// (字段下标(从0开始), 索引中字段的字节长度)
+    pub parts: Vec<(usize, u16)>,
+}
+
+impl Key {
+    pub fn is_primary(&self) -> bool {
+        self.name == "PRIMARY"
+    }
+    /// 全文索引的数据存储在辅助表中，表空间中没有对应的B+树
+    pub fn is_fulltext(&self) -> bool {
+        self.flag & HA_FULLTEXT != 0
+    }
+    pub fn is_spatial(&self) -> bool {
+        self.flag & HA_SPATIAL != 0
+    }
+}
+
+/// 解析后的 .frm 表结构
 pub struct Table {
+    pub name: String,
+    pub comment: String,
+    pub columns: Vec<Column>,
+    pub keys: Vec<Key>,
+}
 
-}
\ No newline at end of file
+impl Table {
+    pub fn open(frm_path: &Path) -> io::Result<Table> {
+        let buf = std::fs::read(frm_path)?;
+        let name = frm_path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Table::new(name, &buf)
+    }
+
+    pub fn new(name: String, buf: &[u8]) -> io::Result<Table> {
+        let header = FrmHeader::new(slice(buf, 0, 64)?)?;
+        // 索引定义
+        let key_offset = header.index_section_offset() as usize;
+        let key_len = header.index_section_real_len() as usize;
+        let index_section = IndexSection::new(slice(buf, key_offset, key_len)?);
+        // 默认值记录
+        let record = slice(buf, header.record_offset(), header.record_length() as usize)?;
+        // form section 位置
+        let names_length = header.names_length() as usize;
+        let form_pos = u32::from_le_bytes(slice(buf, 64 + names_length, 4)?.try_into().unwrap());
+        let form = FormSection::new(slice(buf, form_pos as usize, 288)?)?;
+        let screen = ScreenSection::new(slice(
+            buf,
+            form_pos as usize + 288,
+            form.screens_length() as usize,
+        )?);
+        let field_count = form.column_count() as usize;
+        let fields_offset = form_pos as usize + 288 + screen.len();
+        let fields = slice(buf, fields_offset, field_count * FIELD_PACK_LENGTH)?;
+        let names_offset = fields_offset + fields.len();
+        let names = slice(buf, names_offset, form.names_length() as usize)?;
+        let intervals_offset = names_offset + names.len();
+        let intervals = slice(buf, intervals_offset, form.intervals_length() as usize)?;
+        let comments_offset = intervals_offset + intervals.len();
+        let comments = slice(buf, comments_offset, form.comments_length() as usize)?;
+
+        let field_names = split_names(names);
+        let intervals = split_intervals(intervals, form.interval_count() as usize);
+        let mut null_bit_pos = if header.create_options() & HA_OPTION_PACK_RECORD != 0 {
+            0
+        } else {
+            1
+        };
+        let mut comment_offset = 0;
+        let mut columns = Vec::with_capacity(field_count);
+        for index in 0..field_count {
+            let field = &fields[index * FIELD_PACK_LENGTH..(index + 1) * FIELD_PACK_LENGTH];
+            let len = u16::from_le_bytes([field[3], field[4]]);
+            let rec_pos = u32::from_le_bytes([field[5], field[6], field[7], 0]) as usize;
+            let pack_flag = u16::from_le_bytes([field[8], field[9]]);
+            let unireg_type = field[10];
+            let interval_nr = field[12] as usize;
+            let data_type = DataType::from(field[13]);
+            let charset = if data_type == DataType::MysqlTypeGeometry {
+                63
+            } else {
+                field[14] as u16 + ((field[11] as u16) << 8)
+            };
+            let comment_len = u16::from_le_bytes([field[15], field[16]]) as usize;
+            let comment_end = (comment_offset + comment_len).min(comments.len());
+            let comment = String::from_utf8_lossy(&comments[comment_offset..comment_end]);
+            comment_offset = comment_end;
+
+            let is_null = pack_flag & FIELDFLAG_MAYBE_NULL != 0;
+            let null_bit = if is_null {
+                null_bit_pos += 1;
+                Some(null_bit_pos - 1)
+            } else {
+                None
+            };
+            if data_type == DataType::MysqlTypeBit && pack_flag & FIELDFLAG_TREAT_BIT_AS_CHAR == 0 {
+                // 不足一个字节的位存储在null标识位之后
+                null_bit_pos += len as usize & 7;
+            }
+            let is_number = pack_flag & FIELDFLAG_NUMBER != 0;
+            let elements = if interval_nr > 0 {
+                intervals.get(interval_nr - 1).cloned().unwrap_or_default()
+            } else {
+                Vec::new()
+            };
+            let mut column = Column {
+                name: field_names.get(index).cloned().unwrap_or_default(),
+                data_type,
+                len,
+                comment: comment.to_string(),
+                charset,
+                is_null,
+                is_unsigned: is_number && pack_flag & FIELDFLAG_DECIMAL == 0,
+                is_zerofill: is_number && pack_flag & FIELDFLAG_ZEROFILL != 0,
+                is_auto_increment: unireg_type == NEXT_NUMBER,
+                decimals: ((pack_flag >> 8) & 31) as u8,
+                pack_length: ((pack_flag >> 3) & 15) as u8,
+                elements,
+                default: None,
+            };
+            column.default = if pack_flag & FIELDFLAG_NO_DEFAULT != 0 || column.is_auto_increment {
+                None
+            } else if unireg_type == TIMESTAMP_DN_FIELD || unireg_type == TIMESTAMP_DNUN_FIELD {
+                Some(String::from("CURRENT_TIMESTAMP"))
+            } else if null_bit
+                .is_some_and(|bit| record.get(bit / 8).is_some_and(|x| x >> (bit % 8) & 1 == 1))
+            {
+                Some(String::from("NULL"))
+            } else {
+                default_value(
+                    &column,
+                    record.get(rec_pos.saturating_sub(1)..).unwrap_or(&[]),
+                )
+                .map(|x| quote(&x))
+            };
+            columns.push(column);
+        }
+
+        let mut keys = Vec::new();
+        for ((block, parts), (name, comment)) in index_section
+            .keys()
+            .into_iter()
+            .zip(index_section.key_names())
+        {
+            keys.push(Key {
+                name,
+                comment,
+                flag: block.flag(),
+                is_unique: block.is_unique(),
+                parts: parts
+                    .iter()
+                    .map(|part| (part.field_number() as usize - 1, part.length()))
+                    .collect(),
+            });
+        }
+        Ok(Table {
+            name,
+            comment: String::from_utf8_lossy(form.comment()).to_string(),
+            columns,
+            keys,
+        })
+    }
+    /// 在表空间中有B+树的索引
+    pub fn innodb_keys(&self) -> impl Iterator<Item = &Key> {
+        self.keys.iter().filter(|key| !key.is_fulltext())
+    }
+}
+
+fn slice(buf: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+    buf.get(offset..offset + len).ok_or_else(|| {
+        io::Error::other(format!(
+            "frm file too short, need {} bytes at {}",
+            len, offset
+        ))
+    })
+}
+
+/// 以首字节为分隔符的名称列表，如 "\xffid\xffname\xff\0"
+fn split_names(buf: &[u8]) -> Vec<String> {
+    if buf.is_empty() {
+        return Vec::new();
+    }
+    let sep = buf[0];
+    buf[1..]
+        .split(|x| *x == sep)
+        .take_while(|name| !name.is_empty() && name[0] != 0)
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect()
+}
+
+/// 多个 ENUM/SET 的取值列表，每个列表以分隔符开始，以分隔符加 \0 结束
+fn split_intervals(buf: &[u8], count: usize) -> Vec<Vec<String>> {
+    let mut intervals = Vec::with_capacity(count);
+    let mut offset = 0;
+    for _ in 0..count {
+        if offset >= buf.len() {
+            break;
+        }
+        let sep = buf[offset];
+        offset += 1;
+        let mut values = Vec::new();
+        let mut start = offset;
+        while offset < buf.len() {
+            if buf[offset] == sep {
+                values.push(String::from_utf8_lossy(&buf[start..offset]).to_string());
+                offset += 1;
+                start = offset;
+                if buf.get(offset) == Some(&0) {
+                    offset += 1;
+                    break;
+                }
+            } else {
+                offset += 1;
+            }
+        }
+        intervals.push(values);
+    }
+    intervals
+}
+
+/// 解析默认值记录中的字段值，多字节整数为小端序
+fn default_value(column: &Column, data: &[u8]) -> Option<String> {
+    let le = |len: usize| -> Option<u64> {
+        let bytes = data.get(..len)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0_u64, |acc, x| (acc << 8) | *x as u64),
+        )
+    };
+    let signed = |len: usize| -> Option<i64> {
+        let value = le(len)?;
+        let shift = 64 - len * 8;
+        Some(((value << shift) as i64) >> shift)
+    };
+    let int = |len: usize| -> Option<String> {
+        if column.is_unsigned {
+            le(len).map(|x| x.to_string())
+        } else {
+            signed(len).map(|x| x.to_string())
+        }
+    };
+    match column.data_type {
+        DataType::MysqlTypeTiny => int(1),
+        DataType::MysqlTypeShort => int(2),
+        DataType::MysqlTypeInt24 => int(3),
+        DataType::MysqlTypeLong => int(4),
+        DataType::MysqlTypeLongLong => int(8),
+        DataType::MysqlTypeFloat => le(4).map(|x| f32::from_bits(x as u32).to_string()),
+        DataType::MysqlTypeDouble => le(8).map(|x| f64::from_bits(x).to_string()),
+        DataType::MysqlTypeYear => le(1).map(|x| {
+            if x == 0 {
+                String::from("0000")
+            } else {
+                (1900 + x).to_string()
+            }
+        }),
+        DataType::MysqlTypeNewDate => {
+            le(3).map(|x| format!("{:04}-{:02}-{:02}", x >> 9, (x >> 5) & 15, x & 31))
+        }
+        DataType::MysqlTypeVarchar => {
+            let len_bytes = if column.len < 256 { 1 } else { 2 };
+            let len = le(len_bytes)? as usize;
+            let value = data.get(len_bytes..len_bytes + len)?;
+            Some(String::from_utf8_lossy(value).to_string())
+        }
+        DataType::MysqlTypeString => {
+            let value = data.get(..column.len as usize)?;
+            Some(
+                String::from_utf8_lossy(value)
+                    .trim_end_matches(' ')
+                    .to_string(),
+            )
+        }
+        DataType::MysqlTypeEnum => {
+            let len = if column.elements.len() < 256 { 1 } else { 2 };
+            let index = le(len)? as usize;
+            if index == 0 {
+                Some(String::new())
+            } else {
+                column.elements.get(index - 1).cloned()
+            }
+        }
+        DataType::MysqlTypeSet => {
+            let len = match column.elements.len().div_ceil(8) {
+                len @ 0..=4 => len,
+                _ => 8,
+            };
+            let bits = le(len)?;
+            let values: Vec<&str> = column
+                .elements
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| bits >> index & 1 == 1)
+                .map(|(_, x)| x.as_str())
+                .collect();
+            Some(values.join(","))
+        }
+        DataType::MysqlTypeTinyBlob
+        | DataType::MysqlTypeMediumBlob
+        | DataType::MysqlTypeLongBlob
+        | DataType::MysqlTypeBlob
+        | DataType::MysqlTypeJson
+        | DataType::MysqlTypeGeometry => None,
+        _ => {
+            // 其他类型暂时以十六进制显示
+            let len = column.innodb_len().min(data.len());
+            Some(format!("0x{}", hex::encode(&data[..len])))
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use std::io;
+
+/// 对应 InnoDB 的 mach0data，按大端和压缩整数格式顺序读取
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8], pos: usize) -> Self {
+        Self { buf, pos }
+    }
+    pub fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let data = self
+            .buf
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| io::Error::other(format!("truncated at {}", self.pos)))?;
+        self.pos += len;
+        Ok(data)
+    }
+    pub fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+    pub fn u16(&mut self) -> io::Result<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+    pub fn u32(&mut self) -> io::Result<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+    pub fn uint(&mut self, len: usize) -> io::Result<u32> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0u32, |acc, b| acc << 8 | *b as u32))
+    }
+    /// mach_read_compressed：按首字节决定长度，1~5字节
+    pub fn compressed(&mut self) -> io::Result<u32> {
+        let first = *self
+            .buf
+            .get(self.pos)
+            .ok_or_else(|| io::Error::other(format!("truncated at {}", self.pos)))?;
+        Ok(match first {
+            0x00..=0x7F => self.uint(1)?,
+            0x80..=0xBF => self.uint(2)? & 0x3FFF,
+            0xC0..=0xDF => self.uint(3)? & 0x1F_FFFF,
+            0xE0..=0xEF => self.uint(4)? & 0x0FFF_FFFF,
+            0xF0..=0xF7 => {
+                self.pos += 1;
+                self.u32()?
+            }
+            // MySQL 8.0 用于接近 u32::MAX 的值
+            0xF8..=0xFB => self.uint(2)? & 0x3FF | 0xFFFF_FC00,
+            0xFC..=0xFF => self.uint(3)? & 0x1_FFFF | 0xFFFE_0000,
+        })
+    }
+    /// mach_u64_read_compressed：压缩的高32位加4字节的低32位
+    pub fn compressed_u64(&mut self) -> io::Result<u64> {
+        let high = self.compressed()? as u64;
+        let low = self.u32()? as u64;
+        Ok(high << 32 | low)
+    }
+    /// mach_u64_read_much_compressed：首字节不为0xFF时只有低32位
+    pub fn much_compressed_u64(&mut self) -> io::Result<u64> {
+        if self.buf.get(self.pos) != Some(&0xFF) {
+            return Ok(self.compressed()? as u64);
+        }
+        self.pos += 1;
+        let high = self.compressed()? as u64;
+        let low = self.compressed()? as u64;
+        Ok(high << 32 | low)
+    }
+    /// 当前位置
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+    pub fn u64(&mut self) -> io::Result<u64> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+    /// 剩余的数据
+    pub fn rest(&self) -> &'a [u8] {
+        self.buf.get(self.pos..).unwrap_or_default()
+    }
+}
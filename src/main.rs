@@ -1,27 +1,64 @@
-use crate::file_header::page_type::PageType;
-use crate::page::base_page::BasePage;
-use crate::page::index_page::page::IndexPage;
-use crate::page::sdi_blob_page::SdiBlobPage;
-use crate::page::PageEnums;
-use crate::tablespace::table::read_table_info;
+use crate::diff::TableSpaceDiff;
+use crate::redo::apply::RollForward;
+use crate::redo::RedoLog;
+use crate::tablespace::dblwr::DoublewriteBuffer;
+use crate::tablespace::dictionary::read_dictionary;
+use crate::tablespace::encryption::{Keyring, TablespaceKey};
+use crate::tablespace::temporal::parse_time_zone;
+use crate::tablespace::undo::UndoFiles;
 use crate::tablespace::TableSpace;
+use crate::tui::Navigator;
 
+mod diff;
 mod file_header;
 mod file_trailer;
+mod frm;
+mod mach;
 mod page;
+mod redo;
 mod tablespace;
+mod tui;
 
 use clap::{Parser, Subcommand};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
 
 #[derive(Parser, Debug)]
 #[command(
-author = "Lu Beilin",
-version,
-about = "一个.ibd文件解析工具，帮助学习InnoDB"
+    author = "Lu Beilin",
+    version,
+    about = "一个.ibd文件解析工具，帮助学习InnoDB"
 )]
 pub struct BaseArgs {
     /// .ibd文件路径
     ibd_page: String,
+    /// 建表语句文件，sdi损坏或缺少.frm时用作表结构
+    #[arg(long, global = true)]
+    schema: Option<String>,
+    /// 指定索引的index_id，格式为 索引名=id，可多次指定；
+    /// 未指定的索引按创建顺序匹配表空间中的index_id
+    #[arg(long = "index-id", value_parser = parse_index_id, global = true)]
+    index_id: Vec<(String, u64)>,
+    /// undo记录所在的文件(ibdata1、undo_001、.ibu)，可多次指定；
+    /// 指定后 page 命令显示记录的历史版本。只指定 --as-of-trx 时从数据目录中查找
+    #[arg(long, global = true)]
+    undo: Vec<String>,
+    /// 沿undo链回溯，显示记录对该事务ID可见的版本
+    #[arg(long = "as-of-trx", global = true)]
+    as_of_trx: Option<u64>,
+    /// redo日志文件或所在目录，读取页时前滚页LSN之后的日志(离线崩溃恢复)
+    #[arg(long, global = true)]
+    redo: Option<String>,
+    /// 前滚到该LSN为止，默认为日志末尾
+    #[arg(long = "to-lsn", global = true, requires = "redo")]
+    to_lsn: Option<u64>,
+    /// keyring_file 插件或 component_keyring_file 组件的数据文件，用于解密加密的表空间
+    #[arg(long, global = true)]
+    keyring: Option<String>,
+    /// TIMESTAMP 列转换到该时区显示，格式为 +08:00，默认按 UTC 显示
+    #[arg(long = "time-zone", value_parser = parse_time_zone, global = true, allow_hyphen_values = true)]
+    time_zone: Option<i32>,
     #[clap(subcommand)]
     command: Commands,
 }
@@ -37,12 +74,137 @@ enum Commands {
     },
     /// 查看b+树根节点
     Root,
+    /// 以建表语句的形式查看表结构
+    Ddl,
+    /// 查看系统表空间(ibdata1)数据字典中的表、字段和索引
+    Tables,
+    /// 交互式浏览页，沿着兄弟页、子页和溢出页跳转
+    Tui,
+    /// 与同一张表的另一个.ibd文件比较，列出变化的页和记录
+    Diff {
+        /// 另一个.ibd文件路径
+        other: String,
+    },
+    /// 查看redo日志中作用于该表空间的记录
+    Redo {
+        /// redo日志文件(ib_logfile0、#ib_redoN)或所在目录
+        path: String,
+        /// 表空间ID，默认为.ibd文件的 space_id
+        #[arg(long)]
+        space_id: Option<u32>,
+        /// 只显示该页的日志，并标出页LSN之后尚未写入页的日志
+        #[arg(long)]
+        page: Option<u32>,
+    },
+    /// 检查空间索引，父节点记录的 MBR 应包含子页中的全部记录
+    Rtree,
+    /// 查看 change buffer 位图，列出有未合并修改的索引页
+    Ibuf,
+    /// 检查校验和错误的页，并在 doublewrite 缓冲区中查找可用的副本
+    DblwrCheck {
+        /// doublewrite 所在的文件(ibdata1、#ib_16384_0.dblwr)，可多次指定；
+        /// 未指定时从数据目录中查找
+        #[arg(long)]
+        dblwr: Vec<String>,
+        /// 用副本替换损坏的页，把修复后的表空间写到该文件
+        #[arg(long)]
+        output: Option<String>,
+    },
+}
+
+fn parse_index_id(value: &str) -> Result<(String, u64), String> {
+    let (name, id) = value
+        .split_once('=')
+        .ok_or_else(|| format!("invalid index id {}, expected name=id", value))?;
+    let id = id
+        .parse()
+        .map_err(|e| format!("invalid index id {}: {}", value, e))?;
+    Ok((name.to_string(), id))
+}
+
+impl BaseArgs {
+    fn open(&self, ibd_file_path: String) -> io::Result<TableSpace> {
+        let keyring = match &self.keyring {
+            Some(keyring) => Some(Keyring::open(Path::new(keyring))?),
+            None => None,
+        };
+        let mut tablespace = match &self.schema {
+            Some(schema) => {
+                let index_ids: HashMap<String, u64> = self.index_id.iter().cloned().collect();
+                TableSpace::with_schema(
+                    ibd_file_path,
+                    Path::new(schema),
+                    &index_ids,
+                    keyring.as_ref(),
+                )
+            }
+            None => TableSpace::new(ibd_file_path, keyring.as_ref()),
+        }?;
+        if let Some(redo) = &self.redo {
+            let redo_log = RedoLog::open(Path::new(redo))?;
+            let space_id = tablespace.page(0)?.fil_header().space_id();
+            tablespace.roll_forward = Some(RollForward::new(&redo_log, space_id, self.to_lsn));
+        }
+        if let Some(time_zone) = self.time_zone {
+            tablespace.table_info.set_time_zone(time_zone);
+        }
+        Ok(tablespace)
+    }
 }
 
 fn cmd() {
     let args = BaseArgs::parse();
-    let tablespace = TableSpace::new(args.ibd_page).unwrap();
-    match args.command {
+    // 表空间可能已损坏，不解析表结构
+    if let Commands::DblwrCheck { dblwr, output } = &args.command {
+        let path = &args.ibd_page;
+        let buffer = DoublewriteBuffer::new(path, dblwr).unwrap();
+        let keyring = args
+            .keyring
+            .as_ref()
+            .map(|keyring| Keyring::open(Path::new(keyring)))
+            .transpose()
+            .unwrap();
+        // 加密的表空间解密后再校验，读不到密钥时按未加密检查
+        let key = TableSpace::read_page_(path, 0)
+            .and_then(|page0| TablespaceKey::load(path, page0, keyring.as_ref()))
+            .unwrap_or_else(|e| {
+                println!("{}, checking pages without decryption", e);
+                None
+            });
+        for file in &buffer.files {
+            println!("dblwr file:{}", file.display());
+        }
+        println!(
+            "dblwr pages:{}",
+            buffer.pages.values().map(Vec::len).sum::<usize>()
+        );
+        let checks = buffer.check(path, key.as_ref()).unwrap();
+        for check in &checks {
+            print!(
+                "page_num:{} {} lsn:{}",
+                check.page_num, check.checksum, check.lsn
+            );
+            match check.copy {
+                Some(copy) => println!(
+                    " -> dblwr copy {}[{}] lsn:{} {}",
+                    copy.path.display(),
+                    copy.slot,
+                    copy.fil_header().lsn(),
+                    copy.checksum(key.as_ref())
+                ),
+                None => println!(" -> no valid copy ({} corrupted)", check.copies),
+            }
+        }
+        let repairable = checks.iter().filter(|check| check.copy.is_some()).count();
+        println!("corrupted:{} repairable:{}", checks.len(), repairable);
+        if let Some(output) = output {
+            let repaired = buffer.repair(path, output, key.as_ref()).unwrap();
+            println!("repaired {} pages, written to {}", repaired, output);
+        }
+        return;
+    }
+    let tablespace = args.open(args.ibd_page.clone()).unwrap();
+    match &args.command {
         Commands::List => {
             let page = tablespace.fsp_page().unwrap();
             println!("size:{}", page.fsp_header.size());
@@ -52,8 +214,20 @@ fn cmd() {
             }
         }
         Commands::Page { page_num } => {
-            let page = tablespace.page(page_num).unwrap();
+            let page = tablespace.page(*page_num).unwrap();
             println!("{}", page);
+            if let (_, Some(recovery)) = tablespace.recover_page(*page_num).unwrap() {
+                print!("{}", recovery);
+            }
+            // 只有指定了 undo 文件或事务ID时才沿 undo 链查找历史版本
+            let with_undo = !args.undo.is_empty() || args.as_of_trx.is_some();
+            if let Some(index_page) = page.index_page().filter(|_| with_undo) {
+                let undo_files = UndoFiles::new(&tablespace.idb_file_path, &args.undo).unwrap();
+                match args.as_of_trx {
+                    Some(as_of_trx) => undo_files.print_as_of(index_page, as_of_trx),
+                    None => undo_files.print_versions(index_page),
+                }
+            }
         }
         Commands::Root => {
             let root = tablespace.index_roots();
@@ -61,6 +235,88 @@ fn cmd() {
                 println!("key_name:{},root_page_num:{}", name, num)
             }
         }
+        Commands::Ddl => {
+            print!("{}", tablespace.table_info);
+        }
+        Commands::Tables => {
+            for table in read_dictionary(&tablespace.idb_file_path).unwrap() {
+                print!("{}", table);
+            }
+        }
+        Commands::Tui => {
+            let mut navigator = Navigator::new(&tablespace).unwrap();
+            navigator.run().unwrap();
+        }
+        Commands::Diff { other } => {
+            let other = args.open(other.clone()).unwrap();
+            TableSpaceDiff::new(&tablespace, &other).print().unwrap();
+        }
+        Commands::Redo {
+            path,
+            space_id,
+            page,
+        } => {
+            let redo_log = RedoLog::open(Path::new(path)).unwrap();
+            print!("{}", redo_log);
+            let space_id =
+                space_id.unwrap_or_else(|| tablespace.page(0).unwrap().fil_header().space_id());
+            // 页LSN为最后一次修改该页的mtr的结束LSN，之后的日志还没有写入页
+            let page_lsn =
+                page.map(|page_num| tablespace.page(page_num).unwrap().fil_header().lsn());
+            if let Some(page_lsn) = page_lsn {
+                println!("page lsn:{}", page_lsn);
+            }
+            for record in redo_log.page_records(space_id, *page) {
+                let pending = page_lsn.is_some_and(|page_lsn| record.end_lsn > page_lsn);
+                println!("{}{}", if pending { "* " } else { "  " }, record);
+            }
+        }
+        Commands::Ibuf => {
+            let entries = tablespace.ibuf_bitmap().unwrap();
+            let mut buffered = 0;
+            for (page_num, entry) in &entries {
+                if !entry.buffered {
+                    continue;
+                }
+                buffered += 1;
+                // 有未合并修改的页上的二级索引记录不完整
+                match tablespace.page(*page_num).unwrap().index_page() {
+                    Some(page) => println!(
+                        "page_num:{} index:{} level:{} {}",
+                        page_num,
+                        page.index.name,
+                        page.index_header.level(),
+                        entry
+                    ),
+                    None => println!("page_num:{} {}", page_num, entry),
+                }
+            }
+            println!("pages:{} buffered:{}", entries.len(), buffered);
+        }
+        Commands::Rtree => {
+            let mut indexes: Vec<_> = tablespace
+                .table_info
+                .indexes
+                .values()
+                .filter(|index| index.is_spatial())
+                .collect();
+            indexes.sort_by_key(|index| index.index_id);
+            for index in indexes {
+                let (pages, issues) = tablespace.check_rtree(index).unwrap();
+                println!(
+                    "index:{} root_page_num:{} pages:{} issues:{}",
+                    index.name,
+                    index.root_page_num,
+                    pages,
+                    issues.len()
+                );
+                for issue in issues {
+                    println!("  {}", issue);
+                }
+            }
+        }
+        // 已在打开表空间之前处理
+        Commands::DblwrCheck { .. } => {}
     }
 }
 
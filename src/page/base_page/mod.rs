@@ -1,6 +1,6 @@
 use crate::file_header::FileHeader;
 use crate::file_trailer::FileTrailer;
-use crate::tablespace::table::{Index, TableInfo};
+use crate::tablespace::table::TableInfo;
 use bytes::Bytes;
 use std::fmt::{Display, Formatter};
 use std::ops::Deref;
@@ -15,13 +15,13 @@ pub struct BasePage<P> {
 pub struct UnKnowPage;
 
 impl Display for UnKnowPage {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, _f: &mut Formatter<'_>) -> std::fmt::Result {
         Ok(())
     }
 }
 
 impl InternalPage for UnKnowPage {
-    fn new(buf: Bytes, fil_header: &FileHeader<Bytes>) -> Self {
+    fn new(_buf: Bytes, _fil_header: &FileHeader<Bytes>) -> Self {
         UnKnowPage
     }
 }
@@ -50,20 +50,6 @@ impl<P: Display> Display for BasePage<P> {
     }
 }
 
-impl<P: Display> BasePage<P> {
-    pub fn new0(buf: Bytes, page: P) -> BasePage<P> {
-        let len = buf.len();
-        let fil_header = FileHeader::new(buf.slice(..38));
-        let fil_trailer = FileTrailer::new(buf.slice(len - 8..));
-        Self {
-            buf,
-            fil_header,
-            page,
-            fil_trailer,
-        }
-    }
-}
-
 impl<P: InternalPage> BasePage<P> {
     pub fn new(buf: Bytes) -> BasePage<P> {
         let len = buf.len();
@@ -0,0 +1,212 @@
+use crate::file_header::page_type::PageType;
+use bytes::Bytes;
+use std::fmt;
+use std::io;
+use std::io::Read;
+
+const FIL_PAGE_TYPE: usize = 24;
+const FIL_PAGE_VERSION: usize = 26;
+const FIL_PAGE_ORIGINAL_TYPE_V1: usize = 28;
+const FIL_PAGE_DATA: usize = 38;
+
+/// 透明页压缩(COMPRESSION='zlib'/'lz4')的压缩算法
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Zlib,
+    Lz4,
+    Unknown(u8),
+}
+
+impl From<u8> for Compression {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Compression::None,
+            1 => Compression::Zlib,
+            2 => Compression::Lz4,
+            x => Compression::Unknown(x),
+        }
+    }
+}
+
+/// FIL_PAGE_COMPRESSED 页在 File Header 中 FIL_PAGE_FILE_FLUSH_LSN 位置保存的压缩信息，
+/// 压缩后的数据从 FIL_PAGE_DATA 开始，之后的空间在文件中被打洞(punch hole)释放，读出来全为0
+///
+/// 名称 |长度|描述
+/// ------------------|:--------------------|:------------------
+/// FIL_PAGE_VERSION |1| 压缩格式的版本
+/// FIL_PAGE_ALGORITHM_V1 |1| 压缩算法，1为zlib，2为lz4
+/// FIL_PAGE_ORIGINAL_TYPE_V1 |2| 压缩前的页类型
+/// FIL_PAGE_ORIGINAL_SIZE_V1 |2| 压缩前 FIL_PAGE_DATA 之后的数据长度
+/// FIL_PAGE_COMPRESS_SIZE_V1 |2| 压缩后的数据长度
+pub struct CompressionHeader<B> {
+    buffer: B,
+}
+
+impl<B: AsRef<[u8]>> CompressionHeader<B> {
+    pub fn new(buffer: B) -> CompressionHeader<B> {
+        assert_eq!(
+            buffer.as_ref().len(),
+            8,
+            "CompressionHeader len {}!= 8",
+            buffer.as_ref().len()
+        );
+        Self { buffer }
+    }
+}
+
+impl<B: AsRef<[u8]>> CompressionHeader<B> {
+    pub fn version(&self) -> u8 {
+        self.buffer.as_ref()[0]
+    }
+    pub fn algorithm(&self) -> Compression {
+        Compression::from(self.buffer.as_ref()[1])
+    }
+    pub fn original_type(&self) -> PageType {
+        let tmp: [u8; 2] = self.buffer.as_ref()[2..4].try_into().unwrap();
+        PageType::from(u16::from_be_bytes(tmp))
+    }
+    pub fn original_size(&self) -> u16 {
+        let tmp: [u8; 2] = self.buffer.as_ref()[4..6].try_into().unwrap();
+        u16::from_be_bytes(tmp)
+    }
+    pub fn compressed_size(&self) -> u16 {
+        let tmp: [u8; 2] = self.buffer.as_ref()[6..8].try_into().unwrap();
+        u16::from_be_bytes(tmp)
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for CompressionHeader<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CompressionHeader")
+            .field("version", &self.version())
+            .field("algorithm", &self.algorithm())
+            .field("original_type", &self.original_type())
+            .field("original_size", &self.original_size())
+            .field("compressed_size", &self.compressed_size())
+            .finish()
+    }
+}
+
+/// Compression::deserialize：解压 FIL_PAGE_COMPRESSED 页，还原为完整的页并恢复原来的页类型。
+/// 其它类型的页原样返回
+pub fn decompress(buf: Bytes) -> io::Result<Bytes> {
+    let page_type = PageType::from(u16::from_be_bytes([
+        buf[FIL_PAGE_TYPE],
+        buf[FIL_PAGE_TYPE + 1],
+    ]));
+    if page_type != PageType::FilPageCompressed {
+        return Ok(buf);
+    }
+    let header = CompressionHeader::new(&buf[FIL_PAGE_VERSION..FIL_PAGE_DATA - 4]);
+    let original_size = header.original_size() as usize;
+    let compressed_size = header.compressed_size() as usize;
+    if FIL_PAGE_DATA + original_size != buf.len() || FIL_PAGE_DATA + compressed_size > buf.len() {
+        return Err(io::Error::other(format!(
+            "invalid compressed page, original size {} compressed size {}",
+            original_size, compressed_size
+        )));
+    }
+    let src = &buf[FIL_PAGE_DATA..FIL_PAGE_DATA + compressed_size];
+    let mut page = vec![0; buf.len()];
+    page[..FIL_PAGE_DATA].copy_from_slice(&buf[..FIL_PAGE_DATA]);
+    let dst = &mut page[FIL_PAGE_DATA..];
+    let len = match header.algorithm() {
+        Compression::Zlib => {
+            let mut decoder = flate2::read::ZlibDecoder::new(src);
+            let mut len = 0;
+            while len < dst.len() {
+                match decoder.read(&mut dst[len..])? {
+                    0 => break,
+                    n => len += n,
+                }
+            }
+            len
+        }
+        Compression::Lz4 => lz4_flex::block::decompress_into(src, dst)
+            .map_err(|e| io::Error::other(format!("lz4: {}", e)))?,
+        // 压缩后没有变小时不压缩
+        Compression::None => {
+            dst[..compressed_size].copy_from_slice(src);
+            compressed_size
+        }
+        Compression::Unknown(x) => {
+            return Err(io::Error::other(format!(
+                "unknown compression algorithm {}",
+                x
+            )))
+        }
+    };
+    if len != original_size {
+        return Err(io::Error::other(format!(
+            "decompressed {} bytes, expected {}",
+            len, original_size
+        )));
+    }
+    page[FIL_PAGE_TYPE..FIL_PAGE_TYPE + 2]
+        .copy_from_slice(&buf[FIL_PAGE_ORIGINAL_TYPE_V1..FIL_PAGE_ORIGINAL_TYPE_V1 + 2]);
+    Ok(Bytes::from(page))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const PAGE_SIZE: usize = 4096;
+
+    fn data() -> Vec<u8> {
+        (0..PAGE_SIZE - FIL_PAGE_DATA)
+            .map(|i| (i % 64 / 8) as u8)
+            .collect()
+    }
+
+    /// 按 Compression::serialize 的格式生成压缩页，压缩后的数据之后为打洞留下的0
+    fn compressed_page(algorithm: u8, compressed: &[u8]) -> Bytes {
+        let mut page = vec![0; PAGE_SIZE];
+        page[FIL_PAGE_TYPE..FIL_PAGE_TYPE + 2]
+            .copy_from_slice(&(PageType::FilPageCompressed as u16).to_be_bytes());
+        let mut header = vec![1, algorithm];
+        header.extend((PageType::FilPageIndex as u16).to_be_bytes());
+        header.extend(((PAGE_SIZE - FIL_PAGE_DATA) as u16).to_be_bytes());
+        header.extend((compressed.len() as u16).to_be_bytes());
+        page[FIL_PAGE_VERSION..FIL_PAGE_DATA - 4].copy_from_slice(&header);
+        page[FIL_PAGE_DATA..FIL_PAGE_DATA + compressed.len()].copy_from_slice(compressed);
+        Bytes::from(page)
+    }
+
+    fn assert_decompressed(page: Bytes) {
+        let header = CompressionHeader::new(&page[FIL_PAGE_VERSION..FIL_PAGE_DATA - 4]);
+        assert_eq!(header.original_type(), PageType::FilPageIndex);
+        let page = decompress(page).unwrap();
+        let page_type = u16::from_be_bytes([page[FIL_PAGE_TYPE], page[FIL_PAGE_TYPE + 1]]);
+        assert_eq!(page_type, PageType::FilPageIndex as u16);
+        assert_eq!(page[FIL_PAGE_DATA..], data());
+    }
+
+    #[test]
+    fn zlib_page() {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&data()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        assert!(compressed.len() < 100);
+        assert_decompressed(compressed_page(1, &compressed));
+    }
+
+    #[test]
+    fn lz4_page() {
+        let compressed = lz4_flex::block::compress(&data());
+        assert_decompressed(compressed_page(2, &compressed));
+    }
+
+    #[test]
+    fn invalid_page() {
+        let page = compressed_page(3, &[1, 2, 3]);
+        assert!(decompress(page).is_err_and(|e| e.to_string().contains("unknown compression")));
+        // 压缩数据被截断
+        let compressed = lz4_flex::block::compress(&data());
+        let page = compressed_page(2, &compressed[..compressed.len() / 2]);
+        assert!(decompress(page).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::InternalPage;
+use bytes::{Buf, Bytes};
+use console::style;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// 数据字典头所在的页号
+pub const DICT_HDR_PAGE_NO: u32 = 7;
+
+/// 系统表空间第7页，数据字典头(MySQL 5.7 及之前)
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// DICT_HDR_ROW_ID |8| 最近分配的隐藏主键 DB_ROW_ID
+/// DICT_HDR_TABLE_ID |8| 最近分配的表ID
+/// DICT_HDR_INDEX_ID |8| 最近分配的索引ID
+/// DICT_HDR_MAX_SPACE_ID |4| 最近分配的表空间ID
+/// DICT_HDR_MIX_ID_LOW |4| 未使用
+/// DICT_HDR_TABLES |4| SYS_TABLES 聚簇索引根页号
+/// DICT_HDR_TABLE_IDS |4| SYS_TABLES 中 ID 列二级索引根页号
+/// DICT_HDR_COLUMNS |4| SYS_COLUMNS 聚簇索引根页号
+/// DICT_HDR_INDEXES |4| SYS_INDEXES 聚簇索引根页号
+/// DICT_HDR_FIELDS |4| SYS_FIELDS 聚簇索引根页号
+#[derive(Debug)]
+pub struct DictHdrPage {
+    pub row_id: u64,
+    pub table_id: u64,
+    pub index_id: u64,
+    pub max_space_id: u32,
+    pub tables: u32,
+    pub table_ids: u32,
+    pub columns: u32,
+    pub indexes: u32,
+    pub fields: u32,
+}
+
+impl InternalPage for DictHdrPage {
+    fn new(buf: Bytes, _: &FileHeader<Bytes>) -> DictHdrPage {
+        Self {
+            row_id: buf.slice(..8).get_u64(),
+            table_id: buf.slice(8..16).get_u64(),
+            index_id: buf.slice(16..24).get_u64(),
+            max_space_id: buf.slice(24..28).get_u32(),
+            tables: buf.slice(32..36).get_u32(),
+            table_ids: buf.slice(36..40).get_u32(),
+            columns: buf.slice(40..44).get_u32(),
+            indexes: buf.slice(44..48).get_u32(),
+            fields: buf.slice(48..52).get_u32(),
+        }
+    }
+}
+
+impl Display for DictHdrPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("DictHeader").green())?;
+        writeln!(f, " row_id:{}", self.row_id)?;
+        writeln!(f, " table_id:{}", self.table_id)?;
+        writeln!(f, " index_id:{}", self.index_id)?;
+        writeln!(f, " max_space_id:{}", self.max_space_id)?;
+        writeln!(f, " SYS_TABLES root:{}", self.tables)?;
+        writeln!(f, " SYS_TABLE_IDS root:{}", self.table_ids)?;
+        writeln!(f, " SYS_COLUMNS root:{}", self.columns)?;
+        writeln!(f, " SYS_INDEXES root:{}", self.indexes)?;
+        writeln!(f, " SYS_FIELDS root:{}", self.fields)
+    }
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+/// 加密信息的长度(包含 server uuid)
+pub const ENCRYPTION_INFO_SIZE: usize = 111;
+const ENCRYPTION_MAGIC_SIZE: usize = 3;
+const ENCRYPTION_SERVER_UUID_LEN: usize = 36;
+/// 加密后的表空间密钥(32)和IV(32)
+const ENCRYPTION_KEY_INFO_LEN: usize = 64;
+
+/// 加密表空间第0页 XDES 数组之后的加密信息
+///
+/// 名称 |长度|描述
+/// ------------------|:--------------------|:------------------
+/// magic |3| lCA(5.7，没有 server uuid)、lCB、lCC
+/// master_key_id |4| 主密钥的ID
+/// server_uuid |36| 生成主密钥的实例的 server uuid，lCA 没有该字段
+/// key_info |64| 用主密钥 AES-256-ECB 加密的表空间密钥(32)和IV(32)
+/// checksum |4| 表空间密钥和IV明文的crc32
+pub struct EncryptionInfo<B> {
+    buffer: B,
+}
+
+impl<B: AsRef<[u8]>> EncryptionInfo<B> {
+    pub fn new(buffer: B) -> EncryptionInfo<B> {
+        assert_eq!(
+            buffer.as_ref().len(),
+            ENCRYPTION_INFO_SIZE,
+            "EncryptionInfo len {}!= {}",
+            buffer.as_ref().len(),
+            ENCRYPTION_INFO_SIZE
+        );
+        Self { buffer }
+    }
+}
+
+impl<B: AsRef<[u8]>> EncryptionInfo<B> {
+    pub fn magic(&self) -> &[u8] {
+        &self.buffer.as_ref()[..ENCRYPTION_MAGIC_SIZE]
+    }
+    /// 加密信息的版本，magic 不正确时为 None
+    pub fn version(&self) -> Option<u8> {
+        match self.magic() {
+            b"lCA" => Some(1),
+            b"lCB" => Some(2),
+            b"lCC" => Some(3),
+            _ => None,
+        }
+    }
+    pub fn master_key_id(&self) -> u32 {
+        let tmp: [u8; 4] = self.buffer.as_ref()[3..7].try_into().unwrap();
+        u32::from_be_bytes(tmp)
+    }
+    /// 版本1没有 server uuid
+    pub fn server_uuid(&self) -> Option<String> {
+        if self.version()? == 1 {
+            return None;
+        }
+        let uuid = &self.buffer.as_ref()[7..7 + ENCRYPTION_SERVER_UUID_LEN];
+        Some(String::from_utf8_lossy(uuid).to_string())
+    }
+    fn key_info_start(&self) -> usize {
+        match self.version() {
+            Some(1) => 7,
+            _ => 7 + ENCRYPTION_SERVER_UUID_LEN,
+        }
+    }
+    /// 加密后的表空间密钥和IV
+    pub fn key_info(&self) -> &[u8] {
+        let start = self.key_info_start();
+        &self.buffer.as_ref()[start..start + ENCRYPTION_KEY_INFO_LEN]
+    }
+    pub fn checksum(&self) -> u32 {
+        let start = self.key_info_start() + ENCRYPTION_KEY_INFO_LEN;
+        let tmp: [u8; 4] = self.buffer.as_ref()[start..start + 4].try_into().unwrap();
+        u32::from_be_bytes(tmp)
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for EncryptionInfo<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EncryptionInfo")
+            .field("magic", &String::from_utf8_lossy(self.magic()))
+            .field("master_key_id", &self.master_key_id())
+            .field("server_uuid", &self.server_uuid())
+            .field("checksum", &self.checksum())
+            .finish()
+    }
+}
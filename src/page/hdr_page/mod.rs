@@ -1,3 +1,4 @@
+pub mod encryption;
 pub mod header;
 pub mod page;
 pub mod x_des_entry;
@@ -3,15 +3,29 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 
 use crate::file_header::FileHeader;
-use crate::file_trailer::FileTrailer;
 use crate::page::base_page::InternalPage;
+use crate::page::hdr_page::encryption::{EncryptionInfo, ENCRYPTION_INFO_SIZE};
 use crate::page::hdr_page::header::HdrFspHeader;
 use crate::page::hdr_page::x_des_entry::XDesEntry;
 
-#[derive(Debug)]
+/// 加密信息在页体中的偏移
+const ENCRYPTION_INFO_OFFSET: usize = 112 + 40 * 256;
+
 pub struct FspHdrPage {
     pub fsp_header: HdrFspHeader<Bytes>,
     pub entry_list: Vec<XDesEntry<Bytes>>,
+    /// 加密表空间的加密信息
+    pub encryption: Option<EncryptionInfo<Bytes>>,
+}
+
+impl fmt::Debug for FspHdrPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FspHdrPage")
+            .field("fsp_header", &self.fsp_header)
+            .field("entry_list", &self.entry_list)
+            .field("encryption", &self.encryption)
+            .finish()
+    }
 }
 
 impl Display for FspHdrPage {
@@ -37,9 +51,16 @@ impl InternalPage for FspHdrPage {
         }
         // let empty_start = 112 + len as usize * 40;
         // let empty_space = buf.slice(empty_start..);
+        // 加密信息在 XDES 数组(256项)之后
+        let start = ENCRYPTION_INFO_OFFSET;
+        let encryption = Some(EncryptionInfo::new(
+            buf.slice(start..start + ENCRYPTION_INFO_SIZE),
+        ))
+        .filter(|info| info.version().is_some());
         Self {
             fsp_header,
             entry_list,
+            encryption,
         }
     }
 }
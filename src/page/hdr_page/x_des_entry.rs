@@ -27,10 +27,10 @@ impl<B: AsRef<[u8]>> XDesEntry<B> {
     pub fn flst_node(&self) -> ListNode {
         ListNode::new(&self.buffer.as_ref()[8..20])
     }
-    /// XDES_FREE	该 extent 在全局 free 链表上
-    /// XDES_FREE_FRAG	该 extent 在全局 free frag 链表上
-    /// XDES_FULL_FRAG	该 extent 在全局 full frag 链表上
-    /// XDES_FSEG	该 extent 不在任何全局链表上，而是分配给了第一个字段 XDES_ID 记录的值的 segment
+    /// XDES_FREE 该 extent 在全局 free 链表上
+    /// XDES_FREE_FRAG 该 extent 在全局 free frag 链表上
+    /// XDES_FULL_FRAG 该 extent 在全局 full frag 链表上
+    /// XDES_FSEG 该 extent 不在任何全局链表上，而是分配给了第一个字段 XDES_ID 记录的值的 segment
     pub fn state(&self) -> u32 {
         let tmp: [u8; 4] = self.buffer.as_ref()[20..24].try_into().unwrap();
         u32::from_be_bytes(tmp)
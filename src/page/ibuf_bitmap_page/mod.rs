@@ -0,0 +1,112 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::InternalPage;
+use bytes::Bytes;
+use console::style;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// 每个页在位图中占4位
+const IBUF_BITS_PER_PAGE: usize = 4;
+/// 一个位图页管理的页数，等于页大小
+pub const IBUF_BITMAP_PAGES: u32 = 16 * 1024;
+/// 空闲空间以 页大小/32 为单位
+const IBUF_PAGE_SIZE_PER_FREE_SPACE: usize = 32;
+
+/// 位图中一个页的标志
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// IBUF_BITMAP_FREE |2位| 页的空闲空间，0~3，以 页大小/32 为单位，3表示至少 3/32
+/// IBUF_BITMAP_BUFFERED |1位| 在 change buffer 中有未合并到该页的修改
+/// IBUF_BITMAP_IBUF |1位| 该页属于 change buffer 的B+树
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct IbufBitmapEntry {
+    pub free: u8,
+    pub buffered: bool,
+    pub ibuf: bool,
+}
+
+impl IbufBitmapEntry {
+    /// 空闲空间的下限(字节)
+    pub fn free_bytes(&self) -> usize {
+        self.free as usize * IBUF_BITMAP_PAGES as usize / IBUF_PAGE_SIZE_PER_FREE_SPACE
+    }
+}
+
+impl Display for IbufBitmapEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "free:{}(>={}B) buffered:{} ibuf:{}",
+            self.free,
+            self.free_bytes(),
+            self.buffered,
+            self.ibuf
+        )
+    }
+}
+
+/// change buffer 位图页，FIL_PAGE_IBUF_BITMAP。
+/// 每 16384 个页的第2页(页号 % 16384 == 1)，记录这 16384 个页的空闲空间和 change buffer 状态
+#[derive(Debug)]
+pub struct IbufBitmapPage {
+    /// 管理的第一个页的页号
+    pub first_page_num: u32,
+    bitmap: Bytes,
+}
+
+impl InternalPage for IbufBitmapPage {
+    fn new(buf: Bytes, fil_header: &FileHeader<Bytes>) -> IbufBitmapPage {
+        let len = IBUF_BITMAP_PAGES as usize * IBUF_BITS_PER_PAGE / 8;
+        Self {
+            first_page_num: fil_header.offset() / IBUF_BITMAP_PAGES * IBUF_BITMAP_PAGES,
+            bitmap: buf.slice(..len.min(buf.len())),
+        }
+    }
+}
+
+impl IbufBitmapPage {
+    /// ibuf_bitmap_page_get_bits：页号不在该位图页管理的范围内时返回 None
+    pub fn entry(&self, page_num: u32) -> Option<IbufBitmapEntry> {
+        let index = page_num.checked_sub(self.first_page_num)?;
+        if index >= IBUF_BITMAP_PAGES {
+            return None;
+        }
+        let bit_offset = index as usize * IBUF_BITS_PER_PAGE;
+        let byte = *self.bitmap.get(bit_offset / 8)?;
+        let bit = |n: usize| (byte >> (bit_offset % 8 + n)) & 1;
+        Some(IbufBitmapEntry {
+            free: bit(0) << 1 | bit(1),
+            buffered: bit(2) == 1,
+            ibuf: bit(3) == 1,
+        })
+    }
+    /// 有标志的页
+    pub fn entries(&self) -> impl Iterator<Item = (u32, IbufBitmapEntry)> + '_ {
+        (self.first_page_num..self.first_page_num + IBUF_BITMAP_PAGES).filter_map(|page_num| {
+            self.entry(page_num)
+                .filter(|entry| *entry != IbufBitmapEntry::default())
+                .map(|entry| (page_num, entry))
+        })
+    }
+}
+
+impl Display for IbufBitmapPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("IbufBitmap").green())?;
+        writeln!(
+            f,
+            " pages:{}..{}",
+            self.first_page_num,
+            self.first_page_num + IBUF_BITMAP_PAGES
+        )?;
+        let mut buffered = 0;
+        for (page_num, entry) in self.entries() {
+            if entry.buffered {
+                buffered += 1;
+            }
+            writeln!(f, " page_num:{} {}", page_num, entry)?;
+        }
+        writeln!(f, " buffered pages:{}", buffered)
+    }
+}
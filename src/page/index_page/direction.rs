@@ -1,4 +1,5 @@
 /// 插入方向的枚举
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 #[repr(u16)]
 pub enum Direction {
@@ -9,6 +10,10 @@ pub enum Direction {
 
 impl From<u16> for Direction {
     fn from(value: u16) -> Self {
-        unsafe { std::mem::transmute(value) }
+        match value {
+            0x0001 => Direction::Left,
+            0x0002 => Direction::Right,
+            _ => Direction::NoDirection,
+        }
     }
 }
@@ -7,6 +7,10 @@ pub enum RecordFormat {
 
 impl From<u8> for RecordFormat {
     fn from(value: u8) -> Self {
-        unsafe { std::mem::transmute(value) }
+        if value & 0b1 == 0 {
+            RecordFormat::Redundant
+        } else {
+            RecordFormat::Compact
+        }
     }
 }
@@ -1,6 +1,6 @@
+use console::style;
 use std::fmt;
 use std::fmt::Formatter;
-use console::style;
 
 use crate::page::index_page::direction::Direction;
 use crate::page::index_page::format_flag::RecordFormat;
@@ -10,16 +10,16 @@ use crate::page::FilePointer;
 /// ------------------|:--------------------|:------------------
 /// PAGE_N_DIR_SLOTS |2|在页目录中的槽数量
 /// PAGE_HEAP_TOP |2| 还未使用的空间最小地址，也就是说从该地址之后就是 Free Space
-/// PAGE_N_HEAP&FORMAT_FLAG	|2|	本页中的记录的数量（包括最小和最大记录以及标记为删除的记录），第15位是行模式，0:Redundant,1:COMPACT模式,
-/// PAGE_FREE	|2|	第一个已经标记为删除的记录地址（各个已删除的记录通过 next_record 也会组成一个单链表，这个单链表中的记录可以被重新利用）
-/// PAGE_GARBAGE |2|	已删除的字节数，行记录结构中delete_flag为1的记录大小总数
-/// PAGE_LAST_INSERT	|2|	最后插入记录的位置
-/// PAGE_DIRECTION	|2|	记录插入的方向 (新插入记录的主键值比上一条记录的主键值大，插入方向就是右边，反之则是左边) 取值为 0x02 右 0x01 左  0x05 无序
-/// PAGE_N_DIRECTION	|2|	一个方向连续插入的记录数量
-/// PAGE_N_RECS	|2|	该页中记录的数量（不包括最小和最大记录以及被标记为删除的记录）
-/// PAGE_MAX_TRX_ID	|8|	修改当前页的最大事务ID，该值仅在二级索引中定义
-/// PAGE_LEVEL	|2|	当前页在索引树中的位置，高度
-/// PAGE_INDEX_ID	|8|	索引ID，表示当前页属于哪个索引
+/// PAGE_N_HEAP&FORMAT_FLAG |2| 本页中的记录的数量（包括最小和最大记录以及标记为删除的记录），第15位是行模式，0:Redundant,1:COMPACT模式,
+/// PAGE_FREE |2| 第一个已经标记为删除的记录地址（各个已删除的记录通过 next_record 也会组成一个单链表，这个单链表中的记录可以被重新利用）
+/// PAGE_GARBAGE |2| 已删除的字节数，行记录结构中delete_flag为1的记录大小总数
+/// PAGE_LAST_INSERT |2| 最后插入记录的位置
+/// PAGE_DIRECTION |2| 记录插入的方向 (新插入记录的主键值比上一条记录的主键值大，插入方向就是右边，反之则是左边) 取值为 0x02 右 0x01 左  0x05 无序
+/// PAGE_N_DIRECTION |2| 一个方向连续插入的记录数量
+/// PAGE_N_RECS |2| 该页中记录的数量（不包括最小和最大记录以及被标记为删除的记录）
+/// PAGE_MAX_TRX_ID |8| 修改当前页的最大事务ID，该值仅在二级索引中定义
+/// PAGE_LEVEL |2| 当前页在索引树中的位置，高度
+/// PAGE_INDEX_ID |8| 索引ID，表示当前页属于哪个索引
 pub struct IndexHeader<B> {
     buffer: B,
 }
@@ -93,7 +93,7 @@ impl<B: AsRef<[u8]>> IndexHeader<B> {
 
 impl<B: AsRef<[u8]>> fmt::Display for IndexHeader<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}",style("IndexPageHeader").green())?;
+        writeln!(f, "{}", style("IndexPageHeader").green())?;
         writeln!(
             f,
             " slots:{} ({})",
@@ -110,7 +110,7 @@ impl<B: AsRef<[u8]>> fmt::Display for IndexHeader<B> {
             f,
             " format_flag:{:?} ({} >> 7)",
             self.format_flag(),
-            hex::encode(&[self.buffer.as_ref()[4]])
+            hex::encode([self.buffer.as_ref()[4]])
         )?;
         writeln!(
             f,
@@ -198,12 +198,12 @@ impl<B: AsRef<[u8]>> fmt::Debug for IndexHeader<B> {
 /// 仅在根节点定义
 /// 名称             |长度|描述
 /// ------------------|:--------------------|:------------------
-/// LEAF_SPACE_ID	|4|	叶子节点所在段的space id
-/// LEAF_PAGE_NUM	|4|	叶子节点所在段的Inode页号
-/// LEAF_OFFSET	|2|	叶子节点所在段内Inode页的偏移量
-/// INTERNAL_SPACE_ID	|4|	非叶子节点所在段的space id
-/// INTERNAL_PAGE_NUM	|4|	非叶子节点所在段的Inode页号
-/// INTERNAL_OFFSET	|2|	非叶子节点所在段内Inode页的偏移量
+/// LEAF_SPACE_ID |4| 叶子节点所在段的space id
+/// LEAF_PAGE_NUM |4| 叶子节点所在段的Inode页号
+/// LEAF_OFFSET |2| 叶子节点所在段内Inode页的偏移量
+/// INTERNAL_SPACE_ID |4| 非叶子节点所在段的space id
+/// INTERNAL_PAGE_NUM |4| 非叶子节点所在段的Inode页号
+/// INTERNAL_OFFSET |2| 非叶子节点所在段内Inode页的偏移量
 pub struct FSegHeader<B> {
     buffer: B,
 }
@@ -237,7 +237,7 @@ impl<B: AsRef<[u8]>> FSegHeader<B> {
 
 impl<B: AsRef<[u8]>> fmt::Display for FSegHeader<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "{}",style("FSegHeader").green())?;
+        writeln!(f, "{}", style("FSegHeader").green())?;
         writeln!(
             f,
             " leaf_space_id:{} ({})",
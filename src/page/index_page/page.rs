@@ -1,24 +1,26 @@
 use crate::file_header::FileHeader;
-use crate::file_trailer::FileTrailer;
 use crate::page::base_page::InternalIndexPage;
+use crate::page::index_page::format_flag::RecordFormat;
 use crate::page::index_page::header::{FSegHeader, IndexHeader};
-use crate::page::index_page::records::{new, Row};
+use crate::page::index_page::records::new::row::ColInfo;
+use crate::page::index_page::records::{new, old, Row};
 use crate::tablespace::data_type::DataType;
-use crate::tablespace::table::{Index, TableInfo};
+use crate::tablespace::table::{Column, Index, TableInfo};
 use bytes::{Buf, Bytes};
+use console::style;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use console::style;
 
 #[derive(Debug)]
 pub struct IndexPage {
     pub index: Index,
     pub index_header: IndexHeader<Bytes>,
+    /// 同一层的下一页，FIL_NULL 表示最右边的页
+    pub next_page: u32,
     pub f_seg_header: FSegHeader<Bytes>,
     pub infimum: Row<Bytes>,
     pub supremum: Row<Bytes>,
     pub user_records: Vec<Row<Bytes>>,
-    pub free_space: Bytes,
     pub page_directory: Vec<u16>,
 }
 
@@ -26,73 +28,61 @@ impl Display for IndexPage {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.index_header)?;
         write!(f, "{}", self.f_seg_header)?;
-        writeln!(f, "{}",style("row:").green())?;
+        writeln!(f, "{}", style("row:").green())?;
         writeln!(f, "     var&null / not_used / delete_mask / min_rec_mask / n_owned / heap_no / rec_type / next / value")?;
         writeln!(f, " infimum : {}", self.infimum)?;
         writeln!(f, " supremum: {}", self.supremum)?;
-        writeln!(f, "{}",style(" user_records:").green())?;
+        writeln!(f, "{}", style(" user_records:").green())?;
 
         write!(f, "var&null / not_used / delete_mask / min_rec_mask / n_owned / heap_no / rec_type / next /")?;
-        if self.index_header.level() == 0 {
-            for col in &self.index.elements {
-                write!(f, " {} /", style(&col.name).yellow())?;
-            }
-        } else {
-            for col in &self.index.indexes {
-                write!(f, " {} /", style(&col.name).yellow())?;
-            }
+        for col in self.columns() {
+            write!(f, " {} /", style(&col.name).yellow())?;
         }
-        writeln!(f, "")?;
+        writeln!(f)?;
         for row in &self.user_records {
             writeln!(f, "  {}", row)?;
         }
+        if self.index_header.level() != 0 && !self.columns().is_empty() {
+            writeln!(f, "{}", style(" node_pointers:").green())?;
+            for (i, row) in self.user_records.iter().enumerate() {
+                let Some(child) = self.child_page_num(row) else {
+                    continue;
+                };
+                let low = if row.min_rec_mask() != 0 {
+                    String::from("-∞")
+                } else {
+                    self.node_key(row)
+                };
+                // 页中最后一条记录的上界是右兄弟页的第一个键，只有最右边的页才是 +∞
+                let high = match self.user_records.get(i + 1) {
+                    Some(next) => self.node_key(next),
+                    None if self.next_page == u32::MAX => String::from("+∞"),
+                    None => format!("first key of page {}", self.next_page),
+                };
+                writeln!(f, "  child_page:{} key:[{}, {})", child, low, high)?;
+            }
+        }
         writeln!(f, "page_directory: {:?}", self.page_directory)?;
         Ok(())
     }
 }
 
 impl IndexPage {
-    pub fn new0(buf: Bytes, index: Index) -> IndexPage {
+    pub fn new0(buf: Bytes, fil_header: &FileHeader<Bytes>, index: Index) -> IndexPage {
         let index_header = IndexHeader::new(buf.slice(..36));
         let f_seg_header = FSegHeader::new(buf.slice(36..56));
-        let infimum = new::row::Row::new(
-            vec![(DataType::Char(8), 0, 8, false, false)],
-            5,
-            buf.slice(56..56 + 13),
-        );
-        let supremum = new::row::Row::new(
-            vec![(DataType::Char(8), 0, 8, false, false)],
-            5,
-            buf.slice(56 + 13..56 + 26),
-        );
-        let num = index_header.heap_num() as usize - 2;
-        let mut user_records = Vec::with_capacity(num);
-        let mut next = 56 + 5 + infimum.header().next_record();
-        let infimum = Row::New(infimum);
-        let supremum = Row::New(supremum);
         let columns = if index_header.level() != 0 {
             &index.indexes
         } else {
             &index.elements
         };
-        let mut end = 56 + 26;
-        for index in 0..num {
-            let (start, col_info) = new::row::Row::parse_row_prefix(columns, next as usize, &buf);
-            let mut record_len: usize =
-                col_info.iter().map(|(_, _, len, _, _)| *len as usize).sum();
-            end = (next + record_len as i16) as usize;
-            let row = new::row::Row::new(col_info, next as usize - start, buf.slice(start..end));
-
-            next += row.header().next_record();
-
-            user_records.push(Row::New(row));
-            if next == 56 + 13 + 5 {
-                assert_eq!(index, num - 1, "not last {},{}", next, index);
-            }
-        }
+        // 已删除的记录在空闲链表中，记录链表中只有 PAGE_N_RECS 条记录
+        let num = index_header.recs() as usize;
+        let (infimum, supremum, user_records) = match index_header.format_flag() {
+            RecordFormat::Compact => IndexPage::compact_records(&buf, columns, num),
+            RecordFormat::Redundant => IndexPage::redundant_records(&buf, columns, num),
+        };
         let slots = index_header.slots() as usize;
-        let empty_end = buf.len() - slots * 2;
-        let free_space = buf.slice(end..empty_end);
         let buf_len = buf.len();
         let mut page_directory = Vec::new();
         for slot in 0..slots {
@@ -104,21 +94,122 @@ impl IndexPage {
         Self {
             index,
             index_header,
+            next_page: fil_header.next(),
             f_seg_header,
             infimum,
             supremum,
             user_records,
-            free_space,
             page_directory,
         }
     }
 }
 
+impl IndexPage {
+    /// COMPACT 行格式，next_record 为相对下一条记录的偏移
+    fn compact_records(
+        buf: &Bytes,
+        columns: &[Column],
+        num: usize,
+    ) -> (Row<Bytes>, Row<Bytes>, Vec<Row<Bytes>>) {
+        let infimum = new::row::Row::new(
+            vec![ColInfo::new(DataType::Char(8), 8, None)],
+            5,
+            buf.slice(56..56 + 13),
+        );
+        let supremum = new::row::Row::new(
+            vec![ColInfo::new(DataType::Char(8), 8, None)],
+            5,
+            buf.slice(56 + 13..56 + 26),
+        );
+        let mut user_records = Vec::with_capacity(num);
+        let mut next = 56 + 5 + infimum.header().next_record();
+        for index in 0..num {
+            let (start, col_info, defaults) =
+                new::row::Row::parse_row_prefix(columns, next as usize, buf);
+            let record_len: usize = col_info.iter().map(|x| x.len as usize).sum();
+            let end = (next + record_len as i16) as usize;
+            let row = new::row::Row::new(col_info, next as usize - start, buf.slice(start..end))
+                .with_defaults(defaults);
+
+            next += row.header().next_record();
+
+            user_records.push(Row::New(row));
+            if next == 56 + 13 + 5 {
+                assert_eq!(index, num - 1, "not last {},{}", next, index);
+            }
+        }
+        (Row::New(infimum), Row::New(supremum), user_records)
+    }
+    /// REDUNDANT 行格式，next_record 为下一条记录在页中的绝对位置
+    fn redundant_records(
+        buf: &Bytes,
+        columns: &[Column],
+        num: usize,
+    ) -> (Row<Bytes>, Row<Bytes>, Vec<Row<Bytes>>) {
+        // 页内偏移减去 File Header 长度即为 buf 中的偏移
+        const FIL_HEADER_LEN: usize = 38;
+        const SUPREMUM: usize = 116 - FIL_HEADER_LEN;
+        let infimum = old::row::Row::new(
+            vec![ColInfo::new(DataType::Char(8), 8, None)],
+            7,
+            buf.slice(56..56 + 15),
+        );
+        let supremum = old::row::Row::new(
+            vec![ColInfo::new(DataType::Char(9), 9, None)],
+            7,
+            buf.slice(56 + 15..56 + 31),
+        );
+        let mut user_records = Vec::with_capacity(num);
+        let mut next = (infimum.header().next_record() as usize).saturating_sub(FIL_HEADER_LEN);
+        while next != SUPREMUM && next > 56 && next < buf.len() && user_records.len() < num {
+            let (start, col_info) = old::row::Row::parse_row_prefix(columns, next, buf);
+            let record_len: usize = col_info.iter().map(|x| x.len as usize).sum();
+            let end = next + record_len;
+            let row = old::row::Row::new(col_info, next - start, buf.slice(start..end));
+            next = (row.header().next_record() as usize).saturating_sub(FIL_HEADER_LEN);
+            user_records.push(Row::Old(row));
+        }
+        (Row::Old(infimum), Row::Old(supremum), user_records)
+    }
+}
+
+impl IndexPage {
+    /// 记录中的列，叶子节点为全部列，非叶子节点为索引列(二级索引还有主键列)和子页号
+    pub fn columns(&self) -> &Vec<Column> {
+        if self.index_header.level() != 0 {
+            &self.index.indexes
+        } else {
+            &self.index.elements
+        }
+    }
+    /// 非叶子节点记录中子页号之前的键值
+    fn node_key(&self, row: &Row<Bytes>) -> String {
+        let n = row.col_info().len().saturating_sub(1);
+        let values: Vec<String> = (0..n).map(|i| row.value(i)).collect();
+        format!("({})", values.join(", "))
+    }
+    /// 非叶子节点记录指向的子页号
+    pub fn child_page_num(&self, row: &Row<Bytes>) -> Option<u32> {
+        if self.index_header.level() == 0 {
+            return None;
+        }
+        let (data, _) = row.col(row.col_info().len().checked_sub(1)?);
+        let tmp: [u8; 4] = data.try_into().ok()?;
+        Some(u32::from_be_bytes(tmp))
+    }
+}
+
 impl InternalIndexPage for IndexPage {
-    fn new(buf: Bytes, _: &FileHeader<Bytes>, table_info: &TableInfo) -> IndexPage {
+    fn new(buf: Bytes, fil_header: &FileHeader<Bytes>, table_info: &TableInfo) -> IndexPage {
         let index_header = IndexHeader::new(buf.slice(..36));
-        let index = table_info.indexes.get(&index_header.index_id()).unwrap();
-        IndexPage::new0(buf, index.clone())
+        let index_id = index_header.index_id();
+        // 表结构中没有的索引(如系统表空间中其他表的索引)按没有列处理，只解析记录头
+        let index = table_info
+            .indexes
+            .get(&index_id)
+            .cloned()
+            .unwrap_or_else(|| Index::unknown(index_id));
+        IndexPage::new0(buf, fil_header, index)
     }
 }
 
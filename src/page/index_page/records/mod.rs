@@ -1,22 +1,122 @@
+use crate::page::index_page::records::new::row::ColInfo;
+use crate::page::OverflowPointer;
+use crate::tablespace::charset::format_str;
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::data_value::{format_decimal, format_elements};
+use crate::tablespace::geometry::format_geometry;
+use crate::tablespace::json::format_json;
+use crate::tablespace::temporal::format_temporal;
+use std::fmt;
 use std::fmt::Formatter;
-use std::{fmt, io};
+use std::io;
 
 pub mod new;
+pub mod old;
 
 pub enum Row<B> {
+    /// COMPACT、DYNAMIC、COMPRESSED 行格式
     New(new::row::Row<B>),
+    /// REDUNDANT 行格式
+    Old(old::row::Row<B>),
+}
+
+impl<B: AsRef<[u8]>> Row<B> {
+    pub fn delete_mask(&self) -> u8 {
+        match self {
+            Row::New(row) => row.header().delete_mask(),
+            Row::Old(row) => row.header().delete_mask(),
+        }
+    }
+    pub fn min_rec_mask(&self) -> u8 {
+        match self {
+            Row::New(row) => row.header().min_rec_mask(),
+            Row::Old(row) => row.header().min_rec_mask(),
+        }
+    }
+    pub fn heap_no(&self) -> u16 {
+        match self {
+            Row::New(row) => row.header().heap_no(),
+            Row::Old(row) => row.header().heap_no(),
+        }
+    }
+    pub fn col(&self, index: usize) -> (&[u8], Option<OverflowPointer<&[u8]>>) {
+        match self {
+            Row::New(row) => row.col(index),
+            Row::Old(row) => row.col(index),
+        }
+    }
+    pub fn col_info(&self) -> &Vec<ColInfo> {
+        match self {
+            Row::New(row) => row.col_info(),
+            Row::Old(row) => row.col_info(),
+        }
+    }
+    pub fn is_null(&self, index: usize) -> bool {
+        match self {
+            Row::New(row) => row.is_null(index),
+            Row::Old(row) => row.is_null(index),
+        }
+    }
+    /// 列值的文本形式，见 format_value
+    pub fn value(&self, index: usize) -> String {
+        match self {
+            Row::New(row) => row.value(index),
+            Row::Old(row) => row.value(index),
+        }
+    }
+    /// 记录的原始数据，包括记录头之前的变长字段长度列表和空值列表
+    pub fn buf(&self) -> &[u8] {
+        match self {
+            Row::New(row) => row.buf(),
+            Row::Old(row) => row.buf(),
+        }
+    }
+}
+
+/// 列值的文本形式，页内容、TUI 和 diff 都用它显示列值：
+/// NULL 显示为 NULL；空间类型、DECIMAL、JSON 按格式解析；ENUM、SET、BIT 显示成员或位串；
+/// 字符串按字符集解码后加引号；时间类型显示为 ISO 格式；其余显示为十六进制，
+/// 没有字符集的字符串逐字节显示。解析失败时显示十六进制和错误。
+/// overflow 为 true 时 data 只是页内前缀，不解析其内容；没有读取溢出页(LOB)的实现，
+/// 存储在溢出页中的 JSON 不解码
+pub fn format_value(info: &ColInfo, data: &[u8], overflow: bool) -> String {
+    let data_type = info.data_type;
+    let or_hex = |value: io::Result<String>| {
+        value.unwrap_or_else(|e| format!("{} ({})", hex::encode(data), e))
+    };
+    if info.is_null {
+        String::from("NULL")
+    } else if overflow && data_type == DataType::Json {
+        String::from("(json stored off-page, not decoded)")
+    } else if let Some(value) = format_geometry(data_type, data)
+        .or_else(|| format_decimal(data_type, data))
+        .or_else(|| format_json(data_type, data))
+        .filter(|_| !overflow)
+    {
+        or_hex(value)
+    } else if let Some(value) = format_elements(data_type, &info.elements, data) {
+        value
+    } else if let Some(value) = format_str(data_type, info.charset.filter(|_| !overflow), data) {
+        or_hex(value.map(|x| format!("{:?}", x)))
+    } else if let Some(value) = format_temporal(data_type, info.time_zone, data) {
+        or_hex(value)
+    } else if data_type.is_str() {
+        let bytes: Vec<String> = data.iter().map(|x| format!("{:02x}", x)).collect();
+        format!("[{}]", bytes.join(" "))
+    } else {
+        hex::encode(data)
+    }
 }
 
 impl<B: AsRef<[u8]>> fmt::Display for Row<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Row::New(row) => {
-                writeln!(f, "{}", row)?;
-                write!(f, " raw data: ")?;
-                for x in row.buf() {
-                    write!(f, "{:02x} ", x)?;
-                }
-            }
+            Row::New(row) => writeln!(f, "{}", row)?,
+            Row::Old(row) => writeln!(f, "{}", row)?,
+        }
+        write!(f, " raw data: ")?;
+        for x in self.buf() {
+            write!(f, "{:02x} ", x)?;
         }
         Ok(())
     }
@@ -26,6 +126,7 @@ impl<B: AsRef<[u8]>> fmt::Debug for Row<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             Row::New(row) => row.fmt(f),
+            Row::Old(row) => row.fmt(f),
         }
     }
 }
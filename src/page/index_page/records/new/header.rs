@@ -3,16 +3,16 @@ use std::fmt::Formatter;
 
 pub const NEW_HEAD_LEN: usize = 5;
 
-/// 名称	|大小（单位：bit）|	描述
+/// 名称 |大小（单位：bit）| 描述
 /// ------|:------|:------
-/// 预留位1	|1|	没有使用
-/// 预留位2	|1|	没有使用
-/// delete_mask	|1|	标记该记录是否被删除 1表示已删除
-/// min_rec_mask	|1|	标记该记录是否为B+树的非叶子节点中的最小记录
-/// n_owned	|4|	表示当前槽管理的记录数
-/// heap_no	|13|	表示当前记录在记录堆的位置信息
-/// record_type	|3|	表示当前记录的类型，0表示普通记录，1表示B+树非叶节点记录，2表示最小记录，3表示最大记录
-/// next_record	|16|	表示下一条记录的相对位置
+/// instant_flag |1| 8.0.12 起 INSTANT 加列后插入的记录，记录头之前存有字段数量
+/// version_flag |1| 8.0.29 起 INSTANT 加列/删列后插入的记录，记录头之前存有1字节的行版本
+/// delete_mask |1| 标记该记录是否被删除 1表示已删除
+/// min_rec_mask |1| 标记该记录是否为B+树的非叶子节点中的最小记录
+/// n_owned |4| 表示当前槽管理的记录数
+/// heap_no |13| 表示当前记录在记录堆的位置信息
+/// record_type |3| 表示当前记录的类型，0表示普通记录，1表示B+树非叶节点记录，2表示最小记录，3表示最大记录
+/// next_record |16| 表示下一条记录的相对位置
 pub struct RecordHeader<B> {
     buffer: B,
 }
@@ -31,13 +31,15 @@ impl<B: AsRef<[u8]>> RecordHeader<B> {
 }
 
 impl<B: AsRef<[u8]>> RecordHeader<B> {
-    pub fn buf(&self) -> &[u8] {
-        self.buffer.as_ref()
-    }
-
     pub fn not_used(&self) -> u8 {
         self.buffer.as_ref()[0] >> 6
     }
+    pub fn instant_flag(&self) -> bool {
+        self.buffer.as_ref()[0] & 0x80 != 0
+    }
+    pub fn version_flag(&self) -> bool {
+        self.buffer.as_ref()[0] & 0x40 != 0
+    }
     pub fn delete_mask(&self) -> u8 {
         (self.buffer.as_ref()[0] >> 5) & 0b1
     }
@@ -61,12 +63,12 @@ impl<B: AsRef<[u8]>> RecordHeader<B> {
 }
 impl<B: AsRef<[u8]>> fmt::Display for RecordHeader<B> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}>>6 /", hex::encode(&[self.buffer.as_ref()[0]]))?;
-        write!(f, " ({}>>5)&0b1 /", hex::encode(&[self.buffer.as_ref()[0]]))?;
-        write!(f, " ({}>>4)&0b1 /", hex::encode(&[self.buffer.as_ref()[0]]))?;
-        write!(f, " {}&0x0F /", hex::encode(&[self.buffer.as_ref()[0]]))?;
+        write!(f, "{}>>6 /", hex::encode([self.buffer.as_ref()[0]]))?;
+        write!(f, " ({}>>5)&0b1 /", hex::encode([self.buffer.as_ref()[0]]))?;
+        write!(f, " ({}>>4)&0b1 /", hex::encode([self.buffer.as_ref()[0]]))?;
+        write!(f, " {}&0x0F /", hex::encode([self.buffer.as_ref()[0]]))?;
         write!(f, " {}>>3 /", hex::encode(&self.buffer.as_ref()[1..3]))?;
-        write!(f, " {}&0x07 /", hex::encode(&[self.buffer.as_ref()[2]]))?;
+        write!(f, " {}&0x07 /", hex::encode([self.buffer.as_ref()[2]]))?;
         write!(f, " {} /", hex::encode(&self.buffer.as_ref()[3..5]))
     }
 }
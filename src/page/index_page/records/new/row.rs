@@ -1,91 +1,159 @@
+use crate::page::index_page::records::format_value;
 use crate::page::index_page::records::new::header::{RecordHeader, NEW_HEAD_LEN};
-use crate::page::{ListNode, OverflowPointer};
+use crate::page::OverflowPointer;
+use crate::tablespace::charset::Charset;
 use crate::tablespace::data_type::DataType;
-use crate::tablespace::table::{Column, Index};
+use crate::tablespace::table::{Column, InstantDefault};
 use std::fmt;
 use std::fmt::Formatter;
-use std::io::Read;
+use std::rc::Rc;
+
+/// 记录中一列的位置和属性
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColInfo {
+    pub data_type: DataType,
+    /// 相对记录数据开始处的偏移量
+    pub offset: u16,
+    /// 占用的字节数，溢出列包括20字节的溢出页指针
+    pub len: u16,
+    pub is_overflow: bool,
+    pub is_null: bool,
+    pub charset: Option<Charset>,
+    pub time_zone: Option<i32>,
+    /// ENUM、SET 的成员
+    pub elements: Rc<[String]>,
+}
+
+impl ColInfo {
+    pub fn new(data_type: DataType, len: u16, charset: Option<Charset>) -> ColInfo {
+        Self {
+            data_type,
+            offset: 0,
+            len,
+            is_overflow: false,
+            is_null: false,
+            charset,
+            time_zone: None,
+            elements: Rc::from([]),
+        }
+    }
+    pub fn null(data_type: DataType, charset: Option<Charset>) -> ColInfo {
+        Self {
+            is_null: true,
+            ..Self::new(data_type, 0, charset)
+        }
+    }
+}
+
+/// 记录中不存在、使用 INSTANT 默认值的列，(列下标,值)
+pub type InstantValues = Vec<(usize, Vec<u8>)>;
 
 pub struct Row<B> {
-    col_info: Vec<(DataType, u16, u16, bool, bool)>,
+    col_info: Vec<ColInfo>,
+    defaults: InstantValues,
     rec_offset: usize,
     buffer: B,
 }
 
 impl<B: AsRef<[u8]>> Row<B> {
-    pub fn new(
-        col_info: Vec<(DataType, u16, u16, bool, bool)>,
-        rec_offset: usize,
-        buffer: B,
-    ) -> Row<B> {
+    pub fn new(col_info: Vec<ColInfo>, rec_offset: usize, buffer: B) -> Row<B> {
         Self {
             col_info,
+            defaults: Vec::new(),
             rec_offset,
             buffer,
         }
     }
+    /// INSTANT 添加的列在记录中不存在时使用的默认值
+    pub fn with_defaults(mut self, defaults: InstantValues) -> Row<B> {
+        self.defaults = defaults;
+        self
+    }
+    /// 解析空值列表和变长列表，返回记录开始处、列信息和 INSTANT 添加的列的默认值。
+    /// 记录头中有 version_flag 时，记录头之前为1字节的行版本；有 instant_flag 时为1~2字节的字段数量。
+    /// 记录中不存在的列长度为0
     pub fn parse_row_prefix(
-        columns: &Vec<Column>,
+        columns: &[Column],
         mut rec_offset: usize,
         buffer: B,
-    ) -> (usize, Vec<(DataType, u16, u16, bool, bool)>) {
-        //计算列信息，(类型,偏移量,长度,是否溢出,是否为空)  注意长度为0，值不为空的情况(空字符串)
-        let mut col_info: Vec<(DataType, u16, u16, bool, bool)> = Vec::with_capacity(columns.len());
+    ) -> (usize, Vec<ColInfo>, InstantValues) {
+        // 注意长度为0，值不为空的情况(空字符串)
+        let mut col_info: Vec<ColInfo> = Vec::with_capacity(columns.len());
+        let mut defaults = Vec::new();
         let buf = buffer.as_ref();
+        let header = RecordHeader::new(&buf[rec_offset - NEW_HEAD_LEN..rec_offset]);
         rec_offset -= NEW_HEAD_LEN;
+        // 记录中存有的列
+        let present: Vec<bool> = if header.version_flag() {
+            let version = buf[rec_offset - 1];
+            rec_offset -= 1;
+            columns.iter().map(|x| x.in_version(version)).collect()
+        } else if header.instant_flag() {
+            let val = buf[rec_offset - 1] as usize;
+            rec_offset -= 1;
+            let n_fields = if val & 0x80 == 0 {
+                val
+            } else {
+                rec_offset -= 1;
+                ((val & 0x7F) << 8) | buf[rec_offset] as usize
+            };
+            (0..columns.len()).map(|i| i < n_fields).collect()
+        } else {
+            columns.iter().map(|x| x.in_core()).collect()
+        };
         let mut null_num = 0;
         //处理空值列表 (data_type, is_nullable)
-        for column in columns {
+        for (index, (column, &present)) in columns.iter().zip(&present).enumerate() {
             let data_type = column.data_type;
             let is_nullable = column.is_nullable;
-            if is_nullable {
+            let charset = column.charset;
+            if !present {
+                match &column.instant_default {
+                    Some(InstantDefault::Value(value)) => {
+                        defaults.push((index, value.clone()));
+                        col_info.push(ColInfo::new(data_type, 0, charset));
+                    }
+                    _ => col_info.push(ColInfo::null(data_type, charset)),
+                }
+            } else if is_nullable {
                 let val = buf[rec_offset - null_num / 8 - 1];
                 if (val >> (null_num % 8)) & 0b1 == 0b1 {
-                    col_info.push((data_type, 0, 0, false, true));
+                    col_info.push(ColInfo::null(data_type, charset));
                 } else {
-                    col_info.push((data_type, 0, data_type.len() as u16, false, false));
+                    col_info.push(ColInfo::new(data_type, column.fixed_len() as u16, charset));
                 }
                 null_num += 1;
             } else {
-                col_info.push((data_type, 0, data_type.len() as u16, false, false));
+                col_info.push(ColInfo::new(data_type, column.fixed_len() as u16, charset));
             }
         }
         rec_offset -= null_num / 8;
         if null_num % 8 != 0 {
             rec_offset -= 1;
         }
-        // 处理变长列表
-        for (data_type, _, len, is_overflow, is_null) in col_info.iter_mut() {
-            match *data_type {
-                DataType::Varchar
-                | DataType::Tinytext
-                | DataType::Mediumtext
-                | DataType::Longtext
-                | DataType::Text => {
-                    if !*is_null {
-                        rec_offset -= 1;
-                        let val = buf[rec_offset] as u16;
-                        if val & 0x80 == 0 {
-                            *len = val;
-                        } else {
-                            rec_offset -= 1;
-                            *len = buf[rec_offset] as u16 + ((val & 0x3f) << 8);
-                            // 两个字节
-                            if val & 0x40 == 0x40 {
-                                *is_overflow = true;
-                            }
-                        }
-                    }
+        // 处理变长列表，最大长度不超过255字节的列长度只占1字节，
+        // 否则小于128的长度占1字节，其余占2字节：最高位为1，次高位为溢出标记
+        for ((info, &present), column) in col_info.iter_mut().zip(&present).zip(columns) {
+            info.time_zone = column.time_zone;
+            info.elements = column.elements.clone();
+            if present && column.is_var() && !info.is_null {
+                rec_offset -= 1;
+                let val = buf[rec_offset] as u16;
+                if info.data_type.is_big() && val & 0x80 != 0 {
+                    rec_offset -= 1;
+                    info.len = buf[rec_offset] as u16 + ((val & 0x3f) << 8);
+                    info.is_overflow = val & 0x40 == 0x40;
+                } else {
+                    info.len = val;
                 }
-                _ => {}
             }
         }
         let mut last_offset = 0;
-        for (_, offset, len, _, _) in col_info.iter_mut() {
-            *offset = last_offset;
-            last_offset += *len;
+        for info in col_info.iter_mut() {
+            info.offset = last_offset;
+            last_offset += info.len;
         }
-        (rec_offset, col_info)
+        (rec_offset, col_info, defaults)
     }
 }
 
@@ -103,17 +171,20 @@ impl<B: AsRef<[u8]>> Row<B> {
         self.buffer.as_ref()
     }
     pub fn col(&self, index: usize) -> (&[u8], Option<OverflowPointer<&[u8]>>) {
-        let (_, offset, len, is_overflow, _) = self.col_info[index];
-        let data = &self.data()[offset as usize..offset as usize + len as usize];
-        if is_overflow {
-            let start = (len & 0xFF00) as usize;
-            (&data[..start], Some(OverflowPointer::new(&data[start..])))
-        } else {
-            (data, None)
-        }
+        let (_, data, ptr) = self.col_type(index);
+        (data, ptr)
     }
     pub fn col_type(&self, index: usize) -> (DataType, &[u8], Option<OverflowPointer<&[u8]>>) {
-        let (data_type, offset, len, is_overflow, _) = self.col_info[index];
+        let ColInfo {
+            data_type,
+            offset,
+            len,
+            is_overflow,
+            ..
+        } = self.col_info[index];
+        if let Some((_, value)) = self.defaults.iter().find(|(i, _)| *i == index) {
+            return (data_type, value, None);
+        }
         let data = &self.data()[offset as usize..offset as usize + len as usize];
         if is_overflow {
             let start = (len & 0xFF00) as usize;
@@ -127,11 +198,16 @@ impl<B: AsRef<[u8]>> Row<B> {
         }
     }
 
-    pub fn col_info(&self) -> &Vec<(DataType, u16, u16, bool, bool)> {
-        &self.col_info
+    pub fn is_null(&self, index: usize) -> bool {
+        self.col_info[index].is_null
     }
-    pub fn size(&self) -> usize {
-        self.buffer.as_ref().len()
+    /// 列值的文本形式
+    pub fn value(&self, index: usize) -> String {
+        let (data, ptr) = self.col(index);
+        format_value(&self.col_info[index], data, ptr.is_some())
+    }
+    pub fn col_info(&self) -> &Vec<ColInfo> {
+        &self.col_info
     }
 }
 
@@ -140,17 +216,8 @@ impl<B: AsRef<[u8]>> fmt::Display for Row<B> {
         write!(f, "{:?} ", &self.buf()[..self.rec_offset - 5])?;
         write!(f, "{} ", self.header())?;
         for index in 0..self.col_info().len() {
-            let (data_type, col, ptr) = self.col_type(index);
-            if data_type.is_str() {
-                write!(f, " [")?;
-                for x in col {
-                    write!(f, " {:02x}", x)?;
-                }
-                write!(f, "]")?;
-            } else {
-                write!(f, "{} ", hex::encode(col))?;
-            }
-
+            let (_, ptr) = self.col(index);
+            write!(f, "{} ", self.value(index))?;
             if let Some(ptr) = ptr {
                 write!(f, "(overflow {}", ptr)?;
                 write!(f, ",raw:")?;
@@ -0,0 +1,158 @@
+use crate::page::index_page::records::format_value;
+use crate::page::index_page::records::new::row::ColInfo;
+use crate::page::index_page::records::old::header::{RecordHeader, OLD_HEAD_LEN};
+use crate::page::OverflowPointer;
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::table::Column;
+use std::fmt;
+use std::fmt::Formatter;
+use std::rc::Rc;
+
+/// 溢出页指针长度
+const EXTERN_LEN: u16 = 20;
+
+/// REDUNDANT 行格式的记录，记录头之前是倒序存放的各列结束位置
+pub struct Row<B> {
+    col_info: Vec<ColInfo>,
+    rec_offset: usize,
+    buffer: B,
+}
+
+impl<B: AsRef<[u8]>> Row<B> {
+    pub fn new(col_info: Vec<ColInfo>, rec_offset: usize, buffer: B) -> Row<B> {
+        Self {
+            col_info,
+            rec_offset,
+            buffer,
+        }
+    }
+    /// 解析字段偏移列表，返回记录开始处和列信息。列的数量以记录头中的 n_fields 为准，
+    /// 超出 columns 的列类型记为 UnKnow
+    pub fn parse_row_prefix(
+        columns: &[Column],
+        rec_offset: usize,
+        buffer: B,
+    ) -> (usize, Vec<ColInfo>) {
+        let buf = buffer.as_ref();
+        let header = RecordHeader::new(&buf[rec_offset - OLD_HEAD_LEN..rec_offset]);
+        let n_fields = header.n_fields() as usize;
+        let offs_len = if header.one_byte_offs() { 1 } else { 2 };
+        let mut col_info: Vec<ColInfo> = Vec::with_capacity(n_fields);
+        let mut last_end = 0;
+        for index in 0..n_fields {
+            let pos = rec_offset - OLD_HEAD_LEN - (index + 1) * offs_len;
+            let (end, is_null, is_overflow) = if offs_len == 1 {
+                let val = buf[pos] as u16;
+                (val & 0x7F, val & 0x80 != 0, false)
+            } else {
+                let val = u16::from_be_bytes([buf[pos], buf[pos + 1]]);
+                (val & 0x3FFF, val & 0x8000 != 0, val & 0x4000 != 0)
+            };
+            let len = end.saturating_sub(last_end);
+            let data_type = columns
+                .get(index)
+                .map(|column| column.data_type)
+                .unwrap_or(DataType::UnKnow(0, len as usize));
+            let charset = columns.get(index).and_then(|column| column.charset);
+            // 为空的定长列仍然占用空间，偏移量照常递增
+            col_info.push(ColInfo {
+                data_type,
+                offset: last_end,
+                len,
+                is_overflow,
+                is_null,
+                charset,
+                time_zone: columns.get(index).and_then(|column| column.time_zone),
+                elements: columns
+                    .get(index)
+                    .map(|column| column.elements.clone())
+                    .unwrap_or_else(|| Rc::from([])),
+            });
+            last_end = end;
+        }
+        (rec_offset - OLD_HEAD_LEN - n_fields * offs_len, col_info)
+    }
+}
+
+impl<B: AsRef<[u8]>> Row<B> {
+    pub fn header(&self) -> RecordHeader<&[u8]> {
+        let start = self.rec_offset - OLD_HEAD_LEN;
+        let end = self.rec_offset;
+        RecordHeader::new(&self.buffer.as_ref()[start..end])
+    }
+    pub fn data(&self) -> &[u8] {
+        &self.buffer.as_ref()[self.rec_offset..]
+    }
+    pub fn buf(&self) -> &[u8] {
+        self.buffer.as_ref()
+    }
+    pub fn col(&self, index: usize) -> (&[u8], Option<OverflowPointer<&[u8]>>) {
+        let (_, data, ptr) = self.col_type(index);
+        (data, ptr)
+    }
+    pub fn col_type(&self, index: usize) -> (DataType, &[u8], Option<OverflowPointer<&[u8]>>) {
+        let ColInfo {
+            data_type,
+            offset,
+            len,
+            is_overflow,
+            is_null,
+            ..
+        } = self.col_info[index];
+        if is_null {
+            return (data_type, &[], None);
+        }
+        let data = &self.data()[offset as usize..offset as usize + len as usize];
+        if is_overflow && len >= EXTERN_LEN {
+            let start = (len - EXTERN_LEN) as usize;
+            (
+                data_type,
+                &data[..start],
+                Some(OverflowPointer::new(&data[start..])),
+            )
+        } else {
+            (data_type, data, None)
+        }
+    }
+    pub fn is_null(&self, index: usize) -> bool {
+        self.col_info[index].is_null
+    }
+    /// 列值的文本形式
+    pub fn value(&self, index: usize) -> String {
+        let (data, ptr) = self.col(index);
+        format_value(&self.col_info[index], data, ptr.is_some())
+    }
+    pub fn col_info(&self) -> &Vec<ColInfo> {
+        &self.col_info
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Display for Row<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} ", &self.buf()[..self.rec_offset - OLD_HEAD_LEN])?;
+        write!(f, "{} ", self.header())?;
+        for index in 0..self.col_info().len() {
+            let (_, ptr) = self.col(index);
+            write!(f, "{} ", self.value(index))?;
+            if let Some(ptr) = ptr {
+                write!(f, "(overflow {}", ptr)?;
+                write!(f, ",raw:")?;
+                for x in ptr.buf {
+                    write!(f, "{:02x} ", x)?;
+                }
+                write!(f, ")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<B: AsRef<[u8]>> fmt::Debug for Row<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Row")
+            .field("col_info", &self.col_info())
+            .field("header", &self.header())
+            .field("data", &self.data())
+            .finish()
+    }
+}
@@ -41,12 +41,12 @@ impl<B: AsRef<[u8]>> InodeEntry<B> {
         u32::from_be_bytes(tmp)
     }
     pub fn fragment_array(&self) -> [u32; 32] {
-        let mut array = [0 as u32; 32];
-        for index in 0..32 {
+        let mut array = [0_u32; 32];
+        for (index, item) in array.iter_mut().enumerate() {
             let start = 64 + index * 4;
             let end = start + 4;
             let tmp: [u8; 4] = self.buffer.as_ref()[start..end].try_into().unwrap();
-            array[index] = u32::from_be_bytes(tmp);
+            *item = u32::from_be_bytes(tmp);
         }
         array
     }
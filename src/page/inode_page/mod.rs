@@ -1,5 +1,4 @@
 use crate::file_header::FileHeader;
-use crate::file_trailer::FileTrailer;
 use crate::page::base_page::InternalPage;
 use crate::page::inode_page::inode_entry::InodeEntry;
 use crate::page::ListNode;
@@ -9,13 +8,22 @@ use std::fmt::{Display, Formatter};
 
 pub mod inode_entry;
 
-#[derive(Debug)]
 pub struct InodePage {
     pub list_node: ListNode,
     pub inode_list: Vec<InodeEntry<Bytes>>,
     pub empty_space: Bytes,
 }
 
+impl fmt::Debug for InodePage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InodePage")
+            .field("list_node", &self.list_node)
+            .field("inode_list", &self.inode_list)
+            .field("empty_space", &self.empty_space)
+            .finish()
+    }
+}
+
 impl Display for InodePage {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(f, "{:?}", self)
@@ -1,26 +1,35 @@
 use bytes::Bytes;
-use index_page::header::IndexHeader;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::ops::Deref;
 
 use crate::file_header::page_type::PageType;
 use crate::file_header::FileHeader;
 use crate::page::base_page::{BasePage, UnKnowPage};
+use crate::page::dict_hdr_page::{DictHdrPage, DICT_HDR_PAGE_NO};
 use crate::page::hdr_page::page::FspHdrPage;
+use crate::page::ibuf_bitmap_page::IbufBitmapPage;
 use crate::page::index_page::page::IndexPage;
 use crate::page::inode_page::InodePage;
+use crate::page::rtree_page::RtreePage;
 use crate::page::sdi_blob_page::SdiBlobPage;
 use crate::page::sdi_page::SdiPage;
+use crate::page::trx_sys_page::TrxSysPage;
+use crate::page::undo_page::UndoPage;
 use crate::tablespace::table::TableInfo;
 
 pub mod base_page;
+pub mod compressed_page;
+pub mod dict_hdr_page;
 pub mod hdr_page;
+pub mod ibuf_bitmap_page;
 pub mod index_page;
 pub mod inode_page;
 pub mod lob_first_page;
+pub mod rtree_page;
 pub mod sdi_blob_page;
 pub mod sdi_page;
+pub mod trx_sys_page;
+pub mod undo_page;
 
 /// 在 InnoDB 里链表头叫做 FLST _BASE_NODE，大小为 FLST_BASE_NODE_SIZE（6+6+4字节）。
 /// FLST _BASE_NODE 中包含链表的头尾指针，链表中每个节点称为 FLST_NODE，大小为 FLST_NODE_SIZE（6+6字节）
@@ -130,24 +139,63 @@ impl<B: AsRef<[u8]>> fmt::Display for OverflowPointer<B> {
 
 pub enum PageEnums {
     Index(BasePage<IndexPage>),
+    Rtree(BasePage<RtreePage>),
     FspHdr(BasePage<FspHdrPage>),
     XDex(BasePage<FspHdrPage>),
     Inode(BasePage<InodePage>),
     Sdi(BasePage<SdiPage>),
     SdiBlob(BasePage<SdiBlobPage>),
+    TrxSys(BasePage<TrxSysPage>),
+    DictHdr(BasePage<DictHdrPage>),
+    Undo(BasePage<UndoPage>),
+    IbufBitmap(BasePage<IbufBitmapPage>),
     Undefine(BasePage<UnKnowPage>),
 }
 
 impl PageEnums {
     pub fn page_type(&self) -> PageType {
+        self.fil_header().page_type()
+    }
+    pub fn fil_header(&self) -> &FileHeader<Bytes> {
         match self {
-            PageEnums::Index(page) => page.fil_header.page_type(),
-            PageEnums::FspHdr(page) => page.fil_header.page_type(),
-            PageEnums::XDex(page) => page.fil_header.page_type(),
-            PageEnums::Inode(page) => page.fil_header.page_type(),
-            PageEnums::Sdi(page) => page.fil_header.page_type(),
-            PageEnums::SdiBlob(page) => page.fil_header.page_type(),
-            PageEnums::Undefine(page) => page.fil_header.page_type(),
+            PageEnums::Index(page) => &page.fil_header,
+            PageEnums::Rtree(page) => &page.fil_header,
+            PageEnums::FspHdr(page) => &page.fil_header,
+            PageEnums::XDex(page) => &page.fil_header,
+            PageEnums::Inode(page) => &page.fil_header,
+            PageEnums::Sdi(page) => &page.fil_header,
+            PageEnums::SdiBlob(page) => &page.fil_header,
+            PageEnums::TrxSys(page) => &page.fil_header,
+            PageEnums::DictHdr(page) => &page.fil_header,
+            PageEnums::Undo(page) => &page.fil_header,
+            PageEnums::IbufBitmap(page) => &page.fil_header,
+            PageEnums::Undefine(page) => &page.fil_header,
+        }
+    }
+    /// 整页原始数据
+    pub fn buf(&self) -> &Bytes {
+        match self {
+            PageEnums::Index(page) => &page.buf,
+            PageEnums::Rtree(page) => &page.buf,
+            PageEnums::FspHdr(page) => &page.buf,
+            PageEnums::XDex(page) => &page.buf,
+            PageEnums::Inode(page) => &page.buf,
+            PageEnums::Sdi(page) => &page.buf,
+            PageEnums::SdiBlob(page) => &page.buf,
+            PageEnums::TrxSys(page) => &page.buf,
+            PageEnums::DictHdr(page) => &page.buf,
+            PageEnums::Undo(page) => &page.buf,
+            PageEnums::IbufBitmap(page) => &page.buf,
+            PageEnums::Undefine(page) => &page.buf,
+        }
+    }
+    /// B+树节点页(包括sdi页)
+    pub fn index_page(&self) -> Option<&IndexPage> {
+        match self {
+            PageEnums::Index(page) => Some(&page.page),
+            PageEnums::Rtree(page) => Some(&page.page.original),
+            PageEnums::Sdi(page) => Some(&page.page.original),
+            _ => None,
         }
     }
 }
@@ -157,10 +205,24 @@ pub fn page(buf: Bytes, table_info: &TableInfo) -> PageEnums {
 
     match fil.page_type() {
         PageType::FilPageTypeFspHdr => PageEnums::FspHdr(BasePage::new(buf)),
-        PageType::FilPageTypeXdes => PageEnums::FspHdr(BasePage::new(buf)),
+        PageType::FilPageTypeXdes => PageEnums::XDex(BasePage::new(buf)),
         PageType::FilPageIndex => PageEnums::Index(BasePage::new_index(buf, table_info)),
+        PageType::FilPageRtree => PageEnums::Rtree(BasePage::new_index(buf, table_info)),
         PageType::FilPageInode => PageEnums::Inode(BasePage::new(buf)),
         PageType::FilPageSdi => PageEnums::Sdi(BasePage::new(buf)),
+        PageType::FilPageSdiBlob => PageEnums::SdiBlob(BasePage::new(buf)),
+        PageType::FilPageTypeTrxSys => PageEnums::TrxSys(BasePage::new(buf)),
+        PageType::FilPageUndoLog => PageEnums::Undo(BasePage::new(buf)),
+        PageType::FilPageIbufBitmap => PageEnums::IbufBitmap(BasePage::new(buf)),
+        // 透明页压缩，解压后按原来的页类型解析
+        PageType::FilPageCompressed => match compressed_page::decompress(buf.clone()) {
+            Ok(buf) => page(buf, table_info),
+            Err(_) => PageEnums::Undefine(BasePage::new(buf)),
+        },
+        // 系统表空间中的 FIL_PAGE_TYPE_SYS 页只有第7页为数据字典头
+        PageType::FilPageTypeSys if fil.space_id() == 0 && fil.offset() == DICT_HDR_PAGE_NO => {
+            PageEnums::DictHdr(BasePage::new(buf))
+        }
         _ => PageEnums::Undefine(BasePage::new(buf)),
     }
 }
@@ -171,6 +233,9 @@ impl Display for PageEnums {
             PageEnums::Index(page) => {
                 write!(f, "{}", page)
             }
+            PageEnums::Rtree(page) => {
+                write!(f, "{}", page)
+            }
             PageEnums::FspHdr(page) => {
                 write!(f, "{}", page)
             }
@@ -186,6 +251,18 @@ impl Display for PageEnums {
             PageEnums::SdiBlob(page) => {
                 write!(f, "{}", page)
             }
+            PageEnums::TrxSys(page) => {
+                write!(f, "{}", page)
+            }
+            PageEnums::DictHdr(page) => {
+                write!(f, "{}", page)
+            }
+            PageEnums::Undo(page) => {
+                write!(f, "{}", page)
+            }
+            PageEnums::IbufBitmap(page) => {
+                write!(f, "{}", page)
+            }
             PageEnums::Undefine(page) => {
                 write!(f, "{}", page)
             }
@@ -0,0 +1,63 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::InternalIndexPage;
+use crate::page::index_page::page::IndexPage;
+use crate::tablespace::geometry::Mbr;
+use crate::tablespace::table::TableInfo;
+use bytes::Bytes;
+use console::style;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+
+/// R-tree 索引页，FIL_PAGE_RTREE。页结构与B+树索引页相同，记录的第一个字段为 MBR，
+/// 非叶子节点记录为 MBR 和子页号，叶子节点记录为 MBR 和主键
+#[derive(Debug)]
+pub struct RtreePage {
+    /// FIL_RTREE_SPLIT_SEQ_NUM，在 File Header 中 FIL_PAGE_FILE_FLUSH_LSN 的位置，
+    /// 页分裂时递增，用于判断搜索路径上的页是否发生了分裂
+    pub split_seq_num: u64,
+    pub original: IndexPage,
+}
+
+impl Deref for RtreePage {
+    type Target = IndexPage;
+
+    fn deref(&self) -> &Self::Target {
+        &self.original
+    }
+}
+
+impl RtreePage {
+    /// 记录的 MBR，第一个字段不是合法的 MBR 时为 None
+    pub fn mbrs(&self) -> Vec<Option<Mbr>> {
+        self.user_records
+            .iter()
+            .map(|row| Mbr::new(row.col(0).0).ok())
+            .collect()
+    }
+    /// 页中所有记录的外包矩形，应被父节点中指向该页的记录的 MBR 包含
+    pub fn mbr(&self) -> Option<Mbr> {
+        self.mbrs().into_iter().flatten().reduce(|a, b| a.union(&b))
+    }
+}
+
+impl Display for RtreePage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("RtreePage").green())?;
+        writeln!(f, " split_seq_num:{}", self.split_seq_num)?;
+        match self.mbr() {
+            Some(mbr) => writeln!(f, " mbr:{}", mbr)?,
+            None => writeln!(f, " mbr:none")?,
+        }
+        write!(f, "{}", self.original)
+    }
+}
+
+impl InternalIndexPage for RtreePage {
+    fn new(buf: Bytes, fil_header: &FileHeader<Bytes>, table_info: &TableInfo) -> RtreePage {
+        Self {
+            split_seq_num: fil_header.flush_lsn(),
+            original: <IndexPage as InternalIndexPage>::new(buf, fil_header, table_info),
+        }
+    }
+}
@@ -1,17 +1,25 @@
 use crate::file_header::FileHeader;
-use crate::file_trailer::FileTrailer;
 use crate::page::base_page::InternalPage;
 use bytes::{Buf, Bytes};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug)]
 pub struct SdiBlobPage {
     pub part_len: u32,
     pub next_page_num: u32,
     pub data: Bytes,
 }
 
+impl fmt::Debug for SdiBlobPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SdiBlobPage")
+            .field("part_len", &self.part_len)
+            .field("next_page_num", &self.next_page_num)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
 impl Display for SdiBlobPage {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         writeln!(f, "{:?}", self)
@@ -1,15 +1,10 @@
 use crate::file_header::FileHeader;
-use crate::file_trailer::FileTrailer;
-use crate::page::base_page::{InternalIndexPage, InternalPage};
+use crate::page::base_page::InternalPage;
 use crate::page::index_page::page::IndexPage;
-use crate::page::index_page::records::Row;
 use crate::tablespace::table::sdi_index;
 use bytes::Bytes;
-use serde_json::Value;
-use std::collections::HashMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
-use std::io::Read;
 use std::ops::Deref;
 
 #[derive(Debug)]
@@ -34,7 +29,7 @@ impl Display for SdiPage {
 impl InternalPage for SdiPage {
     fn new(buf: Bytes, fil_header: &FileHeader<Bytes>) -> SdiPage {
         let index = sdi_index(fil_header.offset());
-        let original = IndexPage::new0(buf, index);
+        let original = IndexPage::new0(buf, fil_header, index);
         Self { original }
     }
 }
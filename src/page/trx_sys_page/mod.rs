@@ -0,0 +1,160 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::InternalPage;
+use bytes::{Buf, Bytes};
+use console::style;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// 回滚段槽位数量
+pub const TRX_SYS_N_RSEGS: usize = 128;
+/// binlog 信息的位置，相对页尾
+const TRX_SYS_MYSQL_LOG_INFO: usize = 1000;
+const TRX_SYS_MYSQL_LOG_MAGIC_N: u32 = 873422344;
+const TRX_SYS_MYSQL_LOG_NAME_LEN: usize = 512;
+/// doublewrite 信息的位置，相对页尾
+const TRX_SYS_DOUBLEWRITE: usize = 200;
+pub const TRX_SYS_DOUBLEWRITE_MAGIC_N: u32 = 536853855;
+const TRX_SYS_DOUBLEWRITE_SPACE_ID_STORED_N: u32 = 1783657386;
+/// 每个 doublewrite 块的页数
+pub const TRX_SYS_DOUBLEWRITE_BLOCK_SIZE: u32 = 64;
+
+/// 系统表空间第5页，事务系统页
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// TRX_SYS_TRX_ID_STORE |8| 最近一次持久化的最大事务ID，每分配256个事务ID写一次
+/// TRX_SYS_FSEG_HEADER |10| 事务系统段的 space_id、inode页号、页内偏移
+/// TRX_SYS_RSEGS |128*8| 回滚段槽位，每个槽位为回滚段头所在的 space_id 和页号
+/// TRX_SYS_MYSQL_LOG_INFO |524| 位于页尾1000字节处，最后一次提交事务对应的binlog文件名和位置
+/// TRX_SYS_DOUBLEWRITE |38| 位于页尾200字节处，doublewrite 缓冲区的两个块的起始页号
+#[derive(Debug)]
+pub struct TrxSysPage {
+    pub trx_id_store: u64,
+    pub fseg_space_id: u32,
+    pub fseg_page_num: u32,
+    pub fseg_offset: u16,
+    /// (槽位, space_id, 页号)，只包含已使用的槽位
+    pub rsegs: Vec<(usize, u32, u32)>,
+    pub binlog: Option<(String, u64)>,
+    pub doublewrite: Option<Doublewrite>,
+}
+
+/// doublewrite 缓冲区在系统表空间中的位置
+#[derive(Debug, Copy, Clone)]
+pub struct Doublewrite {
+    pub fseg_space_id: u32,
+    pub fseg_page_num: u32,
+    pub fseg_offset: u16,
+    /// 第一个块的起始页号
+    pub block1: u32,
+    /// 第二个块的起始页号
+    pub block2: u32,
+    /// 是否已写入 space_id
+    pub space_id_stored: bool,
+}
+
+impl Doublewrite {
+    /// doublewrite 缓冲区中的所有页号
+    pub fn pages(&self) -> impl Iterator<Item = u32> {
+        (self.block1..self.block1 + TRX_SYS_DOUBLEWRITE_BLOCK_SIZE)
+            .chain(self.block2..self.block2 + TRX_SYS_DOUBLEWRITE_BLOCK_SIZE)
+    }
+}
+
+impl InternalPage for TrxSysPage {
+    fn new(buf: Bytes, _: &FileHeader<Bytes>) -> TrxSysPage {
+        let trx_id_store = buf.slice(..8).get_u64();
+        let fseg_space_id = buf.slice(8..12).get_u32();
+        let fseg_page_num = buf.slice(12..16).get_u32();
+        let fseg_offset = buf.slice(16..18).get_u16();
+        let mut rsegs = Vec::new();
+        for slot in 0..TRX_SYS_N_RSEGS {
+            let start = 18 + slot * 8;
+            let space_id = buf.slice(start..start + 4).get_u32();
+            let page_num = buf.slice(start + 4..start + 8).get_u32();
+            if page_num != u32::MAX {
+                rsegs.push((slot, space_id, page_num));
+            }
+        }
+        // buf 不包含38字节的 File Header 和8字节的 File Trailer
+        let page_size = buf.len() + 38 + 8;
+        let log = page_size - TRX_SYS_MYSQL_LOG_INFO - 38;
+        let binlog = if buf.slice(log..log + 4).get_u32() == TRX_SYS_MYSQL_LOG_MAGIC_N {
+            let offset = buf.slice(log + 4..log + 12).get_u64();
+            let name = &buf[log + 12..log + 12 + TRX_SYS_MYSQL_LOG_NAME_LEN];
+            let len = name.iter().position(|x| *x == 0).unwrap_or(name.len());
+            Some((String::from_utf8_lossy(&name[..len]).to_string(), offset))
+        } else {
+            None
+        };
+        let dblwr = page_size - TRX_SYS_DOUBLEWRITE - 38;
+        let doublewrite =
+            if buf.slice(dblwr + 10..dblwr + 14).get_u32() == TRX_SYS_DOUBLEWRITE_MAGIC_N {
+                Some(Doublewrite {
+                    fseg_space_id: buf.slice(dblwr..dblwr + 4).get_u32(),
+                    fseg_page_num: buf.slice(dblwr + 4..dblwr + 8).get_u32(),
+                    fseg_offset: buf.slice(dblwr + 8..dblwr + 10).get_u16(),
+                    block1: buf.slice(dblwr + 14..dblwr + 18).get_u32(),
+                    block2: buf.slice(dblwr + 18..dblwr + 22).get_u32(),
+                    space_id_stored: buf.slice(dblwr + 34..dblwr + 38).get_u32()
+                        == TRX_SYS_DOUBLEWRITE_SPACE_ID_STORED_N,
+                })
+            } else {
+                None
+            };
+        Self {
+            trx_id_store,
+            fseg_space_id,
+            fseg_page_num,
+            fseg_offset,
+            rsegs,
+            binlog,
+            doublewrite,
+        }
+    }
+}
+
+impl Display for TrxSysPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("TrxSysHeader").green())?;
+        writeln!(f, " trx_id_store:{}", self.trx_id_store)?;
+        writeln!(
+            f,
+            " fseg:space_id:{} page_num:{} offset:{}",
+            self.fseg_space_id, self.fseg_page_num, self.fseg_offset
+        )?;
+        writeln!(f, "{}", style("RollbackSegments").green())?;
+        for (slot, space_id, page_num) in &self.rsegs {
+            writeln!(
+                f,
+                " slot:{} space_id:{} page_num:{}",
+                slot, space_id, page_num
+            )?;
+        }
+        writeln!(f, "{}", style("MysqlLogInfo").green())?;
+        match &self.binlog {
+            Some((name, offset)) => writeln!(f, " binlog:{} offset:{}", name, offset)?,
+            None => writeln!(f, " (无)")?,
+        }
+        writeln!(f, "{}", style("Doublewrite").green())?;
+        match &self.doublewrite {
+            Some(dblwr) => {
+                writeln!(
+                    f,
+                    " fseg:space_id:{} page_num:{} offset:{}",
+                    dblwr.fseg_space_id, dblwr.fseg_page_num, dblwr.fseg_offset
+                )?;
+                writeln!(
+                    f,
+                    " block1:{}..{} block2:{}..{} space_id_stored:{}",
+                    dblwr.block1,
+                    dblwr.block1 + TRX_SYS_DOUBLEWRITE_BLOCK_SIZE,
+                    dblwr.block2,
+                    dblwr.block2 + TRX_SYS_DOUBLEWRITE_BLOCK_SIZE,
+                    dblwr.space_id_stored
+                )
+            }
+            None => writeln!(f, " (未创建)"),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::InternalPage;
+use crate::page::undo_page::record::{UndoRecord, FIL_PAGE_DATA};
+use crate::page::{ListBaseNode, ListNode};
+use bytes::{Buf, Bytes};
+use console::style;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+pub mod record;
+
+/// undo页头长度
+const TRX_UNDO_PAGE_HDR_SIZE: usize = 18;
+/// undo段头长度
+const TRX_UNDO_SEG_HDR_SIZE: usize = 30;
+/// undo日志头长度(不含XID)
+const TRX_UNDO_LOG_HDR_SIZE: usize = 46;
+
+/// undo页头，位于 File Header 之后
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// TRX_UNDO_PAGE_TYPE |2| 1:insert undo，2:update undo
+/// TRX_UNDO_PAGE_START |2| 本页中最新的undo日志的第一条记录的偏移
+/// TRX_UNDO_PAGE_FREE |2| 本页空闲空间的偏移
+/// TRX_UNDO_PAGE_NODE |12| undo段页链表的节点
+#[derive(Debug)]
+pub struct UndoPageHeader {
+    pub page_type: u16,
+    pub start: u16,
+    pub free: u16,
+    pub node: ListNode,
+}
+
+impl UndoPageHeader {
+    pub fn new(buf: &Bytes) -> Self {
+        Self {
+            page_type: buf.slice(..2).get_u16(),
+            start: buf.slice(2..4).get_u16(),
+            free: buf.slice(4..6).get_u16(),
+            node: ListNode::new(&buf[6..18]),
+        }
+    }
+    pub fn type_name(&self) -> &'static str {
+        match self.page_type {
+            1 => "insert",
+            2 => "update",
+            _ => "unknown",
+        }
+    }
+}
+
+/// undo段头，只在undo段的第一个页中
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// TRX_UNDO_STATE |2| 1:active，2:cached，3:to_free，4:to_purge，5:prepared
+/// TRX_UNDO_LAST_LOG |2| 最后一个undo日志头的偏移
+/// TRX_UNDO_FSEG_HEADER |10| undo段的 space_id、inode页号、页内偏移
+/// TRX_UNDO_PAGE_LIST |16| undo段所有页组成的链表
+#[derive(Debug)]
+pub struct UndoSegHeader {
+    pub state: u16,
+    pub last_log: u16,
+    pub fseg_space_id: u32,
+    pub fseg_page_num: u32,
+    pub fseg_offset: u16,
+    pub page_list: ListBaseNode,
+}
+
+impl UndoSegHeader {
+    pub fn new(buf: &Bytes) -> Self {
+        Self {
+            state: buf.slice(..2).get_u16(),
+            last_log: buf.slice(2..4).get_u16(),
+            fseg_space_id: buf.slice(4..8).get_u32(),
+            fseg_page_num: buf.slice(8..12).get_u32(),
+            fseg_offset: buf.slice(12..14).get_u16(),
+            page_list: ListBaseNode::new(&buf[14..30]),
+        }
+    }
+    pub fn state_name(&self) -> &'static str {
+        match self.state {
+            1 => "active",
+            2 => "cached",
+            3 => "to_free",
+            4 => "to_purge",
+            5 => "prepared",
+            _ => "unknown",
+        }
+    }
+}
+
+/// undo日志头，每个事务一个，位于undo段头之后
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// TRX_UNDO_TRX_ID |8| 事务ID
+/// TRX_UNDO_TRX_NO |8| 事务提交序号
+/// TRX_UNDO_DEL_MARKS |2| 是否有删除标记的记录
+/// TRX_UNDO_LOG_START |2| 第一条undo记录的偏移
+/// TRX_UNDO_FLAGS |1| 是否有XID等标志
+/// TRX_UNDO_DICT_TRANS |1| 是否为DDL事务
+/// TRX_UNDO_TABLE_ID |8| DDL事务操作的表ID
+/// TRX_UNDO_NEXT_LOG |2| 下一个undo日志头的偏移
+/// TRX_UNDO_PREV_LOG |2| 上一个undo日志头的偏移
+/// TRX_UNDO_HISTORY_NODE |12| 回滚段 history 链表的节点
+#[derive(Debug)]
+pub struct UndoLogHeader {
+    /// 日志头在页内的偏移
+    pub offset: u16,
+    pub trx_id: u64,
+    pub trx_no: u64,
+    pub del_marks: u16,
+    pub log_start: u16,
+    pub flags: u8,
+    pub dict_trans: u8,
+    pub table_id: u64,
+    pub next_log: u16,
+    pub prev_log: u16,
+    pub history_node: ListNode,
+}
+
+impl UndoLogHeader {
+    pub fn new(buf: &Bytes, offset: u16) -> Self {
+        Self {
+            offset,
+            trx_id: buf.slice(..8).get_u64(),
+            trx_no: buf.slice(8..16).get_u64(),
+            del_marks: buf.slice(16..18).get_u16(),
+            log_start: buf.slice(18..20).get_u16(),
+            flags: buf[20],
+            dict_trans: buf[21],
+            table_id: buf.slice(22..30).get_u64(),
+            next_log: buf.slice(30..32).get_u16(),
+            prev_log: buf.slice(32..34).get_u16(),
+            history_node: ListNode::new(&buf[34..46]),
+        }
+    }
+}
+
+/// undo页，FIL_PAGE_UNDO_LOG
+#[derive(Debug)]
+pub struct UndoPage {
+    pub page_header: UndoPageHeader,
+    /// undo段的第一个页才有段头和日志头
+    pub seg_header: Option<UndoSegHeader>,
+    pub log_headers: Vec<UndoLogHeader>,
+    /// 不知道表结构，只解析记录头
+    pub records: Vec<UndoRecord>,
+}
+
+impl InternalPage for UndoPage {
+    fn new(buf: Bytes, _: &FileHeader<Bytes>) -> UndoPage {
+        let page_header = UndoPageHeader::new(&buf.slice(..TRX_UNDO_PAGE_HDR_SIZE));
+        let free = page_header.free as usize;
+        let seg_start = TRX_UNDO_PAGE_HDR_SIZE;
+        let first_log = FIL_PAGE_DATA + seg_start + TRX_UNDO_SEG_HDR_SIZE;
+        let seg_header =
+            UndoSegHeader::new(&buf.slice(seg_start..seg_start + TRX_UNDO_SEG_HDR_SIZE));
+        // 段头中的状态和最后一个日志头的偏移都合法时认为是undo段的第一个页
+        let seg_header = ((1..=5).contains(&seg_header.state)
+            && (first_log..free).contains(&(seg_header.last_log as usize)))
+        .then_some(seg_header);
+
+        let mut log_headers = Vec::new();
+        if seg_header.is_some() {
+            let mut offset = first_log;
+            while offset + TRX_UNDO_LOG_HDR_SIZE <= free && log_headers.len() < 128 {
+                let start = offset - FIL_PAGE_DATA;
+                let log_header = UndoLogHeader::new(
+                    &buf.slice(start..start + TRX_UNDO_LOG_HDR_SIZE),
+                    offset as u16,
+                );
+                let next_log = log_header.next_log as usize;
+                log_headers.push(log_header);
+                if next_log <= offset {
+                    break;
+                }
+                offset = next_log;
+            }
+        }
+
+        // 每个日志头的记录从 log_start 开始，到下一个日志头或空闲空间为止；
+        // 不是段的第一个页时，记录紧跟在页头之后
+        let mut ranges = Vec::new();
+        if log_headers.is_empty() {
+            ranges.push((FIL_PAGE_DATA + TRX_UNDO_PAGE_HDR_SIZE, free));
+        } else {
+            for (i, log_header) in log_headers.iter().enumerate() {
+                let end = log_headers
+                    .get(i + 1)
+                    .map(|next| next.offset as usize)
+                    .unwrap_or(free);
+                ranges.push((log_header.log_start as usize, end));
+            }
+        }
+        let mut records = Vec::new();
+        for (mut offset, end) in ranges {
+            while offset < end {
+                let Ok(record) = UndoRecord::new(&buf, offset as u16, None) else {
+                    break;
+                };
+                offset = record.next as usize;
+                records.push(record);
+            }
+        }
+        Self {
+            page_header,
+            seg_header,
+            log_headers,
+            records,
+        }
+    }
+}
+
+impl Display for UndoPage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("UndoPageHeader").green())?;
+        writeln!(f, " type:{}", self.page_header.type_name())?;
+        writeln!(f, " start:{}", self.page_header.start)?;
+        writeln!(f, " free:{}", self.page_header.free)?;
+        writeln!(f, " node:{}", self.page_header.node)?;
+        if let Some(seg_header) = &self.seg_header {
+            writeln!(f, "{}", style("UndoSegHeader").green())?;
+            writeln!(f, " state:{}", seg_header.state_name())?;
+            writeln!(f, " last_log:{}", seg_header.last_log)?;
+            writeln!(
+                f,
+                " fseg: space_id:{} page_num:{} offset:{}",
+                seg_header.fseg_space_id, seg_header.fseg_page_num, seg_header.fseg_offset
+            )?;
+            writeln!(
+                f,
+                " page_list: len:{} first:{} last:{}",
+                seg_header.page_list.len, seg_header.page_list.first, seg_header.page_list.last
+            )?;
+        }
+        for log_header in &self.log_headers {
+            writeln!(f, "{}", style("UndoLogHeader").green())?;
+            writeln!(f, " offset:{}", log_header.offset)?;
+            writeln!(f, " trx_id:{}", log_header.trx_id)?;
+            writeln!(f, " trx_no:{}", log_header.trx_no)?;
+            writeln!(f, " del_marks:{}", log_header.del_marks)?;
+            writeln!(f, " log_start:{}", log_header.log_start)?;
+            writeln!(f, " flags:{}", log_header.flags)?;
+            writeln!(f, " dict_trans:{}", log_header.dict_trans)?;
+            writeln!(f, " table_id:{}", log_header.table_id)?;
+            writeln!(f, " next_log:{}", log_header.next_log)?;
+            writeln!(f, " prev_log:{}", log_header.prev_log)?;
+            writeln!(f, " history_node:{}", log_header.history_node)?;
+        }
+        writeln!(f, "{}", style("UndoRecords").green())?;
+        for record in &self.records {
+            writeln!(f, "  {}", record)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use crate::mach::Reader;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// File Header 长度，undo页中的偏移都从页首算起
+pub const FIL_PAGE_DATA: usize = 38;
+/// 插入产生的undo记录
+pub const TRX_UNDO_INSERT_REC: u8 = 11;
+/// 更新未删除的记录
+pub const TRX_UNDO_UPD_EXIST_REC: u8 = 12;
+/// 更新已标记删除的记录
+pub const TRX_UNDO_UPD_DEL_REC: u8 = 13;
+/// 标记删除
+pub const TRX_UNDO_DEL_MARK_REC: u8 = 14;
+/// 更新了外部存储的列
+const TRX_UNDO_UPD_EXTERN: u8 = 128;
+/// MySQL 8.0 修改了LOB，type_cmpl 之后多一个字节的标志
+const TRX_UNDO_MODIFY_BLOB: u8 = 64;
+/// 长度为 UNIV_SQL_NULL 表示 NULL
+const UNIV_SQL_NULL: u32 = u32::MAX;
+/// 长度大于等于该值表示外部存储的列，实际长度为减去该值
+const UNIV_EXTERN_STORAGE_FIELD: u32 = UNIV_SQL_NULL - 16 * 1024;
+
+/// 回滚指针 DB_ROLL_PTR，7字节
+///
+/// 名称   |长度|描述
+/// ------|:------|:------
+/// is_insert |1 bit| 是否为插入产生的undo记录
+/// rseg_id |7 bit| 回滚段ID
+/// page_num |4| undo记录所在页号
+/// offset |2| undo记录在页内的偏移
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RollPtr {
+    pub is_insert: bool,
+    pub rseg_id: u8,
+    pub page_num: u32,
+    pub offset: u16,
+}
+
+impl RollPtr {
+    pub fn new(value: u64) -> Self {
+        Self {
+            is_insert: value >> 55 & 1 == 1,
+            rseg_id: (value >> 48 & 0x7F) as u8,
+            page_num: (value >> 16) as u32,
+            offset: value as u16,
+        }
+    }
+    pub fn value(&self) -> u64 {
+        (self.is_insert as u64) << 55
+            | (self.rseg_id as u64) << 48
+            | (self.page_num as u64) << 16
+            | self.offset as u64
+    }
+    /// 从记录中7字节的 DB_ROLL_PTR 解析
+    pub fn from_bytes(buf: &[u8]) -> Option<Self> {
+        if buf.len() != 7 {
+            return None;
+        }
+        let value = buf.iter().fold(0u64, |acc, b| acc << 8 | *b as u64);
+        Some(RollPtr::new(value))
+    }
+}
+
+impl Display for RollPtr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "insert:{} rseg:{} page:{} offset:{}",
+            self.is_insert, self.rseg_id, self.page_num, self.offset
+        )
+    }
+}
+
+/// undo记录中的一列
+#[derive(Debug, Clone)]
+pub enum UndoField {
+    Null,
+    Data(Vec<u8>),
+    /// 外部存储的列，数据为本地前缀和溢出指针
+    Extern(Vec<u8>),
+}
+
+impl Display for UndoField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            UndoField::Null => write!(f, "NULL"),
+            UndoField::Data(data) => write!(f, "{}", hex::encode(data)),
+            UndoField::Extern(data) => write!(f, "extern({})", hex::encode(data)),
+        }
+    }
+}
+
+/// undo 记录
+///
+/// 名称   |长度|描述
+/// ------|:------|:------
+/// next |2| 下一条undo记录的偏移
+/// type_cmpl |1| 低4位为记录类型，4~5位为 cmpl_info，最高位表示更新了外部存储列
+/// undo_no |压缩| 事务内undo记录编号
+/// table_id |压缩| 表ID
+/// info_bits |1| 更新/删除记录才有，原记录的 info bits
+/// trx_id |压缩| 更新/删除记录才有，原记录的 DB_TRX_ID
+/// roll_ptr |压缩| 更新/删除记录才有，原记录的 DB_ROLL_PTR，指向更早的版本
+/// 主键列 |可变| 每列为压缩的长度和数据
+/// 更新列 |可变| 更新记录才有，列数以及每列的序号、长度和数据
+/// start |2| 本条记录的偏移
+#[derive(Debug, Clone)]
+pub struct UndoRecord {
+    /// 记录在页内的偏移
+    pub offset: u16,
+    pub next: u16,
+    pub rec_type: u8,
+    pub cmpl_info: u8,
+    pub updates_extern: bool,
+    pub undo_no: u64,
+    pub table_id: u64,
+    pub info_bits: Option<u8>,
+    pub trx_id: Option<u64>,
+    pub roll_ptr: Option<RollPtr>,
+    /// 主键列
+    pub key: Vec<UndoField>,
+    /// (列序号, 旧值)
+    pub update: Vec<(u32, UndoField)>,
+    /// 未解析的部分
+    pub rest: Vec<u8>,
+}
+
+impl UndoRecord {
+    /// buf 为不含 File Header 的页数据，offset 为记录在页内的偏移(从页首算起)；
+    /// n_unique 为聚簇索引的唯一列数，未知时只解析记录头，其余部分保存在 rest 中
+    pub fn new(buf: &[u8], offset: u16, n_unique: Option<usize>) -> io::Result<UndoRecord> {
+        let invalid = || io::Error::other(format!("invalid undo record at {}", offset));
+        let start = (offset as usize)
+            .checked_sub(FIL_PAGE_DATA)
+            .ok_or_else(invalid)?;
+        let next = Reader::new(buf, start).u16()?;
+        // 记录末尾2字节为本条记录的偏移
+        let end = (next as usize)
+            .checked_sub(FIL_PAGE_DATA + 2)
+            .filter(|end| *end > start + 2 && *end <= buf.len())
+            .ok_or_else(invalid)?;
+        let mut reader = Reader::new(&buf[..end], start + 2);
+        let type_cmpl = reader.u8()?;
+        if type_cmpl & TRX_UNDO_MODIFY_BLOB != 0 {
+            reader.u8()?;
+        }
+        let rec_type = type_cmpl & 0x0F;
+        let mut record = UndoRecord {
+            offset,
+            next,
+            rec_type,
+            cmpl_info: type_cmpl >> 4 & 0x03,
+            updates_extern: type_cmpl & TRX_UNDO_UPD_EXTERN != 0,
+            undo_no: reader.much_compressed_u64()?,
+            table_id: reader.much_compressed_u64()?,
+            info_bits: None,
+            trx_id: None,
+            roll_ptr: None,
+            key: Vec::new(),
+            update: Vec::new(),
+            rest: Vec::new(),
+        };
+        if rec_type != TRX_UNDO_INSERT_REC {
+            record.info_bits = Some(reader.u8()?);
+            record.trx_id = Some(reader.compressed_u64()?);
+            record.roll_ptr = Some(RollPtr::new(reader.compressed_u64()?));
+        }
+        if let Some(n_unique) = n_unique {
+            for _ in 0..n_unique {
+                record.key.push(read_field(&mut reader)?);
+            }
+            if rec_type == TRX_UNDO_UPD_EXIST_REC || rec_type == TRX_UNDO_UPD_DEL_REC {
+                let n_fields = reader.compressed()?;
+                for _ in 0..n_fields {
+                    let field_no = reader.compressed()?;
+                    let field = read_field(&mut reader)?;
+                    let is_extern = matches!(field, UndoField::Extern(_));
+                    record.update.push((field_no, field));
+                    // MySQL 8.0 外部存储列之后还有LOB的undo信息，不再继续解析
+                    if is_extern {
+                        break;
+                    }
+                }
+            }
+        }
+        record.rest = reader.rest().to_vec();
+        Ok(record)
+    }
+    pub fn type_name(&self) -> &'static str {
+        match self.rec_type {
+            TRX_UNDO_INSERT_REC => "insert",
+            TRX_UNDO_UPD_EXIST_REC => "update_exist",
+            TRX_UNDO_UPD_DEL_REC => "update_deleted",
+            TRX_UNDO_DEL_MARK_REC => "delete_mark",
+            _ => "unknown",
+        }
+    }
+}
+
+impl Display for UndoRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "offset:{} next:{} type:{} cmpl_info:{} extern:{} undo_no:{} table_id:{}",
+            self.offset,
+            self.next,
+            self.type_name(),
+            self.cmpl_info,
+            self.updates_extern,
+            self.undo_no,
+            self.table_id
+        )?;
+        if let (Some(info_bits), Some(trx_id), Some(roll_ptr)) =
+            (self.info_bits, self.trx_id, self.roll_ptr)
+        {
+            write!(
+                f,
+                " info_bits:{} trx_id:{} roll_ptr:({})",
+                info_bits, trx_id, roll_ptr
+            )?;
+        }
+        if !self.key.is_empty() {
+            write!(f, " key:[")?;
+            for (i, field) in self.key.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", field)?;
+            }
+            write!(f, "]")?;
+        }
+        if !self.update.is_empty() {
+            write!(f, " update:[")?;
+            for (i, (field_no, field)) in self.update.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}={}", field_no, field)?;
+            }
+            write!(f, "]")?;
+        }
+        if !self.rest.is_empty() {
+            write!(f, " rest:{}", hex::encode(&self.rest))?;
+        }
+        Ok(())
+    }
+}
+
+/// 压缩的长度加数据
+fn read_field(reader: &mut Reader) -> io::Result<UndoField> {
+    let len = reader.compressed()?;
+    Ok(if len == UNIV_SQL_NULL {
+        UndoField::Null
+    } else if len >= UNIV_EXTERN_STORAGE_FIELD {
+        UndoField::Extern(
+            reader
+                .take((len - UNIV_EXTERN_STORAGE_FIELD) as usize)?
+                .to_vec(),
+        )
+    } else {
+        UndoField::Data(reader.take(len as usize)?.to_vec())
+    })
+}
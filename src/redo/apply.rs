@@ -0,0 +1,441 @@
+use crate::mach::Reader;
+use crate::redo::page_cur::{IndexPageMut, LogIndex};
+use crate::redo::record::*;
+use crate::redo::RedoLog;
+use console::style;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// FIL_PAGE_LSN 在页中的偏移
+const FIL_PAGE_LSN: usize = 16;
+const FIL_PAGE_OFFSET: usize = 4;
+const FIL_PAGE_SPACE_ID: usize = 34;
+const FIL_PAGE_INDEX: u16 = 17855;
+const FIL_PAGE_RTREE: u16 = 17854;
+const FIL_PAGE_SDI: u16 = 17853;
+const FIL_PAGE_UNDO_LOG: u16 = 2;
+/// undo页头的偏移和长度
+const TRX_UNDO_PAGE_HDR: usize = 38;
+const TRX_UNDO_PAGE_HDR_SIZE: usize = 18;
+/// btr0cur.h 中表示不修改系统列的标志
+const BTR_KEEP_SYS_FLAG: u8 = 4;
+/// 长度为 UNIV_SQL_NULL 表示 NULL
+const UNIV_SQL_NULL: u32 = u32::MAX;
+
+/// 一个mtr在某个页上的日志，只保留完整的mtr
+#[derive(Debug)]
+struct PageMtr {
+    start_lsn: u64,
+    end_lsn: u64,
+    records: Vec<RedoRecord>,
+}
+
+/// 页的前滚结果
+#[derive(Debug, Default)]
+pub struct PageRecovery {
+    /// 前滚前的页LSN
+    pub page_lsn: u64,
+    /// 前滚后的页LSN
+    pub end_lsn: u64,
+    pub applied: usize,
+    /// 页LSN之前已经写入页的日志
+    pub skipped: usize,
+    /// 遇到错误后该页之后的日志不再应用
+    pub errors: Vec<String>,
+}
+
+impl Display for PageRecovery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", style("RollForward").green())?;
+        writeln!(
+            f,
+            " page_lsn:{} -> {} applied:{} skipped:{}",
+            self.page_lsn, self.end_lsn, self.applied, self.skipped
+        )?;
+        for error in &self.errors {
+            writeln!(f, " {}", style(error).red())?;
+        }
+        Ok(())
+    }
+}
+
+/// 离线前滚：把redo日志中LSN不超过 to_lsn 的完整mtr应用到页上，
+/// 相当于只做redo阶段的崩溃恢复，不回滚未提交的事务
+#[derive(Debug)]
+pub struct RollForward {
+    pages: HashMap<u32, Vec<PageMtr>>,
+}
+
+impl RollForward {
+    /// to_lsn 为空时应用到日志的末尾
+    pub fn new(redo_log: &RedoLog, space_id: u32, to_lsn: Option<u64>) -> Self {
+        let to_lsn = to_lsn.unwrap_or(redo_log.end_lsn);
+        let mut pages: HashMap<u32, Vec<PageMtr>> = HashMap::new();
+        let mut group: Vec<&RedoRecord> = Vec::new();
+        for record in &redo_log.records {
+            // 多条日志的mtr以 MLOG_MULTI_REC_END 结束，没有结束的mtr不应用
+            let complete = if record.single {
+                group.clear();
+                group.push(record);
+                true
+            } else if record.rec_type == MLOG_MULTI_REC_END {
+                true
+            } else {
+                group.push(record);
+                false
+            };
+            if !complete || group.is_empty() {
+                continue;
+            }
+            let start_lsn = group[0].lsn;
+            let end_lsn = record.end_lsn;
+            if end_lsn <= to_lsn {
+                for record in group
+                    .iter()
+                    .filter(|record| record.has_page() && record.space_id == space_id)
+                {
+                    let mtrs = pages.entry(record.page_no).or_default();
+                    match mtrs.last_mut() {
+                        Some(mtr) if mtr.start_lsn == start_lsn => {
+                            mtr.records.push((*record).clone())
+                        }
+                        _ => mtrs.push(PageMtr {
+                            start_lsn,
+                            end_lsn,
+                            records: vec![(*record).clone()],
+                        }),
+                    }
+                }
+            }
+            group.clear();
+        }
+        Self { pages }
+    }
+    /// 在内存中应用页LSN之后的日志，对应 recv_recover_page
+    pub fn apply(&self, page_no: u32, buf: &mut [u8]) -> PageRecovery {
+        let page_lsn = u64::from_be_bytes(buf[FIL_PAGE_LSN..FIL_PAGE_LSN + 8].try_into().unwrap());
+        let mut recovery = PageRecovery {
+            page_lsn,
+            end_lsn: page_lsn,
+            ..PageRecovery::default()
+        };
+        let Some(mtrs) = self.pages.get(&page_no) else {
+            return recovery;
+        };
+        for mtr in mtrs {
+            if mtr.start_lsn < page_lsn {
+                recovery.skipped += mtr.records.len();
+                continue;
+            }
+            for record in &mtr.records {
+                if let Err(e) = apply_record(record, buf) {
+                    recovery.errors.push(format!(
+                        "lsn {} {}: {}",
+                        record.lsn,
+                        record.type_name(),
+                        e
+                    ));
+                    return recovery;
+                }
+                recovery.applied += 1;
+            }
+            // 页LSN为最后应用的mtr的结束LSN，File Trailer 中保存低32位
+            buf[FIL_PAGE_LSN..FIL_PAGE_LSN + 8].copy_from_slice(&mtr.end_lsn.to_be_bytes());
+            let len = buf.len();
+            buf[len - 4..].copy_from_slice(&(mtr.end_lsn as u32).to_be_bytes());
+            recovery.end_lsn = mtr.end_lsn;
+        }
+        recovery
+    }
+}
+
+/// 应用一条日志，对应 recv_parse_or_apply_log_rec_body
+fn apply_record(record: &RedoRecord, buf: &mut [u8]) -> io::Result<()> {
+    let mut reader = Reader::new(&record.body, 0);
+    let rec_type = record.rec_type;
+    let index = if has_index_8027(rec_type) {
+        LogIndex::parse_8027(&mut reader)?
+    } else if has_index(rec_type) {
+        LogIndex::parse(&mut reader)?
+    } else {
+        LogIndex::default()
+    };
+    // 有行版本的记录需要按行版本确定存在的列，不支持
+    if index.versioned {
+        return Err(io::Error::other("index with row versions not supported"));
+    }
+    match rec_type {
+        MLOG_1BYTE | MLOG_2BYTES | MLOG_4BYTES => {
+            let offset = reader.u16()? as usize;
+            let value = reader.compressed()?;
+            let len = rec_type as usize;
+            let bytes = value.to_be_bytes();
+            write(buf, offset, &bytes[4 - len..])?;
+        }
+        MLOG_8BYTES => {
+            let offset = reader.u16()? as usize;
+            let value = reader.compressed_u64()?;
+            write(buf, offset, &value.to_be_bytes())?;
+        }
+        MLOG_WRITE_STRING => {
+            let offset = reader.u16()? as usize;
+            let len = reader.u16()? as usize;
+            write(buf, offset, reader.take(len)?)?;
+        }
+        MLOG_INIT_FILE_PAGE | MLOG_INIT_FILE_PAGE2 => {
+            // fsp_init_file_page_low
+            buf.fill(0);
+            write(buf, FIL_PAGE_OFFSET, &record.page_no.to_be_bytes())?;
+            write(buf, FIL_PAGE_SPACE_ID, &record.space_id.to_be_bytes())?;
+        }
+        MLOG_PAGE_CREATE | MLOG_COMP_PAGE_CREATE => {
+            IndexPageMut::new(buf).create(rec_type == MLOG_COMP_PAGE_CREATE, FIL_PAGE_INDEX)
+        }
+        MLOG_PAGE_CREATE_RTREE | MLOG_COMP_PAGE_CREATE_RTREE => {
+            IndexPageMut::new(buf).create(rec_type == MLOG_COMP_PAGE_CREATE_RTREE, FIL_PAGE_RTREE)
+        }
+        MLOG_PAGE_CREATE_SDI | MLOG_COMP_PAGE_CREATE_SDI => {
+            IndexPageMut::new(buf).create(rec_type == MLOG_COMP_PAGE_CREATE_SDI, FIL_PAGE_SDI)
+        }
+        MLOG_REC_INSERT_8027 | MLOG_COMP_REC_INSERT_8027 | MLOG_REC_INSERT => {
+            let cursor = reader.u16()? as usize;
+            IndexPageMut::new(buf).parse_insert_rec(&mut reader, cursor, &index)?;
+        }
+        MLOG_LIST_END_COPY_CREATED_8027
+        | MLOG_COMP_LIST_END_COPY_CREATED_8027
+        | MLOG_LIST_END_COPY_CREATED => {
+            // page_parse_copy_rec_list_to_created_page：每条记录插入到最后一条记录之后
+            let len = reader.u32()? as usize;
+            let data = reader.take(len)?;
+            let mut reader = Reader::new(data, 0);
+            let mut page = IndexPageMut::new(buf);
+            let mut cursor = page.last_rec()?;
+            while !reader.rest().is_empty() {
+                cursor = page.parse_insert_rec(&mut reader, cursor, &index)?;
+            }
+            page.reset_last_insert();
+        }
+        MLOG_REC_CLUST_DELETE_MARK_8027
+        | MLOG_COMP_REC_CLUST_DELETE_MARK_8027
+        | MLOG_REC_CLUST_DELETE_MARK => {
+            // btr_cur_parse_del_mark_set_clust_rec
+            let flags = reader.u8()?;
+            let val = reader.u8()?;
+            let sys_vals = SysVals::parse(&mut reader)?;
+            let offset = reader.u16()? as usize;
+            let mut page = IndexPageMut::new(buf);
+            page.set_deleted(check_rec(offset)?, val != 0);
+            if flags & BTR_KEEP_SYS_FLAG == 0 {
+                sys_vals.write(&mut page, offset, &index)?;
+            }
+        }
+        MLOG_REC_SEC_DELETE_MARK | MLOG_COMP_REC_SEC_DELETE_MARK => {
+            let val = reader.u8()?;
+            let offset = reader.u16()? as usize;
+            IndexPageMut::new(buf).set_deleted(check_rec(offset)?, val != 0);
+        }
+        MLOG_REC_MIN_MARK | MLOG_COMP_REC_MIN_MARK => {
+            let offset = reader.u16()? as usize;
+            IndexPageMut::new(buf).set_min_rec_mark(check_rec(offset)?);
+        }
+        MLOG_REC_UPDATE_IN_PLACE_8027
+        | MLOG_COMP_REC_UPDATE_IN_PLACE_8027
+        | MLOG_REC_UPDATE_IN_PLACE => {
+            // btr_cur_parse_update_in_place
+            let flags = reader.u8()?;
+            let sys_vals = SysVals::parse(&mut reader)?;
+            let offset = check_rec(reader.u16()? as usize)?;
+            let info_bits = reader.u8()?;
+            let n_fields = reader.compressed()?;
+            let mut page = IndexPageMut::new(buf);
+            let offsets = page.rec_offsets(offset, &index)?;
+            page.set_info_bits(offset, info_bits);
+            if flags & BTR_KEEP_SYS_FLAG == 0 {
+                sys_vals.write(&mut page, offset, &index)?;
+            }
+            for _ in 0..n_fields {
+                let field_no = reader.compressed()? as usize;
+                let len = reader.compressed()?;
+                let (start, field_len, _) = *offsets
+                    .fields
+                    .get(field_no)
+                    .ok_or_else(|| io::Error::other(format!("invalid field {}", field_no)))?;
+                if len == UNIV_SQL_NULL {
+                    page.set_field_null(offset, field_no, &index)?;
+                    continue;
+                }
+                let data = reader.take(len as usize)?;
+                // 原地更新要求长度不变
+                if data.len() != field_len {
+                    return Err(io::Error::other(format!(
+                        "field {} length {} differs from {}",
+                        field_no,
+                        data.len(),
+                        field_len
+                    )));
+                }
+                write(page.buf, offset + start, data)?;
+            }
+        }
+        MLOG_REC_DELETE_8027 | MLOG_COMP_REC_DELETE_8027 | MLOG_REC_DELETE => {
+            let offset = reader.u16()? as usize;
+            IndexPageMut::new(buf).delete_rec(check_rec(offset)?, &index)?;
+        }
+        MLOG_LIST_END_DELETE_8027 | MLOG_COMP_LIST_END_DELETE_8027 | MLOG_LIST_END_DELETE => {
+            let offset = reader.u16()? as usize;
+            IndexPageMut::new(buf).delete_rec_list_end(check_rec(offset)?, &index)?;
+        }
+        MLOG_LIST_START_DELETE_8027 | MLOG_COMP_LIST_START_DELETE_8027 | MLOG_LIST_START_DELETE => {
+            let offset = reader.u16()? as usize;
+            IndexPageMut::new(buf).delete_rec_list_start(check_rec(offset)?, &index)?;
+        }
+        MLOG_PAGE_REORGANIZE_8027 | MLOG_COMP_PAGE_REORGANIZE_8027 | MLOG_PAGE_REORGANIZE => {
+            IndexPageMut::new(buf).reorganize(&index)?;
+        }
+        MLOG_UNDO_INIT => {
+            // trx_undo_page_init
+            let undo_type = reader.compressed()? as u16;
+            let free = (TRX_UNDO_PAGE_HDR + TRX_UNDO_PAGE_HDR_SIZE) as u16;
+            write(buf, 24, &FIL_PAGE_UNDO_LOG.to_be_bytes())?;
+            write(buf, TRX_UNDO_PAGE_HDR, &undo_type.to_be_bytes())?;
+            write(buf, TRX_UNDO_PAGE_HDR + 2, &free.to_be_bytes())?;
+            write(buf, TRX_UNDO_PAGE_HDR + 4, &free.to_be_bytes())?;
+        }
+        MLOG_UNDO_INSERT => {
+            // trx_undo_parse_add_undo_rec：记录前后各有2字节的偏移
+            let len = reader.u16()? as usize;
+            let data = reader.take(len)?;
+            let free_pos = TRX_UNDO_PAGE_HDR + 4;
+            let first_free =
+                u16::from_be_bytes(buf[free_pos..free_pos + 2].try_into().unwrap()) as usize;
+            let next = (first_free + 4 + len) as u16;
+            write(buf, first_free, &next.to_be_bytes())?;
+            write(buf, first_free + 2, data)?;
+            write(
+                buf,
+                first_free + 2 + len,
+                &(first_free as u16).to_be_bytes(),
+            )?;
+            write(buf, free_pos, &next.to_be_bytes())?;
+        }
+        MLOG_UNDO_ERASE_END => {
+            let free_pos = TRX_UNDO_PAGE_HDR + 4;
+            let first_free =
+                u16::from_be_bytes(buf[free_pos..free_pos + 2].try_into().unwrap()) as usize;
+            let end = buf.len() - 8;
+            buf.get_mut(first_free..end)
+                .ok_or_else(|| io::Error::other("invalid undo page free offset"))?
+                .fill(0xFF);
+        }
+        MLOG_IBUF_BITMAP_INIT | MLOG_DUMMY_RECORD | MLOG_FILE_NAME => {}
+        _ => {
+            return Err(io::Error::other("not supported"));
+        }
+    }
+    Ok(())
+}
+
+/// 日志中的系统列：DB_TRX_ID 的列序号、DB_ROLL_PTR、DB_TRX_ID
+struct SysVals {
+    pos: usize,
+    roll_ptr: [u8; 7],
+    trx_id: u64,
+}
+
+impl SysVals {
+    /// row_upd_parse_sys_vals
+    fn parse(reader: &mut Reader) -> io::Result<Self> {
+        let pos = reader.compressed()? as usize;
+        let roll_ptr = reader.take(7)?.try_into().unwrap();
+        let trx_id = reader.compressed_u64()?;
+        Ok(Self {
+            pos,
+            roll_ptr,
+            trx_id,
+        })
+    }
+    /// row_upd_rec_sys_fields_in_recovery：DB_TRX_ID 之后紧跟 DB_ROLL_PTR
+    fn write(&self, page: &mut IndexPageMut, rec: usize, index: &LogIndex) -> io::Result<()> {
+        let offsets = page.rec_offsets(rec, index)?;
+        let (start, _, _) = *offsets
+            .fields
+            .get(self.pos)
+            .ok_or_else(|| io::Error::other(format!("invalid DB_TRX_ID position {}", self.pos)))?;
+        write(page.buf, rec + start, &self.trx_id.to_be_bytes()[2..])?;
+        write(page.buf, rec + start + 6, &self.roll_ptr)
+    }
+}
+
+fn check_rec(offset: usize) -> io::Result<usize> {
+    if offset == 0 {
+        return Err(io::Error::other("invalid record offset 0"));
+    }
+    Ok(offset)
+}
+
+fn write(buf: &mut [u8], offset: usize, data: &[u8]) -> io::Result<()> {
+    buf.get_mut(offset..offset + data.len())
+        .ok_or_else(|| io::Error::other(format!("write out of page at {}", offset)))?
+        .copy_from_slice(data);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_nbytes() {
+        // 一个mtr中写 space_id 5 page_no 3 的1、2、4、8字节，以 MLOG_MULTI_REC_END 结束
+        let log = [
+            0x01, 0x05, 0x03, 0x01, 0x00, 0x80, 0xAB, // MLOG_1BYTE 0x100 = 0xAB
+            0x02, 0x05, 0x03, 0x01, 0x10, 0x92, 0x34, // MLOG_2BYTES 0x110 = 0x1234
+            0x04, 0x05, 0x03, 0x01, 0x20, 0xF0, 0x12, 0x34, 0x56, 0x78, // MLOG_4BYTES 0x120
+            0x08, 0x05, 0x03, 0x01, 0x30, 0xF0, 0x01, 0x02, 0x03, 0x04, // MLOG_8BYTES 0x130
+            0x05, 0x06, 0x07, 0x08, // 8字节值的低32位
+            0x1F, // MLOG_MULTI_REC_END
+        ];
+        let start_lsn = 1000;
+        let mut records = Vec::new();
+        let mut pos = 0;
+        while pos < log.len() {
+            let (mut record, end) = RedoRecord::new(&log, pos).unwrap();
+            record.lsn = start_lsn + pos as u64;
+            record.end_lsn = start_lsn + end as u64;
+            records.push(record);
+            pos = end;
+        }
+        let end_lsn = start_lsn + log.len() as u64;
+        let redo_log = RedoLog {
+            files: Vec::new(),
+            checkpoint: None,
+            start_lsn,
+            end_lsn,
+            records,
+            errors: Vec::new(),
+        };
+        let roll_forward = RollForward::new(&redo_log, 5, None);
+
+        let mut page = vec![0u8; 16384];
+        page[FIL_PAGE_LSN..FIL_PAGE_LSN + 8].copy_from_slice(&900u64.to_be_bytes());
+        let recovery = roll_forward.apply(3, &mut page);
+        assert!(recovery.errors.is_empty(), "{:?}", recovery.errors);
+        assert_eq!(recovery.applied, 4);
+        assert_eq!((recovery.page_lsn, recovery.end_lsn), (900, end_lsn));
+        assert_eq!(page[0x100], 0xAB);
+        assert_eq!(page[0x110..0x112], [0x12, 0x34]);
+        assert_eq!(page[0x120..0x124], [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(
+            page[0x130..0x138],
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]
+        );
+        assert_eq!(page[FIL_PAGE_LSN..FIL_PAGE_LSN + 8], end_lsn.to_be_bytes());
+        assert_eq!(page[16380..], (end_lsn as u32).to_be_bytes());
+
+        // 页LSN已经包含该mtr时不再应用
+        let recovery = roll_forward.apply(3, &mut page);
+        assert_eq!((recovery.applied, recovery.skipped), (0, 4));
+    }
+}
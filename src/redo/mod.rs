@@ -0,0 +1,455 @@
+use crate::redo::record::RedoRecord;
+use bytes::{Buf, Bytes};
+use console::style;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+pub mod apply;
+pub mod page_cur;
+pub mod record;
+
+/// 日志块大小
+pub const OS_FILE_LOG_BLOCK_SIZE: usize = 512;
+/// 日志文件头大小，包括文件头块和两个检查点块
+pub const LOG_FILE_HDR_SIZE: usize = 2048;
+const LOG_BLOCK_HDR_SIZE: usize = 12;
+const LOG_BLOCK_TRL_SIZE: usize = 4;
+const LOG_BLOCK_DATA_SIZE: usize = OS_FILE_LOG_BLOCK_SIZE - LOG_BLOCK_HDR_SIZE - LOG_BLOCK_TRL_SIZE;
+/// 检查点块在文件中的偏移
+const LOG_CHECKPOINT_1: usize = 512;
+const LOG_CHECKPOINT_2: usize = 1536;
+/// 块号的最大值，块号 = lsn / 512 % LOG_BLOCK_MAX_NO + 1
+const LOG_BLOCK_MAX_NO: u64 = 0x4000_0000;
+/// 关闭 innodb_log_checksums 时的校验和
+const LOG_NO_CHECKSUM_MAGIC: u32 = 0xDEAD_BEEF;
+/// 块头 data_len 中表示加密的位
+const LOG_BLOCK_ENCRYPT_BIT_MASK: u16 = 0x8000;
+
+/// 日志文件头，位于每个日志文件的第一个块
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// LOG_HEADER_FORMAT |4| 日志格式版本
+/// LOG_HEADER_PAD1 |4| 未使用(MySQL 8.0.30 之后为日志文件ID)
+/// LOG_HEADER_START_LSN |8| 文件中数据部分(2048字节之后)起始位置的LSN
+/// LOG_HEADER_CREATOR |32| 创建日志的程序，如 "MySQL 8.0.32"
+#[derive(Debug)]
+pub struct LogFileHeader {
+    pub format: u32,
+    pub start_lsn: u64,
+    pub creator: String,
+}
+
+impl LogFileHeader {
+    pub fn new(buf: &Bytes) -> Self {
+        let creator = &buf[16..48];
+        let end = creator.iter().position(|x| *x == 0).unwrap_or(32);
+        Self {
+            format: buf.slice(..4).get_u32(),
+            start_lsn: buf.slice(8..16).get_u64(),
+            creator: String::from_utf8_lossy(&creator[..end]).to_string(),
+        }
+    }
+}
+
+/// 检查点，文件头之后的第1、3个块
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// LOG_CHECKPOINT_NO |8| 检查点序号
+/// LOG_CHECKPOINT_LSN |8| 检查点LSN，恢复从这里开始
+/// LOG_CHECKPOINT_OFFSET |8| 检查点LSN在日志文件组中的偏移(MySQL 5.7)
+/// LOG_CHECKPOINT_LOG_BUF_SIZE |8| 日志缓冲区大小(MySQL 5.7)
+#[derive(Debug, Copy, Clone)]
+pub struct Checkpoint {
+    pub no: u64,
+    pub lsn: u64,
+    pub offset: u64,
+    pub is_valid: bool,
+}
+
+impl Checkpoint {
+    pub fn new(buf: &Bytes) -> Self {
+        Self {
+            no: buf.slice(..8).get_u64(),
+            lsn: buf.slice(8..16).get_u64(),
+            offset: buf.slice(16..24).get_u64(),
+            is_valid: is_checksum_valid(buf),
+        }
+    }
+}
+
+/// 日志块
+///
+/// 名称             |长度|描述
+/// ------------------|:--------------------|:------------------
+/// LOG_BLOCK_HDR_NO |4| 块号，最高位为 flush 标记
+/// LOG_BLOCK_HDR_DATA_LEN |2| 块中已使用的字节数(包括块头)，512表示写满
+/// LOG_BLOCK_FIRST_REC_GROUP |2| 块中第一个mtr日志的起始偏移，0表示没有
+/// LOG_BLOCK_CHECKPOINT_NO |4| 写入时的检查点序号(MySQL 8.0.30 之后为 epoch_no)
+/// data |496| 日志
+/// LOG_BLOCK_CHECKSUM |4| 校验和
+#[derive(Debug, Clone)]
+pub struct LogBlock {
+    pub hdr_no: u32,
+    pub data_len: u16,
+    pub first_rec_group: u16,
+    pub buf: Bytes,
+}
+
+impl LogBlock {
+    pub fn new(buf: Bytes) -> Self {
+        let hdr_no = buf.slice(..4).get_u32();
+        Self {
+            hdr_no: hdr_no & 0x7FFF_FFFF,
+            data_len: buf.slice(4..6).get_u16(),
+            first_rec_group: buf.slice(6..8).get_u16(),
+            buf,
+        }
+    }
+    pub fn is_checksum_valid(&self) -> bool {
+        is_checksum_valid(&self.buf)
+    }
+    pub fn is_encrypted(&self) -> bool {
+        self.data_len & LOG_BLOCK_ENCRYPT_BIT_MASK != 0
+    }
+    /// 块中日志数据，不包括块头和校验和
+    pub fn data(&self) -> &[u8] {
+        let len = (self.data_len & !LOG_BLOCK_ENCRYPT_BIT_MASK) as usize;
+        let end = len.clamp(
+            LOG_BLOCK_HDR_SIZE,
+            OS_FILE_LOG_BLOCK_SIZE - LOG_BLOCK_TRL_SIZE,
+        );
+        &self.buf[LOG_BLOCK_HDR_SIZE..end]
+    }
+}
+
+/// 块起始位置的LSN。块号只保存了 lsn / 512 的低30位，取离 near 最近的一个
+fn block_lsn(hdr_no: u32, near: u64) -> u64 {
+    let window = LOG_BLOCK_MAX_NO * OS_FILE_LOG_BLOCK_SIZE as u64;
+    let low = (hdr_no as u64).saturating_sub(1) * OS_FILE_LOG_BLOCK_SIZE as u64;
+    let base = near / window * window;
+    [
+        base.checked_sub(window),
+        Some(base),
+        base.checked_add(window),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|base| base + low)
+    .min_by_key(|lsn| lsn.abs_diff(near))
+    .unwrap()
+}
+
+/// 块的校验和，默认为 crc32c，旧版本为 innodb 算法，也可能关闭了校验
+fn is_checksum_valid(buf: &[u8]) -> bool {
+    let checksum = u32::from_be_bytes(buf[508..512].try_into().unwrap());
+    let data = &buf[..508];
+    checksum == crc32c::crc32c(data)
+        || checksum == innodb_checksum(data)
+        || checksum == LOG_NO_CHECKSUM_MAGIC
+}
+
+/// log_block_calc_checksum_innodb
+fn innodb_checksum(data: &[u8]) -> u32 {
+    let mut sum: u32 = 1;
+    let mut sh = 0;
+    for x in data {
+        sum &= 0x7FFF_FFFF;
+        sum = sum.wrapping_add(*x as u32);
+        sum = sum.wrapping_add((*x as u32) << sh);
+        sh += 1;
+        if sh > 24 {
+            sh = 0;
+        }
+    }
+    sum
+}
+
+/// 一个日志文件。日志文件可能有几个GB，打开时逐块扫描，只记录有效块的位置，解析时只读取检查点之后的块
+pub struct LogFile {
+    pub path: PathBuf,
+    pub header: LogFileHeader,
+    pub checkpoints: Vec<Checkpoint>,
+    /// 块号不为0且校验和正确的块，(块号,文件中的偏移)
+    pub blocks: Vec<(u32, u64)>,
+    /// 文件头之后的块数
+    pub n_blocks: usize,
+    /// 校验和错误的块数
+    pub invalid_checksum: usize,
+}
+
+impl LogFile {
+    pub fn open(path: &Path) -> io::Result<LogFile> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut hdr = vec![0; LOG_FILE_HDR_SIZE];
+        reader
+            .read_exact(&mut hdr)
+            .map_err(|_| io::Error::other(format!("{} is not a redo log file", path.display())))?;
+        let hdr = Bytes::from(hdr);
+        let header = LogFileHeader::new(&hdr.slice(..OS_FILE_LOG_BLOCK_SIZE));
+        let checkpoints = [LOG_CHECKPOINT_1, LOG_CHECKPOINT_2]
+            .into_iter()
+            .map(|start| Checkpoint::new(&hdr.slice(start..start + OS_FILE_LOG_BLOCK_SIZE)))
+            .collect();
+        let mut blocks = Vec::new();
+        let mut n_blocks = 0;
+        let mut invalid_checksum = 0;
+        let mut buf = [0; OS_FILE_LOG_BLOCK_SIZE];
+        loop {
+            match reader.read_exact(&mut buf) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+            let offset = (LOG_FILE_HDR_SIZE + n_blocks * OS_FILE_LOG_BLOCK_SIZE) as u64;
+            n_blocks += 1;
+            let block = LogBlock::new(Bytes::copy_from_slice(&buf));
+            if block.hdr_no == 0 {
+                continue;
+            }
+            if block.is_checksum_valid() {
+                blocks.push((block.hdr_no, offset));
+            } else {
+                invalid_checksum += 1;
+            }
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            header,
+            checkpoints,
+            blocks,
+            n_blocks,
+            invalid_checksum,
+        })
+    }
+}
+
+/// 读取日志文件中 offset 处的块
+fn read_block(file: &mut File, offset: u64) -> io::Result<LogBlock> {
+    let mut buf = vec![0; OS_FILE_LOG_BLOCK_SIZE];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buf)?;
+    Ok(LogBlock::new(Bytes::from(buf)))
+}
+
+/// 日志文件组：MySQL 8.0.30 之前为数据目录下的 ib_logfile0、ib_logfile1...，
+/// 之后为 #innodb_redo 目录下的 #ib_redoN。
+/// 检查点之后的日志数据会全部读入内存解析，并保留解析出的所有日志，
+/// 占用的内存与检查点之后的日志量相当
+pub struct RedoLog {
+    pub files: Vec<LogFile>,
+    /// 最新的有效检查点
+    pub checkpoint: Option<Checkpoint>,
+    /// 解析的起止LSN
+    pub start_lsn: u64,
+    pub end_lsn: u64,
+    pub records: Vec<RedoRecord>,
+    /// 解析过程中遇到的问题
+    pub errors: Vec<String>,
+}
+
+impl RedoLog {
+    /// path 为日志文件或所在目录
+    pub fn open(path: &Path) -> io::Result<RedoLog> {
+        let paths = if path.is_dir() {
+            let mut paths = Vec::new();
+            for entry in fs::read_dir(path)? {
+                let path = entry?.path();
+                if let Some(num) = log_file_num(&path) {
+                    paths.push((num, path));
+                }
+            }
+            paths.sort();
+            paths.into_iter().map(|(_, path)| path).collect()
+        } else {
+            vec![path.to_path_buf()]
+        };
+        if paths.is_empty() {
+            return Err(io::Error::other(format!(
+                "no redo log file found in {}",
+                path.display()
+            )));
+        }
+        let files = paths
+            .iter()
+            .map(|path| LogFile::open(path))
+            .collect::<io::Result<Vec<_>>>()?;
+        let checkpoint = files
+            .iter()
+            .flat_map(|file| file.checkpoints.iter())
+            .filter(|checkpoint| checkpoint.is_valid && checkpoint.lsn != 0)
+            .max_by_key(|checkpoint| checkpoint.no)
+            .copied();
+        let mut redo_log = Self {
+            files,
+            checkpoint,
+            start_lsn: 0,
+            end_lsn: 0,
+            records: Vec::new(),
+            errors: Vec::new(),
+        };
+        redo_log.parse()?;
+        Ok(redo_log)
+    }
+    /// 按LSN排列所有有效的块，从检查点开始把连续的块中的日志数据拼接到内存中再解析
+    fn parse(&mut self) -> io::Result<()> {
+        let near = self
+            .checkpoint
+            .map(|checkpoint| checkpoint.lsn)
+            .or_else(|| self.files.first().map(|file| file.header.start_lsn))
+            .unwrap_or(0);
+        // 块的LSN -> (文件下标,文件中的偏移)
+        let mut blocks = BTreeMap::new();
+        for (index, file) in self.files.iter().enumerate() {
+            for &(hdr_no, offset) in &file.blocks {
+                blocks.insert(block_lsn(hdr_no, near), (index, offset));
+            }
+        }
+        let mut handles = self
+            .files
+            .iter()
+            .map(|file| File::open(&file.path))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut read = |&(index, offset): &(usize, u64)| read_block(&mut handles[index], offset);
+        let block_size = OS_FILE_LOG_BLOCK_SIZE as u64;
+        // 检查点所在的块不存在时，从第一个有mtr起点的块开始
+        let start_lsn = match self.checkpoint {
+            Some(checkpoint)
+                if blocks.contains_key(&(checkpoint.lsn / block_size * block_size)) =>
+            {
+                checkpoint.lsn
+            }
+            _ => {
+                let mut start = None;
+                for (lsn, location) in &blocks {
+                    let block = read(location)?;
+                    if block.first_rec_group as usize >= LOG_BLOCK_HDR_SIZE {
+                        start = Some(lsn + block.first_rec_group as u64);
+                        break;
+                    }
+                }
+                let Some(start) = start else {
+                    self.errors.push("no log block found".to_string());
+                    return Ok(());
+                };
+                start
+            }
+        };
+        // 拼接连续的块中的数据，buf[i] 的 LSN 为 first + i / 496 * 512 + 12 + i % 496
+        let first = start_lsn / block_size * block_size;
+        let mut buf = Vec::new();
+        let mut group_starts = Vec::new();
+        let mut block_lsn = first;
+        while let Some(location) = blocks.get(&block_lsn) {
+            let block = read(location)?;
+            if block.is_encrypted() {
+                self.errors
+                    .push(format!("block at lsn {} is encrypted", block_lsn));
+                break;
+            }
+            if block.first_rec_group as usize >= LOG_BLOCK_HDR_SIZE {
+                group_starts.push(buf.len() + block.first_rec_group as usize - LOG_BLOCK_HDR_SIZE);
+            }
+            let data = block.data();
+            buf.extend_from_slice(data);
+            if data.len() < LOG_BLOCK_DATA_SIZE {
+                break;
+            }
+            block_lsn += block_size;
+        }
+        let to_lsn = |pos: usize| {
+            first
+                + (pos / LOG_BLOCK_DATA_SIZE * OS_FILE_LOG_BLOCK_SIZE) as u64
+                + (LOG_BLOCK_HDR_SIZE + pos % LOG_BLOCK_DATA_SIZE) as u64
+        };
+        // 检查点LSN可能落在块头或块尾中，从块内日志数据的边界开始
+        let mut pos = ((start_lsn - first) as usize)
+            .clamp(LOG_BLOCK_HDR_SIZE, LOG_BLOCK_HDR_SIZE + LOG_BLOCK_DATA_SIZE)
+            - LOG_BLOCK_HDR_SIZE;
+        self.start_lsn = to_lsn(pos);
+        while pos < buf.len() {
+            match RedoRecord::new(&buf, pos) {
+                Ok((mut record, end)) => {
+                    record.lsn = to_lsn(pos);
+                    record.end_lsn = to_lsn(end);
+                    self.records.push(record);
+                    pos = end;
+                }
+                Err(e) => {
+                    // 无法解析时跳到下一个mtr的起点
+                    self.errors.push(format!("lsn {}: {}", to_lsn(pos), e));
+                    match group_starts.iter().find(|start| **start > pos) {
+                        Some(start) => pos = *start,
+                        None => break,
+                    }
+                }
+            }
+        }
+        self.end_lsn = to_lsn(pos.min(buf.len()));
+        Ok(())
+    }
+    /// 作用于某个表空间(和页)的日志
+    pub fn page_records(
+        &self,
+        space_id: u32,
+        page_no: Option<u32>,
+    ) -> impl Iterator<Item = &RedoRecord> {
+        self.records.iter().filter(move |record| {
+            record.has_page()
+                && record.space_id == space_id
+                && page_no.is_none_or(|page_no| record.page_no == page_no)
+        })
+    }
+}
+
+impl Display for RedoLog {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for file in &self.files {
+            writeln!(f, "{}", style(file.path.display()).green())?;
+            writeln!(
+                f,
+                " format:{} start_lsn:{} creator:{}",
+                file.header.format, file.header.start_lsn, file.header.creator
+            )?;
+            for checkpoint in &file.checkpoints {
+                writeln!(
+                    f,
+                    " checkpoint no:{} lsn:{} offset:{} valid:{}",
+                    checkpoint.no, checkpoint.lsn, checkpoint.offset, checkpoint.is_valid
+                )?;
+            }
+            writeln!(
+                f,
+                " blocks:{} invalid_checksum:{}",
+                file.n_blocks, file.invalid_checksum
+            )?;
+        }
+        writeln!(
+            f,
+            "parsed lsn {} .. {}, {} records",
+            self.start_lsn,
+            self.end_lsn,
+            self.records.len()
+        )?;
+        for error in &self.errors {
+            writeln!(f, " {}", style(error).red())?;
+        }
+        Ok(())
+    }
+}
+
+/// ib_logfileN 或 #ib_redoN 的序号
+fn log_file_num(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    let num = name
+        .strip_prefix("ib_logfile")
+        .or_else(|| name.strip_prefix("#ib_redo"))?;
+    num.parse().ok()
+}
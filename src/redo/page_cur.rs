@@ -0,0 +1,768 @@
+use crate::mach::Reader;
+use std::io;
+
+/// Page Header 在页中的偏移
+const PAGE_HEADER: usize = 38;
+const PAGE_N_DIR_SLOTS: usize = PAGE_HEADER;
+const PAGE_HEAP_TOP: usize = PAGE_HEADER + 2;
+const PAGE_N_HEAP: usize = PAGE_HEADER + 4;
+const PAGE_FREE: usize = PAGE_HEADER + 6;
+const PAGE_GARBAGE: usize = PAGE_HEADER + 8;
+const PAGE_LAST_INSERT: usize = PAGE_HEADER + 10;
+const PAGE_DIRECTION: usize = PAGE_HEADER + 12;
+const PAGE_N_DIRECTION: usize = PAGE_HEADER + 14;
+const PAGE_N_RECS: usize = PAGE_HEADER + 16;
+const PAGE_MAX_TRX_ID: usize = PAGE_HEADER + 18;
+const PAGE_LEVEL: usize = PAGE_HEADER + 26;
+const PAGE_INDEX_ID: usize = PAGE_HEADER + 28;
+/// Index Header 和 FSEG Header 之后为 infimum
+const PAGE_DATA: usize = PAGE_HEADER + 36 + 20;
+const PAGE_LEFT: u16 = 1;
+const PAGE_RIGHT: u16 = 2;
+const PAGE_NO_DIRECTION: u16 = 5;
+const PAGE_DIR_SLOT_MAX_N_OWNED: u8 = 8;
+const PAGE_DIR_SLOT_MIN_N_OWNED: u8 = 4;
+/// File Trailer 长度
+const FIL_PAGE_DATA_END: usize = 8;
+const REC_INFO_MIN_REC_FLAG: u8 = 0x10;
+const REC_INFO_DELETED_FLAG: u8 = 0x20;
+const REC_STATUS_NODE_PTR: u8 = 1;
+const REC_STATUS_INFIMUM: u8 = 2;
+const REC_STATUS_SUPREMUM: u8 = 3;
+
+/// page0page.cc 中 COMPACT 格式的 infimum 和 supremum
+const INFIMUM_SUPREMUM_COMPACT: [u8; 26] = [
+    0x01, 0x00, 0x02, 0x00, 0x0d, b'i', b'n', b'f', b'i', b'm', b'u', b'm', 0x00, 0x01, 0x00, 0x0b,
+    0x00, 0x00, b's', b'u', b'p', b'r', b'e', b'm', b'u', b'm',
+];
+/// page0page.cc 中 REDUNDANT 格式的 infimum 和 supremum
+const INFIMUM_SUPREMUM_REDUNDANT: [u8; 31] = [
+    0x08, 0x01, 0x00, 0x00, 0x03, 0x00, 0x74, b'i', b'n', b'f', b'i', b'm', b'u', b'm', 0x00, 0x09,
+    0x01, 0x00, 0x08, 0x03, 0x00, 0x00, b's', b'u', b'p', b'r', b'e', b'm', b'u', b'm', 0x00,
+];
+
+/// COMPACT 格式的日志中记录的索引列
+#[derive(Debug, Clone)]
+pub struct LogField {
+    pub nullable: bool,
+    /// 0 表示变长
+    pub fixed_len: usize,
+    /// 长度可能超过255字节，变长长度可能为2字节
+    pub big: bool,
+}
+
+impl LogField {
+    /// 最高位为1表示 NOT NULL，其余为定长列的长度，0x7FFF 表示长度可能超过255字节的变长列
+    fn new(len: u16) -> Self {
+        let fixed_len = (len & 0x7FFF) as usize;
+        Self {
+            nullable: len & 0x8000 == 0,
+            fixed_len: if fixed_len == 0x7FFF { 0 } else { fixed_len },
+            big: fixed_len == 0x7FFF,
+        }
+    }
+}
+
+/// COMPACT 格式的日志中记录的索引信息，REDUNDANT 格式的记录自带列偏移，不需要
+#[derive(Debug, Clone, Default)]
+pub struct LogIndex {
+    pub n_uniq: usize,
+    pub fields: Vec<LogField>,
+    /// 表有 INSTANT ADD/DROP 产生的行版本，记录中各列按物理位置排列
+    pub versioned: bool,
+}
+
+/// MySQL 8.0.28 之后索引信息中的标志
+const INDEX_FLAG_COMPACT: u8 = 0x01;
+const INDEX_FLAG_VERSION: u8 = 0x02;
+const INDEX_FLAG_INSTANT: u8 = 0x04;
+
+impl LogIndex {
+    /// mlog_parse_index，MySQL 8.0.28 之后的格式
+    ///
+    /// 名称   |长度|描述
+    /// ------|:------|:------
+    /// version |1| 索引信息格式的版本
+    /// flag |1| COMPACT、VERSION、INSTANT 标志
+    /// n |2| 字段数，COMPACT 或 VERSION 时才有以下内容
+    /// n_inst_cols |2| INSTANT 时才有，INSTANT ADD 之前的列数
+    /// n_uniq |2| 唯一确定记录的字段数
+    /// fields |2*n| 每个字段的长度，同 mlog_parse_index_8027
+    /// versioned_fields |可变| VERSION 时才有：个数(2字节)，每个字段的序号、物理位置(压缩)，添加、删除时的行版本(各1字节)
+    pub fn parse(reader: &mut Reader) -> io::Result<LogIndex> {
+        reader.u8()?;
+        let flag = reader.u8()?;
+        if flag & (INDEX_FLAG_COMPACT | INDEX_FLAG_VERSION) == 0 {
+            return Ok(Self::default());
+        }
+        let versioned = flag & INDEX_FLAG_VERSION != 0;
+        let n = reader.u16()?;
+        if flag & INDEX_FLAG_INSTANT != 0 {
+            reader.u16()?;
+        }
+        let n_uniq = reader.u16()? as usize;
+        let fields = (0..n)
+            .map(|_| Ok(LogField::new(reader.u16()?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        if versioned {
+            let n_versioned = reader.u16()?;
+            for _ in 0..n_versioned {
+                reader.compressed()?;
+                reader.compressed()?;
+                reader.take(2)?;
+            }
+        }
+        Ok(Self {
+            n_uniq,
+            fields,
+            versioned,
+        })
+    }
+    /// mlog_parse_index_8027，MySQL 8.0.28 之前的格式
+    pub fn parse_8027(reader: &mut Reader) -> io::Result<LogIndex> {
+        let mut n = reader.u16()?;
+        if n & 0x8000 != 0 {
+            reader.u16()?;
+            n &= 0x7FFF;
+        }
+        let n_uniq = reader.u16()? as usize;
+        let fields = (0..n)
+            .map(|_| Ok(LogField::new(reader.u16()?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        Ok(Self {
+            n_uniq,
+            fields,
+            versioned: false,
+        })
+    }
+}
+
+/// 记录的头部长度和每列相对记录原点的 (偏移, 长度, 是否为NULL)
+#[derive(Debug)]
+pub struct RecOffsets {
+    pub extra: usize,
+    pub fields: Vec<(usize, usize, bool)>,
+}
+
+impl RecOffsets {
+    /// 数据部分长度
+    pub fn data_size(&self) -> usize {
+        self.fields
+            .last()
+            .map(|(start, len, _)| start + len)
+            .unwrap_or(0)
+    }
+    /// 记录总长度
+    pub fn size(&self) -> usize {
+        self.extra + self.data_size()
+    }
+}
+
+/// 在内存中修改索引页，对应 page0cur.cc 和 page0page.cc 中恢复时用到的操作
+pub struct IndexPageMut<'a> {
+    pub buf: &'a mut [u8],
+}
+
+impl<'a> IndexPageMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf }
+    }
+    fn get16(&self, offset: usize) -> u16 {
+        u16::from_be_bytes(self.buf[offset..offset + 2].try_into().unwrap())
+    }
+    fn set16(&mut self, offset: usize, value: u16) {
+        self.buf[offset..offset + 2].copy_from_slice(&value.to_be_bytes());
+    }
+    fn check(&self, rec: usize) -> io::Result<usize> {
+        if rec < PAGE_DATA || rec >= self.buf.len() - FIL_PAGE_DATA_END {
+            return Err(io::Error::other(format!("invalid record offset {}", rec)));
+        }
+        Ok(rec)
+    }
+    pub fn is_comp(&self) -> bool {
+        self.get16(PAGE_N_HEAP) & 0x8000 != 0
+    }
+    pub fn infimum(&self) -> usize {
+        if self.is_comp() {
+            PAGE_DATA + 5
+        } else {
+            PAGE_DATA + 1 + 6
+        }
+    }
+    pub fn supremum(&self) -> usize {
+        if self.is_comp() {
+            PAGE_DATA + 2 * 5 + 8
+        } else {
+            PAGE_DATA + 2 + 2 * 6 + 8
+        }
+    }
+    fn supremum_end(&self) -> usize {
+        if self.is_comp() {
+            self.supremum() + 8
+        } else {
+            self.supremum() + 9
+        }
+    }
+    pub fn next(&self, rec: usize) -> usize {
+        let next = self.get16(rec - 2) as usize;
+        if self.is_comp() && next != 0 {
+            (rec + next) & 0xFFFF
+        } else {
+            next
+        }
+    }
+    fn set_next(&mut self, rec: usize, next: usize) {
+        let value = if self.is_comp() && next != 0 {
+            next.wrapping_sub(rec) as u16
+        } else {
+            next as u16
+        };
+        self.set16(rec - 2, value);
+    }
+    fn info_byte(&self, rec: usize) -> usize {
+        if self.is_comp() {
+            rec - 5
+        } else {
+            rec - 6
+        }
+    }
+    pub fn n_owned(&self, rec: usize) -> u8 {
+        self.buf[self.info_byte(rec)] & 0x0F
+    }
+    fn set_n_owned(&mut self, rec: usize, n_owned: u8) {
+        let offset = self.info_byte(rec);
+        self.buf[offset] = self.buf[offset] & 0xF0 | n_owned & 0x0F;
+    }
+    pub fn info_bits(&self, rec: usize) -> u8 {
+        self.buf[self.info_byte(rec)] & 0xF0
+    }
+    pub fn set_info_bits(&mut self, rec: usize, info_bits: u8) {
+        let offset = self.info_byte(rec);
+        self.buf[offset] = self.buf[offset] & 0x0F | info_bits & 0xF0;
+    }
+    pub fn set_deleted(&mut self, rec: usize, deleted: bool) {
+        let info_bits = self.info_bits(rec) & !REC_INFO_DELETED_FLAG;
+        let flag = if deleted { REC_INFO_DELETED_FLAG } else { 0 };
+        self.set_info_bits(rec, info_bits | flag);
+    }
+    pub fn set_min_rec_mark(&mut self, rec: usize) {
+        let info_bits = self.info_bits(rec);
+        self.set_info_bits(rec, info_bits | REC_INFO_MIN_REC_FLAG);
+    }
+    fn status(&self, rec: usize) -> u8 {
+        self.buf[rec - 3] & 0x07
+    }
+    fn set_heap_no(&mut self, rec: usize, heap_no: u16) {
+        if self.is_comp() {
+            let value = heap_no << 3 | self.get16(rec - 4) & 0x07;
+            self.set16(rec - 4, value);
+        } else {
+            let value = heap_no << 3 | self.get16(rec - 5) & 0x07;
+            self.set16(rec - 5, value);
+        }
+    }
+    fn heap_no(&self, rec: usize) -> u16 {
+        if self.is_comp() {
+            self.get16(rec - 4) >> 3
+        } else {
+            self.get16(rec - 5) >> 3
+        }
+    }
+    /// rec_get_offsets
+    pub fn rec_offsets(&self, rec: usize, index: &LogIndex) -> io::Result<RecOffsets> {
+        self.check(rec)?;
+        if self.is_comp() {
+            self.rec_offsets_new(rec, index)
+        } else {
+            self.rec_offsets_old(rec)
+        }
+    }
+    fn rec_offsets_old(&self, rec: usize) -> io::Result<RecOffsets> {
+        let bits = (self.buf[rec - 5] as u32) << 16 | self.get16(rec - 4) as u32;
+        let n_fields = (bits >> 1 & 0x3FF) as usize;
+        let one_byte = bits & 1 == 1;
+        let width = if one_byte { 1 } else { 2 };
+        let extra = 6 + n_fields * width;
+        if extra > rec {
+            return Err(io::Error::other(format!("invalid record at {}", rec)));
+        }
+        let mut fields = Vec::with_capacity(n_fields);
+        let mut start = 0;
+        for i in 0..n_fields {
+            let pos = rec - 6 - (i + 1) * width;
+            let (end, is_null) = if one_byte {
+                let value = self.buf[pos];
+                ((value & 0x7F) as usize, value & 0x80 != 0)
+            } else {
+                let value = self.get16(pos);
+                ((value & 0x3FFF) as usize, value & 0x8000 != 0)
+            };
+            fields.push((start, end.saturating_sub(start), is_null));
+            start = end.max(start);
+        }
+        Ok(RecOffsets { extra, fields })
+    }
+    fn rec_offsets_new(&self, rec: usize, index: &LogIndex) -> io::Result<RecOffsets> {
+        let status = self.status(rec);
+        if status == REC_STATUS_INFIMUM || status == REC_STATUS_SUPREMUM {
+            return Ok(RecOffsets {
+                extra: 5,
+                fields: vec![(0, 8, false)],
+            });
+        }
+        if index.fields.is_empty() {
+            return Err(io::Error::other("no index information in redo record"));
+        }
+        let node_ptr = status == REC_STATUS_NODE_PTR;
+        let fields = if node_ptr {
+            &index.fields[..index.n_uniq.min(index.fields.len())]
+        } else {
+            &index.fields[..]
+        };
+        // 空值列表的长度由索引中所有可为空的列决定
+        let n_nullable = index.fields.iter().filter(|field| field.nullable).count();
+        let mut nulls = rec - 5 - 1;
+        let mut lens = nulls - n_nullable.div_ceil(8);
+        let mut null_mask = 1u8;
+        let mut offsets = Vec::with_capacity(fields.len() + 1);
+        let mut start = 0;
+        for field in fields {
+            if field.nullable {
+                let is_null = self.buf[nulls] & null_mask != 0;
+                null_mask = null_mask.wrapping_shl(1);
+                if null_mask == 0 {
+                    nulls -= 1;
+                    null_mask = 1;
+                }
+                if is_null {
+                    offsets.push((start, 0, true));
+                    continue;
+                }
+            }
+            let len = if field.fixed_len != 0 {
+                field.fixed_len
+            } else {
+                let mut len = self.buf[lens] as usize;
+                lens -= 1;
+                if field.big && len & 0x80 != 0 {
+                    len = (len & 0x3F) << 8 | self.buf[lens] as usize;
+                    lens -= 1;
+                }
+                len
+            };
+            offsets.push((start, len, false));
+            start += len;
+        }
+        if node_ptr {
+            offsets.push((start, 4, false));
+        }
+        Ok(RecOffsets {
+            extra: rec - lens - 1,
+            fields: offsets,
+        })
+    }
+    fn n_slots(&self) -> usize {
+        self.get16(PAGE_N_DIR_SLOTS) as usize
+    }
+    fn slot_pos(&self, slot_no: usize) -> usize {
+        self.buf.len() - FIL_PAGE_DATA_END - 2 * (slot_no + 1)
+    }
+    fn slot(&self, slot_no: usize) -> usize {
+        self.get16(self.slot_pos(slot_no)) as usize
+    }
+    fn set_slot(&mut self, slot_no: usize, rec: usize) {
+        let pos = self.slot_pos(slot_no);
+        self.set16(pos, rec as u16);
+    }
+    /// page_dir_find_owner_slot
+    fn find_owner_slot(&self, rec: usize) -> io::Result<usize> {
+        let owner = self.find_owner_rec(rec)?;
+        (0..self.n_slots())
+            .rev()
+            .find(|slot_no| self.slot(*slot_no) == owner)
+            .ok_or_else(|| io::Error::other(format!("no directory slot owns {}", rec)))
+    }
+    /// page_rec_find_owner_rec
+    fn find_owner_rec(&self, mut rec: usize) -> io::Result<usize> {
+        for _ in 0..self.buf.len() {
+            if self.n_owned(rec) != 0 {
+                return Ok(rec);
+            }
+            rec = self.check(self.next(rec))?;
+        }
+        Err(io::Error::other("record list is corrupted"))
+    }
+    /// page_rec_get_prev
+    fn prev(&self, rec: usize) -> io::Result<usize> {
+        let slot_no = self.find_owner_slot(rec)?;
+        let mut prev = if slot_no == 0 {
+            self.infimum()
+        } else {
+            self.slot(slot_no - 1)
+        };
+        for _ in 0..self.buf.len() {
+            let next = self.check(self.next(prev))?;
+            if next == rec {
+                return Ok(prev);
+            }
+            prev = next;
+        }
+        Err(io::Error::other("record list is corrupted"))
+    }
+    /// page_dir_add_slot：在 start 之后增加一个槽位
+    fn add_slot(&mut self, start: usize) {
+        let n_slots = self.n_slots();
+        self.set16(PAGE_N_DIR_SLOTS, n_slots as u16 + 1);
+        for slot_no in (start + 1..n_slots).rev() {
+            let rec = self.slot(slot_no);
+            self.set_slot(slot_no + 1, rec);
+        }
+    }
+    /// page_dir_split_slot
+    fn split_slot(&mut self, slot_no: usize) -> io::Result<()> {
+        let n_owned = self.n_owned(self.slot(slot_no));
+        let mut rec = self.slot(slot_no - 1);
+        for _ in 0..n_owned / 2 {
+            rec = self.check(self.next(rec))?;
+        }
+        self.add_slot(slot_no - 1);
+        self.set_slot(slot_no, rec);
+        self.set_n_owned(rec, n_owned / 2);
+        let owner = self.slot(slot_no + 1);
+        self.set_n_owned(owner, n_owned - n_owned / 2);
+        Ok(())
+    }
+    /// page_dir_delete_slot
+    fn delete_slot(&mut self, slot_no: usize) {
+        let rec = self.slot(slot_no);
+        let n_owned = self.n_owned(rec);
+        self.set_n_owned(rec, 0);
+        let up = self.slot(slot_no + 1);
+        let up_n_owned = self.n_owned(up);
+        self.set_n_owned(up, n_owned + up_n_owned);
+        let n_slots = self.n_slots();
+        for i in slot_no + 1..n_slots {
+            let rec = self.slot(i);
+            self.set_slot(i - 1, rec);
+        }
+        self.set_slot(n_slots - 1, 0);
+        self.set16(PAGE_N_DIR_SLOTS, n_slots as u16 - 1);
+    }
+    /// page_dir_balance_slot
+    fn balance_slot(&mut self, slot_no: usize) -> io::Result<()> {
+        if slot_no == 0 || slot_no + 1 >= self.n_slots() {
+            return Ok(());
+        }
+        let rec = self.slot(slot_no);
+        let up = self.slot(slot_no + 1);
+        let n_owned = self.n_owned(rec);
+        let up_n_owned = self.n_owned(up);
+        if up_n_owned > PAGE_DIR_SLOT_MIN_N_OWNED {
+            let new_rec = self.check(self.next(rec))?;
+            self.set_n_owned(rec, 0);
+            self.set_n_owned(new_rec, n_owned + 1);
+            self.set_slot(slot_no, new_rec);
+            self.set_n_owned(up, up_n_owned - 1);
+        } else {
+            self.delete_slot(slot_no);
+        }
+        Ok(())
+    }
+    /// page_cur_insert_rec_low：把 rec(头部长度为 extra)插入到 cursor 之后，返回新记录的位置
+    pub fn insert_rec(
+        &mut self,
+        cursor: usize,
+        rec: &[u8],
+        extra: usize,
+        index: &LogIndex,
+    ) -> io::Result<usize> {
+        let rec_size = rec.len();
+        let free = self.get16(PAGE_FREE) as usize;
+        // 优先使用空闲链表头部的空间
+        let reuse = if free != 0 {
+            let offsets = self.rec_offsets(free, index)?;
+            (offsets.size() >= rec_size).then_some((free, offsets.extra))
+        } else {
+            None
+        };
+        let (insert_buf, heap_no) = match reuse {
+            Some((free, free_extra)) => {
+                let heap_no = self.heap_no(free);
+                let next = self.next(free);
+                self.set16(PAGE_FREE, next as u16);
+                let garbage = self.get16(PAGE_GARBAGE);
+                self.set16(PAGE_GARBAGE, garbage.saturating_sub(rec_size as u16));
+                (free - free_extra, heap_no)
+            }
+            None => {
+                let heap_top = self.get16(PAGE_HEAP_TOP) as usize;
+                let n_heap = self.get16(PAGE_N_HEAP);
+                let dir_start = self.slot_pos(self.n_slots() - 1);
+                if heap_top + rec_size > dir_start {
+                    return Err(io::Error::other("page is full"));
+                }
+                self.set16(PAGE_HEAP_TOP, (heap_top + rec_size) as u16);
+                self.set16(PAGE_N_HEAP, n_heap + 1);
+                (heap_top, n_heap & 0x7FFF)
+            }
+        };
+        self.buf[insert_buf..insert_buf + rec_size].copy_from_slice(rec);
+        let insert_rec = insert_buf + extra;
+        let next = self.next(cursor);
+        self.set_next(insert_rec, next);
+        self.set_next(cursor, insert_rec);
+        let n_recs = self.get16(PAGE_N_RECS);
+        self.set16(PAGE_N_RECS, n_recs + 1);
+        self.set_n_owned(insert_rec, 0);
+        self.set_heap_no(insert_rec, heap_no);
+
+        let last_insert = self.get16(PAGE_LAST_INSERT) as usize;
+        let direction = self.get16(PAGE_DIRECTION);
+        let n_direction = self.get16(PAGE_N_DIRECTION);
+        if last_insert != 0 && last_insert == cursor && direction != PAGE_LEFT {
+            self.set16(PAGE_DIRECTION, PAGE_RIGHT);
+            self.set16(PAGE_N_DIRECTION, n_direction + 1);
+        } else if last_insert != 0 && next == last_insert && direction != PAGE_RIGHT {
+            self.set16(PAGE_DIRECTION, PAGE_LEFT);
+            self.set16(PAGE_N_DIRECTION, n_direction + 1);
+        } else {
+            self.set16(PAGE_DIRECTION, PAGE_NO_DIRECTION);
+            self.set16(PAGE_N_DIRECTION, 0);
+        }
+        self.set16(PAGE_LAST_INSERT, insert_rec as u16);
+
+        let owner = self.find_owner_rec(insert_rec)?;
+        let n_owned = self.n_owned(owner);
+        self.set_n_owned(owner, n_owned + 1);
+        if n_owned == PAGE_DIR_SLOT_MAX_N_OWNED {
+            let slot_no = self.find_owner_slot(owner)?;
+            self.split_slot(slot_no)?;
+        }
+        Ok(insert_rec)
+    }
+    /// page_cur_delete_rec
+    pub fn delete_rec(&mut self, rec: usize, index: &LogIndex) -> io::Result<()> {
+        let size = self.rec_offsets(rec, index)?.size();
+        let slot_no = self.find_owner_slot(rec)?;
+        let owner = self.slot(slot_no);
+        let n_owned = self.n_owned(owner);
+        let prev = self.prev(rec)?;
+        let next = self.next(rec);
+        self.set16(PAGE_LAST_INSERT, 0);
+        self.set_next(prev, next);
+        let owner = if owner == rec {
+            self.set_slot(slot_no, prev);
+            prev
+        } else {
+            owner
+        };
+        self.set_n_owned(owner, n_owned - 1);
+        // page_mem_free：放到空闲链表头部
+        let free = self.get16(PAGE_FREE) as usize;
+        self.set_next(rec, free);
+        self.set16(PAGE_FREE, rec as u16);
+        let garbage = self.get16(PAGE_GARBAGE);
+        self.set16(PAGE_GARBAGE, garbage + size as u16);
+        let n_recs = self.get16(PAGE_N_RECS);
+        self.set16(PAGE_N_RECS, n_recs.saturating_sub(1));
+        if n_owned <= PAGE_DIR_SLOT_MIN_N_OWNED {
+            self.balance_slot(slot_no)?;
+        }
+        Ok(())
+    }
+    /// page_delete_rec_list_end：删除 rec 及之后的所有记录
+    pub fn delete_rec_list_end(&mut self, mut rec: usize, index: &LogIndex) -> io::Result<()> {
+        let supremum = self.supremum();
+        if rec == supremum {
+            return Ok(());
+        }
+        if rec == self.infimum() {
+            rec = self.next(rec);
+            if rec == supremum {
+                return Ok(());
+            }
+        }
+        let prev = self.prev(rec)?;
+        let mut last = rec;
+        let mut size = 0;
+        let mut n_recs = 0;
+        let mut cur = rec;
+        while cur != supremum {
+            size += self.rec_offsets(cur, index)?.size();
+            n_recs += 1;
+            last = cur;
+            cur = self.check(self.next(cur))?;
+        }
+        let mut owner = rec;
+        let mut count = 0;
+        while self.n_owned(owner) == 0 {
+            count += 1;
+            owner = self.check(self.next(owner))?;
+        }
+        let n_owned = self.n_owned(owner) - count;
+        let slot_no = self.find_owner_slot(owner)?;
+        self.set_slot(slot_no, supremum);
+        self.set_n_owned(supremum, n_owned);
+        self.set16(PAGE_N_DIR_SLOTS, slot_no as u16 + 1);
+        self.set_next(prev, supremum);
+        let free = self.get16(PAGE_FREE) as usize;
+        self.set_next(last, free);
+        self.set16(PAGE_FREE, rec as u16);
+        let garbage = self.get16(PAGE_GARBAGE);
+        self.set16(PAGE_GARBAGE, garbage + size as u16);
+        let total = self.get16(PAGE_N_RECS);
+        self.set16(PAGE_N_RECS, total.saturating_sub(n_recs));
+        Ok(())
+    }
+    /// page_delete_rec_list_start：删除 rec 之前的所有用户记录
+    pub fn delete_rec_list_start(&mut self, rec: usize, index: &LogIndex) -> io::Result<()> {
+        if rec == self.infimum() {
+            return Ok(());
+        }
+        if rec == self.supremum() {
+            self.create_empty();
+            return Ok(());
+        }
+        for _ in 0..self.buf.len() {
+            let first = self.next(self.infimum());
+            if first == rec || first == self.supremum() {
+                return Ok(());
+            }
+            self.delete_rec(first, index)?;
+        }
+        Err(io::Error::other("record list is corrupted"))
+    }
+    /// supremum 之前的最后一条记录
+    pub fn last_rec(&self) -> io::Result<usize> {
+        let supremum = self.supremum();
+        if self.n_slots() == 0 {
+            return Err(io::Error::other("page directory is empty"));
+        }
+        self.prev(supremum)
+    }
+    pub fn reset_last_insert(&mut self) {
+        self.set16(PAGE_LAST_INSERT, 0);
+        self.set16(PAGE_DIRECTION, PAGE_NO_DIRECTION);
+        self.set16(PAGE_N_DIRECTION, 0);
+    }
+    /// rec_set_nth_field_sql_null：只有 REDUNDANT 格式可以原地把列改为NULL
+    pub fn set_field_null(&mut self, rec: usize, n: usize, index: &LogIndex) -> io::Result<()> {
+        if self.is_comp() {
+            return Err(io::Error::other("cannot set field to NULL in place"));
+        }
+        let offsets = self.rec_offsets(rec, index)?;
+        let (start, len, _) = offsets.fields[n];
+        self.buf[rec + start..rec + start + len].fill(0);
+        if offsets.extra == 6 + offsets.fields.len() {
+            self.buf[rec - 6 - (n + 1)] |= 0x80;
+        } else {
+            let pos = rec - 6 - (n + 1) * 2;
+            self.buf[pos] |= 0x80;
+        }
+        Ok(())
+    }
+    /// page_create_low
+    pub fn create(&mut self, comp: bool, page_type: u16) {
+        self.buf[24..26].copy_from_slice(&page_type.to_be_bytes());
+        let end = self.buf.len() - FIL_PAGE_DATA_END;
+        self.buf[PAGE_HEADER..end].fill(0);
+        let heap_top = if comp {
+            self.buf[PAGE_DATA..PAGE_DATA + INFIMUM_SUPREMUM_COMPACT.len()]
+                .copy_from_slice(&INFIMUM_SUPREMUM_COMPACT);
+            PAGE_DATA + INFIMUM_SUPREMUM_COMPACT.len()
+        } else {
+            self.buf[PAGE_DATA..PAGE_DATA + INFIMUM_SUPREMUM_REDUNDANT.len()]
+                .copy_from_slice(&INFIMUM_SUPREMUM_REDUNDANT);
+            PAGE_DATA + INFIMUM_SUPREMUM_REDUNDANT.len()
+        };
+        self.set16(PAGE_N_DIR_SLOTS, 2);
+        self.set16(PAGE_HEAP_TOP, heap_top as u16);
+        self.set16(PAGE_N_HEAP, if comp { 0x8002 } else { 2 });
+        self.set16(PAGE_DIRECTION, PAGE_NO_DIRECTION);
+        let (infimum, supremum) = (self.infimum(), self.supremum());
+        self.set_slot(0, infimum);
+        self.set_slot(1, supremum);
+        debug_assert_eq!(heap_top, self.supremum_end());
+    }
+    /// page_create_empty：保留索引ID、层级和 PAGE_MAX_TRX_ID
+    pub fn create_empty(&mut self) {
+        let header = self.buf[PAGE_MAX_TRX_ID..PAGE_INDEX_ID + 8].to_vec();
+        let page_type = self.get16(24);
+        let comp = self.is_comp();
+        self.create(comp, page_type);
+        self.buf[PAGE_MAX_TRX_ID..PAGE_INDEX_ID + 8].copy_from_slice(&header);
+    }
+    /// btr_page_reorganize_low：按顺序重新插入所有记录，回收空闲空间
+    pub fn reorganize(&mut self, index: &LogIndex) -> io::Result<()> {
+        let mut records = Vec::new();
+        let supremum = self.supremum();
+        let mut rec = self.next(self.infimum());
+        while rec != supremum {
+            let offsets = self.rec_offsets(rec, index)?;
+            let start = rec - offsets.extra;
+            records.push((
+                self.buf[start..start + offsets.size()].to_vec(),
+                offsets.extra,
+            ));
+            rec = self.check(self.next(rec))?;
+        }
+        let level = self.get16(PAGE_LEVEL);
+        self.create_empty();
+        self.set16(PAGE_LEVEL, level);
+        let mut cursor = self.infimum();
+        for (rec, extra) in records {
+            cursor = self.insert_rec(cursor, &rec, extra, index)?;
+        }
+        self.set16(PAGE_LAST_INSERT, 0);
+        Ok(())
+    }
+    /// page_cur_parse_insert_rec：根据游标记录和日志中的结尾部分构造新记录并插入
+    pub fn parse_insert_rec(
+        &mut self,
+        reader: &mut Reader,
+        cursor: usize,
+        index: &LogIndex,
+    ) -> io::Result<usize> {
+        self.check(cursor)?;
+        let end_seg_len = reader.compressed()? as usize;
+        let (info_and_status_bits, origin_offset, mismatch_index) = if end_seg_len & 1 != 0 {
+            (
+                reader.u8()?,
+                reader.compressed()? as usize,
+                reader.compressed()? as usize,
+            )
+        } else {
+            let offsets = self.rec_offsets(cursor, index)?;
+            let bits = if self.is_comp() {
+                self.info_bits(cursor) | self.status(cursor)
+            } else {
+                self.info_bits(cursor)
+            };
+            (bits, offsets.extra, offsets.size() - (end_seg_len >> 1))
+        };
+        let end_seg = reader.take(end_seg_len >> 1)?;
+        let cursor_offsets = self.rec_offsets(cursor, index)?;
+        let cursor_start = cursor - cursor_offsets.extra;
+        let mut rec = Vec::with_capacity(mismatch_index + end_seg.len());
+        rec.extend_from_slice(
+            self.buf
+                .get(cursor_start..cursor_start + mismatch_index)
+                .ok_or_else(|| io::Error::other("invalid mismatch index"))?,
+        );
+        rec.extend_from_slice(end_seg);
+        if origin_offset < 6 || origin_offset > rec.len() {
+            return Err(io::Error::other("invalid origin offset"));
+        }
+        // 设置新记录的 info bits(COMPACT 还有记录类型)
+        if self.is_comp() {
+            let info = origin_offset - 5;
+            rec[info] = rec[info] & 0x0F | info_and_status_bits & 0xF0;
+            let status = origin_offset - 3;
+            rec[status] = rec[status] & 0xF8 | info_and_status_bits & 0x07;
+        } else {
+            let info = origin_offset - 6;
+            rec[info] = rec[info] & 0x0F | info_and_status_bits & 0xF0;
+        }
+        self.insert_rec(cursor, &rec, origin_offset, index)
+    }
+}
@@ -0,0 +1,494 @@
+use crate::mach::Reader;
+use crate::redo::page_cur::LogIndex;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// 类型的最高位表示mtr只有这一条日志
+pub const MLOG_SINGLE_REC_FLAG: u8 = 128;
+
+// MySQL 8.0.28 起带索引信息的日志改用 67~76 的新类型，索引信息中增加了版本和行版本的字段，
+// 之前的类型加 _8027 后缀，恢复时仍能解析
+pub const MLOG_1BYTE: u8 = 1;
+pub const MLOG_2BYTES: u8 = 2;
+pub const MLOG_4BYTES: u8 = 4;
+pub const MLOG_8BYTES: u8 = 8;
+pub const MLOG_REC_INSERT_8027: u8 = 9;
+pub const MLOG_REC_CLUST_DELETE_MARK_8027: u8 = 10;
+pub const MLOG_REC_SEC_DELETE_MARK: u8 = 11;
+pub const MLOG_REC_UPDATE_IN_PLACE_8027: u8 = 13;
+pub const MLOG_REC_DELETE_8027: u8 = 14;
+pub const MLOG_LIST_END_DELETE_8027: u8 = 15;
+pub const MLOG_LIST_START_DELETE_8027: u8 = 16;
+pub const MLOG_LIST_END_COPY_CREATED_8027: u8 = 17;
+pub const MLOG_PAGE_REORGANIZE_8027: u8 = 18;
+pub const MLOG_PAGE_CREATE: u8 = 19;
+pub const MLOG_UNDO_INSERT: u8 = 20;
+pub const MLOG_UNDO_ERASE_END: u8 = 21;
+pub const MLOG_UNDO_INIT: u8 = 22;
+pub const MLOG_UNDO_HDR_DISCARD: u8 = 23;
+pub const MLOG_UNDO_HDR_REUSE: u8 = 24;
+pub const MLOG_UNDO_HDR_CREATE: u8 = 25;
+pub const MLOG_REC_MIN_MARK: u8 = 26;
+pub const MLOG_IBUF_BITMAP_INIT: u8 = 27;
+pub const MLOG_INIT_FILE_PAGE: u8 = 29;
+pub const MLOG_WRITE_STRING: u8 = 30;
+pub const MLOG_MULTI_REC_END: u8 = 31;
+pub const MLOG_DUMMY_RECORD: u8 = 32;
+pub const MLOG_FILE_CREATE: u8 = 33;
+pub const MLOG_FILE_RENAME: u8 = 34;
+pub const MLOG_FILE_DELETE: u8 = 35;
+pub const MLOG_COMP_REC_MIN_MARK: u8 = 36;
+pub const MLOG_COMP_PAGE_CREATE: u8 = 37;
+pub const MLOG_COMP_REC_INSERT_8027: u8 = 38;
+pub const MLOG_COMP_REC_CLUST_DELETE_MARK_8027: u8 = 39;
+pub const MLOG_COMP_REC_SEC_DELETE_MARK: u8 = 40;
+pub const MLOG_COMP_REC_UPDATE_IN_PLACE_8027: u8 = 41;
+pub const MLOG_COMP_REC_DELETE_8027: u8 = 42;
+pub const MLOG_COMP_LIST_END_DELETE_8027: u8 = 43;
+pub const MLOG_COMP_LIST_START_DELETE_8027: u8 = 44;
+pub const MLOG_COMP_LIST_END_COPY_CREATED_8027: u8 = 45;
+pub const MLOG_COMP_PAGE_REORGANIZE_8027: u8 = 46;
+pub const MLOG_FILE_CREATE2: u8 = 47;
+pub const MLOG_ZIP_WRITE_NODE_PTR: u8 = 48;
+pub const MLOG_ZIP_WRITE_BLOB_PTR: u8 = 49;
+pub const MLOG_ZIP_WRITE_HEADER: u8 = 50;
+pub const MLOG_ZIP_PAGE_COMPRESS: u8 = 51;
+pub const MLOG_ZIP_PAGE_COMPRESS_NO_DATA_8027: u8 = 52;
+pub const MLOG_ZIP_PAGE_REORGANIZE_8027: u8 = 53;
+pub const MLOG_FILE_NAME: u8 = 54;
+pub const MLOG_CHECKPOINT: u8 = 56;
+pub const MLOG_PAGE_CREATE_RTREE: u8 = 57;
+pub const MLOG_COMP_PAGE_CREATE_RTREE: u8 = 58;
+pub const MLOG_INIT_FILE_PAGE2: u8 = 59;
+pub const MLOG_TRUNCATE: u8 = 60;
+pub const MLOG_INDEX_LOAD: u8 = 61;
+pub const MLOG_TABLE_DYNAMIC_META: u8 = 62;
+pub const MLOG_PAGE_CREATE_SDI: u8 = 63;
+pub const MLOG_COMP_PAGE_CREATE_SDI: u8 = 64;
+pub const MLOG_FILE_EXTEND: u8 = 65;
+pub const MLOG_REC_INSERT: u8 = 67;
+pub const MLOG_REC_CLUST_DELETE_MARK: u8 = 68;
+pub const MLOG_REC_DELETE: u8 = 69;
+pub const MLOG_REC_UPDATE_IN_PLACE: u8 = 70;
+pub const MLOG_LIST_END_COPY_CREATED: u8 = 71;
+pub const MLOG_PAGE_REORGANIZE: u8 = 72;
+pub const MLOG_ZIP_PAGE_REORGANIZE: u8 = 73;
+pub const MLOG_ZIP_PAGE_COMPRESS_NO_DATA: u8 = 74;
+pub const MLOG_LIST_END_DELETE: u8 = 75;
+pub const MLOG_LIST_START_DELETE: u8 = 76;
+
+/// 长度为 UNIV_SQL_NULL 表示 NULL
+const UNIV_SQL_NULL: u32 = u32::MAX;
+
+pub fn mlog_type_name(rec_type: u8) -> &'static str {
+    match rec_type {
+        MLOG_1BYTE => "MLOG_1BYTE",
+        MLOG_2BYTES => "MLOG_2BYTES",
+        MLOG_4BYTES => "MLOG_4BYTES",
+        MLOG_8BYTES => "MLOG_8BYTES",
+        MLOG_REC_INSERT_8027 => "MLOG_REC_INSERT_8027",
+        MLOG_REC_CLUST_DELETE_MARK_8027 => "MLOG_REC_CLUST_DELETE_MARK_8027",
+        MLOG_REC_SEC_DELETE_MARK => "MLOG_REC_SEC_DELETE_MARK",
+        MLOG_REC_UPDATE_IN_PLACE_8027 => "MLOG_REC_UPDATE_IN_PLACE_8027",
+        MLOG_REC_DELETE_8027 => "MLOG_REC_DELETE_8027",
+        MLOG_LIST_END_DELETE_8027 => "MLOG_LIST_END_DELETE_8027",
+        MLOG_LIST_START_DELETE_8027 => "MLOG_LIST_START_DELETE_8027",
+        MLOG_LIST_END_COPY_CREATED_8027 => "MLOG_LIST_END_COPY_CREATED_8027",
+        MLOG_PAGE_REORGANIZE_8027 => "MLOG_PAGE_REORGANIZE_8027",
+        MLOG_PAGE_CREATE => "MLOG_PAGE_CREATE",
+        MLOG_UNDO_INSERT => "MLOG_UNDO_INSERT",
+        MLOG_UNDO_ERASE_END => "MLOG_UNDO_ERASE_END",
+        MLOG_UNDO_INIT => "MLOG_UNDO_INIT",
+        MLOG_UNDO_HDR_DISCARD => "MLOG_UNDO_HDR_DISCARD",
+        MLOG_UNDO_HDR_REUSE => "MLOG_UNDO_HDR_REUSE",
+        MLOG_UNDO_HDR_CREATE => "MLOG_UNDO_HDR_CREATE",
+        MLOG_REC_MIN_MARK => "MLOG_REC_MIN_MARK",
+        MLOG_IBUF_BITMAP_INIT => "MLOG_IBUF_BITMAP_INIT",
+        MLOG_INIT_FILE_PAGE => "MLOG_INIT_FILE_PAGE",
+        MLOG_WRITE_STRING => "MLOG_WRITE_STRING",
+        MLOG_MULTI_REC_END => "MLOG_MULTI_REC_END",
+        MLOG_DUMMY_RECORD => "MLOG_DUMMY_RECORD",
+        MLOG_FILE_CREATE => "MLOG_FILE_CREATE",
+        MLOG_FILE_RENAME => "MLOG_FILE_RENAME",
+        MLOG_FILE_DELETE => "MLOG_FILE_DELETE",
+        MLOG_COMP_REC_MIN_MARK => "MLOG_COMP_REC_MIN_MARK",
+        MLOG_COMP_PAGE_CREATE => "MLOG_COMP_PAGE_CREATE",
+        MLOG_COMP_REC_INSERT_8027 => "MLOG_COMP_REC_INSERT_8027",
+        MLOG_COMP_REC_CLUST_DELETE_MARK_8027 => "MLOG_COMP_REC_CLUST_DELETE_MARK_8027",
+        MLOG_COMP_REC_SEC_DELETE_MARK => "MLOG_COMP_REC_SEC_DELETE_MARK",
+        MLOG_COMP_REC_UPDATE_IN_PLACE_8027 => "MLOG_COMP_REC_UPDATE_IN_PLACE_8027",
+        MLOG_COMP_REC_DELETE_8027 => "MLOG_COMP_REC_DELETE_8027",
+        MLOG_COMP_LIST_END_DELETE_8027 => "MLOG_COMP_LIST_END_DELETE_8027",
+        MLOG_COMP_LIST_START_DELETE_8027 => "MLOG_COMP_LIST_START_DELETE_8027",
+        MLOG_COMP_LIST_END_COPY_CREATED_8027 => "MLOG_COMP_LIST_END_COPY_CREATED_8027",
+        MLOG_COMP_PAGE_REORGANIZE_8027 => "MLOG_COMP_PAGE_REORGANIZE_8027",
+        MLOG_FILE_CREATE2 => "MLOG_FILE_CREATE2",
+        MLOG_ZIP_WRITE_NODE_PTR => "MLOG_ZIP_WRITE_NODE_PTR",
+        MLOG_ZIP_WRITE_BLOB_PTR => "MLOG_ZIP_WRITE_BLOB_PTR",
+        MLOG_ZIP_WRITE_HEADER => "MLOG_ZIP_WRITE_HEADER",
+        MLOG_ZIP_PAGE_COMPRESS => "MLOG_ZIP_PAGE_COMPRESS",
+        MLOG_ZIP_PAGE_COMPRESS_NO_DATA_8027 => "MLOG_ZIP_PAGE_COMPRESS_NO_DATA_8027",
+        MLOG_ZIP_PAGE_REORGANIZE_8027 => "MLOG_ZIP_PAGE_REORGANIZE_8027",
+        MLOG_FILE_NAME => "MLOG_FILE_NAME",
+        MLOG_CHECKPOINT => "MLOG_CHECKPOINT",
+        MLOG_PAGE_CREATE_RTREE => "MLOG_PAGE_CREATE_RTREE",
+        MLOG_COMP_PAGE_CREATE_RTREE => "MLOG_COMP_PAGE_CREATE_RTREE",
+        MLOG_INIT_FILE_PAGE2 => "MLOG_INIT_FILE_PAGE2",
+        MLOG_TRUNCATE => "MLOG_TRUNCATE",
+        MLOG_INDEX_LOAD => "MLOG_INDEX_LOAD",
+        MLOG_TABLE_DYNAMIC_META => "MLOG_TABLE_DYNAMIC_META",
+        MLOG_PAGE_CREATE_SDI => "MLOG_PAGE_CREATE_SDI",
+        MLOG_COMP_PAGE_CREATE_SDI => "MLOG_COMP_PAGE_CREATE_SDI",
+        MLOG_FILE_EXTEND => "MLOG_FILE_EXTEND",
+        MLOG_REC_INSERT => "MLOG_REC_INSERT",
+        MLOG_REC_CLUST_DELETE_MARK => "MLOG_REC_CLUST_DELETE_MARK",
+        MLOG_REC_DELETE => "MLOG_REC_DELETE",
+        MLOG_REC_UPDATE_IN_PLACE => "MLOG_REC_UPDATE_IN_PLACE",
+        MLOG_LIST_END_COPY_CREATED => "MLOG_LIST_END_COPY_CREATED",
+        MLOG_PAGE_REORGANIZE => "MLOG_PAGE_REORGANIZE",
+        MLOG_ZIP_PAGE_REORGANIZE => "MLOG_ZIP_PAGE_REORGANIZE",
+        MLOG_ZIP_PAGE_COMPRESS_NO_DATA => "MLOG_ZIP_PAGE_COMPRESS_NO_DATA",
+        MLOG_LIST_END_DELETE => "MLOG_LIST_END_DELETE",
+        MLOG_LIST_START_DELETE => "MLOG_LIST_START_DELETE",
+        _ => "UNKNOWN",
+    }
+}
+
+/// 一条redo日志
+///
+/// 名称   |长度|描述
+/// ------|:------|:------
+/// type |1| 日志类型，最高位为 MLOG_SINGLE_REC_FLAG
+/// space_id |压缩| 表空间ID，MLOG_MULTI_REC_END 等没有
+/// page_no |压缩| 页号，MLOG_MULTI_REC_END 等没有
+/// body |可变| 与类型相关的内容
+#[derive(Debug, Clone)]
+pub struct RedoRecord {
+    /// 日志起始位置的LSN
+    pub lsn: u64,
+    /// 日志结束位置的LSN
+    pub end_lsn: u64,
+    pub rec_type: u8,
+    pub single: bool,
+    pub space_id: u32,
+    pub page_no: u32,
+    pub body: Vec<u8>,
+}
+
+impl RedoRecord {
+    /// 从 pos 处解析一条日志，返回日志和结束位置；无法识别的类型返回错误
+    pub fn new(buf: &[u8], pos: usize) -> io::Result<(RedoRecord, usize)> {
+        let mut reader = Reader::new(buf, pos);
+        let type_byte = reader.u8()?;
+        let rec_type = type_byte & !MLOG_SINGLE_REC_FLAG;
+        let (space_id, page_no) = if has_page(rec_type) {
+            (reader.compressed()?, reader.compressed()?)
+        } else {
+            (0, 0)
+        };
+        let start = reader.pos();
+        parse_body(&mut reader, rec_type)?;
+        let record = RedoRecord {
+            lsn: 0,
+            end_lsn: 0,
+            rec_type,
+            single: type_byte & MLOG_SINGLE_REC_FLAG != 0,
+            space_id,
+            page_no,
+            body: buf[start..reader.pos()].to_vec(),
+        };
+        Ok((record, reader.pos()))
+    }
+    /// 是否作用于某个页
+    pub fn has_page(&self) -> bool {
+        has_page(self.rec_type)
+    }
+    pub fn type_name(&self) -> &'static str {
+        mlog_type_name(self.rec_type)
+    }
+}
+
+impl Display for RedoRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "lsn:{} type:{}", self.lsn, self.type_name())?;
+        if self.single {
+            write!(f, "(single)")?;
+        }
+        if self.has_page() {
+            write!(f, " space_id:{} page_no:{}", self.space_id, self.page_no)?;
+        }
+        if !self.body.is_empty() {
+            write!(f, " len:{} body:", self.body.len())?;
+            // 过长的内容只显示开头
+            if self.body.len() > 64 {
+                write!(f, "{}...", hex::encode(&self.body[..64]))?;
+            } else {
+                write!(f, "{}", hex::encode(&self.body))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn has_page(rec_type: u8) -> bool {
+    !matches!(
+        rec_type,
+        MLOG_MULTI_REC_END
+            | MLOG_DUMMY_RECORD
+            | MLOG_CHECKPOINT
+            | MLOG_TRUNCATE
+            | MLOG_TABLE_DYNAMIC_META
+    )
+}
+
+/// MySQL 8.0.28 之前 COMPACT 格式的日志，内容以 mlog_parse_index_8027 格式的索引信息开头
+pub fn has_index_8027(rec_type: u8) -> bool {
+    matches!(
+        rec_type,
+        MLOG_COMP_REC_INSERT_8027
+            | MLOG_COMP_REC_CLUST_DELETE_MARK_8027
+            | MLOG_COMP_REC_SEC_DELETE_MARK
+            | MLOG_COMP_REC_UPDATE_IN_PLACE_8027
+            | MLOG_COMP_REC_DELETE_8027
+            | MLOG_COMP_LIST_END_DELETE_8027
+            | MLOG_COMP_LIST_START_DELETE_8027
+            | MLOG_COMP_LIST_END_COPY_CREATED_8027
+            | MLOG_COMP_PAGE_REORGANIZE_8027
+            | MLOG_ZIP_PAGE_REORGANIZE_8027
+            | MLOG_ZIP_PAGE_COMPRESS_NO_DATA_8027
+    )
+}
+
+/// MySQL 8.0.28 之后的日志，内容以 mlog_parse_index 格式的索引信息开头
+pub fn has_index(rec_type: u8) -> bool {
+    matches!(
+        rec_type,
+        MLOG_REC_INSERT
+            | MLOG_REC_CLUST_DELETE_MARK
+            | MLOG_REC_DELETE
+            | MLOG_REC_UPDATE_IN_PLACE
+            | MLOG_LIST_END_COPY_CREATED
+            | MLOG_PAGE_REORGANIZE
+            | MLOG_ZIP_PAGE_REORGANIZE
+            | MLOG_ZIP_PAGE_COMPRESS_NO_DATA
+            | MLOG_LIST_END_DELETE
+            | MLOG_LIST_START_DELETE
+    )
+}
+
+/// 按类型跳过日志内容，对应 recv_parse_or_apply_log_rec_body
+fn parse_body(reader: &mut Reader, rec_type: u8) -> io::Result<()> {
+    if has_index_8027(rec_type) {
+        LogIndex::parse_8027(reader)?;
+    } else if has_index(rec_type) {
+        LogIndex::parse(reader)?;
+    }
+    match rec_type {
+        MLOG_1BYTE | MLOG_2BYTES | MLOG_4BYTES => {
+            reader.u16()?;
+            reader.compressed()?;
+        }
+        MLOG_8BYTES => {
+            // mlog_parse_nbytes：8字节的值为压缩的高32位加4字节的低32位
+            reader.u16()?;
+            reader.compressed_u64()?;
+        }
+        MLOG_WRITE_STRING => {
+            reader.u16()?;
+            let len = reader.u16()?;
+            reader.take(len as usize)?;
+        }
+        MLOG_REC_INSERT_8027 | MLOG_COMP_REC_INSERT_8027 | MLOG_REC_INSERT => {
+            reader.u16()?;
+            parse_insert(reader)?;
+        }
+        MLOG_REC_CLUST_DELETE_MARK_8027
+        | MLOG_COMP_REC_CLUST_DELETE_MARK_8027
+        | MLOG_REC_CLUST_DELETE_MARK => {
+            // flags、val、系统列，记录偏移
+            reader.take(2)?;
+            parse_sys_vals(reader)?;
+            reader.u16()?;
+        }
+        MLOG_REC_SEC_DELETE_MARK | MLOG_COMP_REC_SEC_DELETE_MARK => {
+            reader.u8()?;
+            reader.u16()?;
+        }
+        MLOG_REC_UPDATE_IN_PLACE_8027
+        | MLOG_COMP_REC_UPDATE_IN_PLACE_8027
+        | MLOG_REC_UPDATE_IN_PLACE => {
+            reader.u8()?;
+            parse_sys_vals(reader)?;
+            reader.u16()?;
+            parse_update(reader)?;
+        }
+        MLOG_REC_DELETE_8027
+        | MLOG_COMP_REC_DELETE_8027
+        | MLOG_LIST_END_DELETE_8027
+        | MLOG_COMP_LIST_END_DELETE_8027
+        | MLOG_LIST_START_DELETE_8027
+        | MLOG_COMP_LIST_START_DELETE_8027
+        | MLOG_REC_DELETE
+        | MLOG_LIST_END_DELETE
+        | MLOG_LIST_START_DELETE
+        | MLOG_REC_MIN_MARK
+        | MLOG_COMP_REC_MIN_MARK => {
+            reader.u16()?;
+        }
+        MLOG_LIST_END_COPY_CREATED_8027
+        | MLOG_COMP_LIST_END_COPY_CREATED_8027
+        | MLOG_LIST_END_COPY_CREATED => {
+            let len = reader.u32()?;
+            reader.take(len as usize)?;
+        }
+        MLOG_ZIP_PAGE_REORGANIZE_8027
+        | MLOG_ZIP_PAGE_COMPRESS_NO_DATA_8027
+        | MLOG_ZIP_PAGE_REORGANIZE
+        | MLOG_ZIP_PAGE_COMPRESS_NO_DATA => {
+            // 压缩级别
+            reader.u8()?;
+        }
+        MLOG_PAGE_REORGANIZE_8027
+        | MLOG_COMP_PAGE_REORGANIZE_8027
+        | MLOG_PAGE_REORGANIZE
+        | MLOG_PAGE_CREATE
+        | MLOG_COMP_PAGE_CREATE
+        | MLOG_PAGE_CREATE_RTREE
+        | MLOG_COMP_PAGE_CREATE_RTREE
+        | MLOG_PAGE_CREATE_SDI
+        | MLOG_COMP_PAGE_CREATE_SDI
+        | MLOG_UNDO_ERASE_END
+        | MLOG_UNDO_HDR_DISCARD
+        | MLOG_IBUF_BITMAP_INIT
+        | MLOG_INIT_FILE_PAGE
+        | MLOG_INIT_FILE_PAGE2
+        | MLOG_MULTI_REC_END
+        | MLOG_DUMMY_RECORD => {}
+        MLOG_UNDO_INSERT => {
+            let len = reader.u16()?;
+            reader.take(len as usize)?;
+        }
+        MLOG_UNDO_INIT => {
+            reader.compressed()?;
+        }
+        MLOG_UNDO_HDR_REUSE | MLOG_UNDO_HDR_CREATE => {
+            reader.much_compressed_u64()?;
+        }
+        MLOG_FILE_CREATE | MLOG_FILE_CREATE2 => {
+            reader.u32()?;
+            parse_file_name(reader)?;
+        }
+        MLOG_FILE_RENAME => {
+            parse_file_name(reader)?;
+            parse_file_name(reader)?;
+        }
+        MLOG_FILE_DELETE | MLOG_FILE_NAME => {
+            parse_file_name(reader)?;
+        }
+        MLOG_FILE_EXTEND => {
+            // 起始偏移和扩展的大小
+            reader.take(16)?;
+        }
+        MLOG_ZIP_WRITE_NODE_PTR => {
+            reader.take(4 + 4)?;
+        }
+        MLOG_ZIP_WRITE_BLOB_PTR => {
+            reader.take(4 + 20)?;
+        }
+        MLOG_ZIP_WRITE_HEADER => {
+            reader.u8()?;
+            let len = reader.u8()?;
+            reader.take(len as usize)?;
+        }
+        MLOG_ZIP_PAGE_COMPRESS => {
+            let size = reader.u16()? as usize;
+            let trailer_size = reader.u16()? as usize;
+            // FIL_PAGE_PREV 和 FIL_PAGE_NEXT
+            reader.take(8 + size + trailer_size)?;
+        }
+        MLOG_CHECKPOINT | MLOG_TRUNCATE | MLOG_INDEX_LOAD => {
+            reader.u64()?;
+        }
+        MLOG_TABLE_DYNAMIC_META => {
+            // 表ID、元数据版本，之后为2字节长度的持久化元数据(损坏的索引、自增值)
+            reader.much_compressed_u64()?;
+            reader.much_compressed_u64()?;
+            let len = reader.u16()?;
+            reader.take(len as usize)?;
+        }
+        _ => {
+            return Err(io::Error::other(format!(
+                "unknown redo record type {} at {}",
+                rec_type,
+                reader.pos()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// page_cur_parse_insert_rec
+fn parse_insert(reader: &mut Reader) -> io::Result<()> {
+    let end_seg_len = reader.compressed()?;
+    if end_seg_len & 0x1 != 0 {
+        // info_and_status_bits、origin_offset、mismatch_index
+        reader.u8()?;
+        reader.compressed()?;
+        reader.compressed()?;
+    }
+    reader.take((end_seg_len >> 1) as usize)?;
+    Ok(())
+}
+
+/// row_upd_parse_sys_vals：DB_TRX_ID 的位置、DB_ROLL_PTR、DB_TRX_ID
+fn parse_sys_vals(reader: &mut Reader) -> io::Result<()> {
+    reader.compressed()?;
+    reader.take(7)?;
+    reader.compressed_u64()?;
+    Ok(())
+}
+
+/// row_upd_index_parse：info_bits、列数以及每列的序号、长度和数据
+fn parse_update(reader: &mut Reader) -> io::Result<()> {
+    reader.u8()?;
+    let n_fields = reader.compressed()?;
+    for _ in 0..n_fields {
+        reader.compressed()?;
+        let len = reader.compressed()?;
+        if len != UNIV_SQL_NULL {
+            reader.take(len as usize)?;
+        }
+    }
+    Ok(())
+}
+
+fn parse_file_name(reader: &mut Reader) -> io::Result<()> {
+    let len = reader.u16()?;
+    reader.take(len as usize)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_8bytes() {
+        let buf = [
+            0x08, 0x05, 0x03, // MLOG_8BYTES，space_id 5，page_no 3
+            0x00, 0x26, // 页内偏移
+            0x01, 0x00, 0x00, 0x00, 0x02, // 压缩的高32位 1，低32位 2
+            0x01, 0x05, 0x03, 0x00, 0x40, 0x7F, // 下一条 MLOG_1BYTE 日志
+        ];
+        let (record, pos) = RedoRecord::new(&buf, 0).unwrap();
+        assert_eq!(record.rec_type, MLOG_8BYTES);
+        assert_eq!(record.body, [0x00, 0x26, 0x01, 0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(pos, 10);
+        let (record, pos) = RedoRecord::new(&buf, pos).unwrap();
+        assert_eq!(record.rec_type, MLOG_1BYTE);
+        assert_eq!((record.space_id, record.page_no), (5, 3));
+        assert_eq!(record.body, [0x00, 0x40, 0x7F]);
+        assert_eq!(pos, buf.len());
+    }
+}
@@ -0,0 +1,318 @@
+use crate::tablespace::data_type::DataType;
+use encoding_rs::{
+    Encoding, BIG5, EUC_JP, EUC_KR, GB18030, GBK, SHIFT_JIS, UTF_16BE, UTF_16LE, UTF_8,
+    WINDOWS_1252,
+};
+use std::borrow::Cow;
+use std::io;
+
+/// 字符串列的字符集，由 SDI 中的 collation_id 或建表语句中的字符集名确定
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Charset {
+    Utf8mb4,
+    Utf8mb3,
+    /// MySQL 的 latin1 实际为 cp1252
+    Latin1,
+    Ascii,
+    Gbk,
+    Gb18030,
+    Gb2312,
+    Big5,
+    Sjis,
+    Cp932,
+    Euckr,
+    Ujis,
+    Eucjpms,
+    /// BINARY、VARBINARY、BLOB，没有字符集
+    Binary,
+    Ucs2,
+    Utf16,
+    Utf16le,
+    Utf32,
+}
+
+impl Charset {
+    /// 由排序规则ID得到字符集，不支持的字符集返回 None
+    pub fn from_collation_id(collation_id: u32) -> Option<Charset> {
+        let charset = match collation_id {
+            45 | 46 | 224..=247 | 255..=323 => Charset::Utf8mb4,
+            33 | 76 | 83 | 192..=215 | 223 => Charset::Utf8mb3,
+            5 | 8 | 15 | 31 | 47 | 48 | 49 | 94 => Charset::Latin1,
+            11 | 65 => Charset::Ascii,
+            28 | 87 => Charset::Gbk,
+            248..=250 => Charset::Gb18030,
+            24 | 86 => Charset::Gb2312,
+            1 | 84 => Charset::Big5,
+            13 | 88 => Charset::Sjis,
+            95 | 96 => Charset::Cp932,
+            19 | 85 => Charset::Euckr,
+            12 | 91 => Charset::Ujis,
+            97 | 98 => Charset::Eucjpms,
+            63 => Charset::Binary,
+            35 | 90 | 128..=151 | 159 => Charset::Ucs2,
+            54 | 55 | 101..=124 => Charset::Utf16,
+            56 | 62 => Charset::Utf16le,
+            60 | 61 | 160..=183 => Charset::Utf32,
+            _ => return None,
+        };
+        Some(charset)
+    }
+    /// 由字符集名得到字符集，不支持的字符集返回 None
+    pub fn from_name(name: &str) -> Option<Charset> {
+        let charset = match name.to_ascii_lowercase().as_str() {
+            "utf8mb4" => Charset::Utf8mb4,
+            "utf8" | "utf8mb3" => Charset::Utf8mb3,
+            "latin1" => Charset::Latin1,
+            "ascii" => Charset::Ascii,
+            "gbk" => Charset::Gbk,
+            "gb18030" => Charset::Gb18030,
+            "gb2312" => Charset::Gb2312,
+            "big5" => Charset::Big5,
+            "sjis" => Charset::Sjis,
+            "cp932" => Charset::Cp932,
+            "euckr" => Charset::Euckr,
+            "ujis" => Charset::Ujis,
+            "eucjpms" => Charset::Eucjpms,
+            "binary" => Charset::Binary,
+            "ucs2" => Charset::Ucs2,
+            "utf16" => Charset::Utf16,
+            "utf16le" => Charset::Utf16le,
+            "utf32" => Charset::Utf32,
+            _ => return None,
+        };
+        Some(charset)
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Charset::Utf8mb4 => "utf8mb4",
+            Charset::Utf8mb3 => "utf8mb3",
+            Charset::Latin1 => "latin1",
+            Charset::Ascii => "ascii",
+            Charset::Gbk => "gbk",
+            Charset::Gb18030 => "gb18030",
+            Charset::Gb2312 => "gb2312",
+            Charset::Big5 => "big5",
+            Charset::Sjis => "sjis",
+            Charset::Cp932 => "cp932",
+            Charset::Euckr => "euckr",
+            Charset::Ujis => "ujis",
+            Charset::Eucjpms => "eucjpms",
+            Charset::Binary => "binary",
+            Charset::Ucs2 => "ucs2",
+            Charset::Utf16 => "utf16",
+            Charset::Utf16le => "utf16le",
+            Charset::Utf32 => "utf32",
+        }
+    }
+    /// 单个字符的最大字节数
+    pub fn mbmaxlen(&self) -> usize {
+        match self {
+            Charset::Utf8mb4 | Charset::Gb18030 | Charset::Utf16 | Charset::Utf16le => 4,
+            Charset::Utf32 => 4,
+            Charset::Utf8mb3 | Charset::Ujis | Charset::Eucjpms => 3,
+            Charset::Gbk | Charset::Gb2312 | Charset::Big5 | Charset::Ucs2 => 2,
+            Charset::Sjis | Charset::Cp932 | Charset::Euckr => 2,
+            Charset::Latin1 | Charset::Ascii | Charset::Binary => 1,
+        }
+    }
+    /// 字符集的默认排序规则ID
+    pub fn default_collation_id(&self) -> u32 {
+        match self {
+            Charset::Utf8mb4 => 255,
+            Charset::Utf8mb3 => 33,
+            Charset::Latin1 => 8,
+            Charset::Ascii => 11,
+            Charset::Gbk => 28,
+            Charset::Gb18030 => 248,
+            Charset::Gb2312 => 24,
+            Charset::Big5 => 1,
+            Charset::Sjis => 13,
+            Charset::Cp932 => 95,
+            Charset::Euckr => 19,
+            Charset::Ujis => 12,
+            Charset::Eucjpms => 97,
+            Charset::Binary => 63,
+            Charset::Ucs2 => 35,
+            Charset::Utf16 => 54,
+            Charset::Utf16le => 56,
+            Charset::Utf32 => 60,
+        }
+    }
+    /// 单个字符的最小字节数，与最大字节数不同时为变长字符集
+    pub fn mbminlen(&self) -> usize {
+        match self {
+            Charset::Ucs2 | Charset::Utf16 | Charset::Utf16le => 2,
+            Charset::Utf32 => 4,
+            _ => 1,
+        }
+    }
+    fn encoding(&self) -> Option<&'static Encoding> {
+        match self {
+            Charset::Utf8mb4 | Charset::Utf8mb3 => Some(UTF_8),
+            Charset::Latin1 | Charset::Ascii => Some(WINDOWS_1252),
+            // gb2312 是 gbk 的子集，cp932 与 sjis、eucjpms 与 ujis 只在少数扩展字符上不同
+            Charset::Gbk | Charset::Gb2312 => Some(GBK),
+            Charset::Big5 => Some(BIG5),
+            Charset::Sjis | Charset::Cp932 => Some(SHIFT_JIS),
+            Charset::Euckr => Some(EUC_KR),
+            Charset::Ujis | Charset::Eucjpms => Some(EUC_JP),
+            Charset::Gb18030 => Some(GB18030),
+            Charset::Ucs2 | Charset::Utf16 => Some(UTF_16BE),
+            Charset::Utf16le => Some(UTF_16LE),
+            Charset::Utf32 | Charset::Binary => None,
+        }
+    }
+    /// 解码字符串，遇到非法的字节序列时返回错误
+    pub fn decode(&self, buf: &[u8]) -> io::Result<String> {
+        let invalid = || io::Error::other(format!("invalid {} string", self.name()));
+        match self {
+            Charset::Binary => Err(io::Error::other("binary string has no charset")),
+            // 大端的4字节码点
+            Charset::Utf32 => {
+                if !buf.len().is_multiple_of(4) {
+                    return Err(invalid());
+                }
+                buf.chunks(4)
+                    .map(|x| char::from_u32(u32::from_be_bytes(x.try_into().unwrap())))
+                    .collect::<Option<String>>()
+                    .ok_or_else(invalid)
+            }
+            _ => self
+                .encoding()
+                .unwrap()
+                .decode_without_bom_handling_and_without_replacement(buf)
+                .map(|x| x.into_owned())
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+/// 排序规则中影响字符串比较的属性
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Collation {
+    /// 不区分大小写(_ci)
+    pub case_insensitive: bool,
+    /// PAD SPACE，比较时忽略末尾空格。8.0 的 _0900 排序规则和 binary 为 NO PAD
+    pub pad_space: bool,
+}
+
+impl Collation {
+    /// 由排序规则ID得到，_bin、_cs 和 binary 区分大小写
+    pub fn from_id(collation_id: u32) -> Collation {
+        let case_sensitive = match collation_id {
+            // utf8mb4_0900_as_cs 及各语言的 _as_cs、utf8mb4_0900_bin
+            278..=304 | 307..=309 => true,
+            310..=323 => collation_id % 2 == 1,
+            46 | 47 | 49 | 55 | 61 | 62 | 63 | 65 | 83 | 87 | 90 | 249 => true,
+            84 | 85 | 86 | 88 | 91 | 96 | 98 => true,
+            _ => false,
+        };
+        Collation {
+            case_insensitive: !case_sensitive,
+            pad_space: !matches!(collation_id, 63 | 255..=323),
+        }
+    }
+    /// 由排序规则名得到，如 utf8mb4_0900_ai_ci
+    pub fn from_name(name: &str) -> Collation {
+        let name = name.to_ascii_lowercase();
+        Collation {
+            case_insensitive: name.ends_with("_ci"),
+            pad_space: name != "binary" && !name.contains("_0900_"),
+        }
+    }
+    /// 比较用的字符串，PAD SPACE 时去掉末尾空格，不区分大小写时转为小写。
+    /// 只近似 MySQL 的权重，不处理重音等
+    pub fn sort_key<'a>(&self, value: &'a str) -> Cow<'a, str> {
+        let value = if self.pad_space {
+            value.trim_end_matches(' ')
+        } else {
+            value
+        };
+        if self.case_insensitive {
+            Cow::Owned(value.to_lowercase())
+        } else {
+            Cow::Borrowed(value)
+        }
+    }
+}
+
+/// 字符串列的值，CHAR 去掉末尾填充的空格。二进制字符串和未知字符集返回 None
+pub fn format_str(
+    data_type: DataType,
+    charset: Option<Charset>,
+    data: &[u8],
+) -> Option<io::Result<String>> {
+    let charset = charset.filter(|x| *x != Charset::Binary && data_type.is_str())?;
+    Some(charset.decode(data).map(|value| match data_type {
+        DataType::Char(_) => value.trim_end_matches(' ').to_string(),
+        _ => value,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collation_sort_key() {
+        // utf8mb4_general_ci：不区分大小写，忽略末尾空格
+        let general_ci = Collation::from_id(45);
+        assert_eq!(general_ci.sort_key("Abc  "), general_ci.sort_key("aBC"));
+        // utf8mb4_0900_ai_ci：NO PAD
+        let ai_ci = Collation::from_id(255);
+        assert_ne!(ai_ci.sort_key("abc "), ai_ci.sort_key("ABC"));
+        // utf8mb4_bin：区分大小写，忽略末尾空格
+        let bin = Collation::from_id(46);
+        assert_ne!(bin.sort_key("abc"), bin.sort_key("ABC"));
+        assert_eq!(bin.sort_key("abc "), bin.sort_key("abc"));
+        assert_eq!(Collation::from_name("utf8mb4_0900_ai_ci"), ai_ci);
+        assert_eq!(Collation::from_name("latin1_swedish_ci"), general_ci);
+    }
+
+    #[test]
+    fn decode() {
+        let decode = |collation_id: u32, hex: &str| {
+            let charset = Charset::from_collation_id(collation_id).unwrap();
+            charset.decode(&hex::decode(hex).unwrap()).unwrap()
+        };
+        assert_eq!(decode(28, "d6d0cec4"), "中文");
+        assert_eq!(decode(24, "d6d0cec4"), "中文");
+        assert_eq!(decode(248, "9439fc36"), "😀");
+        assert_eq!(decode(84, "a4a4a4e5"), "中文");
+        assert_eq!(decode(95, "93fa967b"), "日本");
+        assert_eq!(decode(91, "c6fccbdc"), "日本");
+        assert_eq!(decode(19, "b0a1"), "가");
+        assert_eq!(decode(8, "e980"), "é€");
+        assert_eq!(decode(54, "4e2d0061"), "中a");
+        assert_eq!(decode(60, "0001f600"), "😀");
+        assert!(Charset::Utf8mb4.decode(&[0xE4, 0xB8]).is_err());
+        assert!(Charset::Utf32.decode(&[0, 0, 0x4E]).is_err());
+        assert!(Charset::Utf32.decode(&[0, 0x11, 0, 0]).is_err());
+        assert_eq!(Charset::from_name("UTF8").unwrap().mbmaxlen(), 3);
+        assert_eq!(Charset::from_collation_id(999), None);
+    }
+
+    #[test]
+    fn format_char() {
+        let gbk = Some(Charset::Gbk);
+        let value = |data_type, charset, data: &[u8]| {
+            format_str(data_type, charset, data).map(|x| x.unwrap())
+        };
+        assert_eq!(
+            value(DataType::Char(10), gbk, b"\xd6\xd0  "),
+            Some(String::from("中"))
+        );
+        assert_eq!(
+            value(DataType::Varchar(10), gbk, b"\xd6\xd0  "),
+            Some(String::from("中  "))
+        );
+        assert_eq!(
+            value(DataType::Varchar(10), Some(Charset::Binary), b"ab"),
+            None
+        );
+        assert_eq!(value(DataType::Int, gbk, b"ab"), None);
+        assert!(format_str(DataType::Char(2), gbk, b"\xd6")
+            .unwrap()
+            .is_err());
+    }
+}
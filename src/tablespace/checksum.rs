@@ -0,0 +1,178 @@
+use crate::file_header::page_type::PageType;
+use crate::page::compressed_page;
+use bytes::Bytes;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// innodb_checksum_algorithm=none 时写入的校验和
+const BUF_NO_CHECKSUM_MAGIC: u32 = 0xDEAD_BEEF;
+/// ut0rnd.h 中的哈希掩码
+const UT_HASH_RANDOM_MASK: u64 = 1463735687;
+const UT_HASH_RANDOM_MASK2: u64 = 1653893711;
+const FIL_PAGE_OFFSET: usize = 4;
+const FIL_PAGE_LSN: usize = 16;
+const FIL_PAGE_TYPE: usize = 24;
+const FIL_PAGE_FILE_FLUSH_LSN: usize = 26;
+const FIL_PAGE_DATA: usize = 38;
+const FIL_PAGE_END_LSN_OLD_CHKSUM: usize = 8;
+
+/// 页校验的结果，对应 buf_page_is_corrupted
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PageChecksum {
+    /// 全0的页，还未使用
+    Empty,
+    Crc32,
+    Innodb,
+    None,
+    /// File Header 和 File Trailer 中的LSN不一致，页只写了一部分
+    LsnMismatch,
+    Invalid,
+}
+
+impl PageChecksum {
+    /// buf_page_is_corrupted 的逆：页可以被 InnoDB 读取
+    pub fn is_valid(&self) -> bool {
+        matches!(
+            self,
+            PageChecksum::Empty | PageChecksum::Crc32 | PageChecksum::Innodb | PageChecksum::None
+        )
+    }
+}
+
+impl Display for PageChecksum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PageChecksum::Empty => "empty",
+            PageChecksum::Crc32 => "crc32",
+            PageChecksum::Innodb => "innodb",
+            PageChecksum::None => "none",
+            PageChecksum::LsnMismatch => "lsn mismatch",
+            PageChecksum::Invalid => "invalid checksum",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 按页头和页尾中的校验和判断页使用的算法，不符合任何算法时为损坏
+pub fn check_page(page: &[u8]) -> PageChecksum {
+    // 压缩的页在打洞后页尾全为0，校验和是压缩前计算的，InnoDB 解压后再校验
+    let page_type = u16::from_be_bytes([page[FIL_PAGE_TYPE], page[FIL_PAGE_TYPE + 1]]);
+    if PageType::from(page_type) == PageType::FilPageCompressed {
+        return match compressed_page::decompress(Bytes::copy_from_slice(page)) {
+            Ok(page) => check_page(&page),
+            Err(_) => PageChecksum::Invalid,
+        };
+    }
+    let len = page.len();
+    let field1 = read_u32(page, 0);
+    let field2 = read_u32(page, len - FIL_PAGE_END_LSN_OLD_CHKSUM);
+    let lsn_low = read_u32(page, FIL_PAGE_LSN + 4);
+    if lsn_low != read_u32(page, len - 4) {
+        return PageChecksum::LsnMismatch;
+    }
+    if page.iter().all(|b| *b == 0) {
+        return PageChecksum::Empty;
+    }
+    let crc32 = crc32_checksum(page);
+    if field1 == crc32 && field2 == crc32 {
+        return PageChecksum::Crc32;
+    }
+    if field1 == BUF_NO_CHECKSUM_MAGIC && field2 == BUF_NO_CHECKSUM_MAGIC {
+        return PageChecksum::None;
+    }
+    // 很早的版本在旧校验和的位置写的是LSN的高32位
+    let old_valid = field2 == innodb_old_checksum(page) || field2 == read_u32(page, FIL_PAGE_LSN);
+    let new_valid = field1 == 0 || field1 == innodb_checksum(page);
+    if old_valid && new_valid {
+        return PageChecksum::Innodb;
+    }
+    PageChecksum::Invalid
+}
+
+/// buf_calc_page_crc32：跳过校验和、FIL_PAGE_FILE_FLUSH_LSN 和 space_id 以及页尾
+pub fn crc32_checksum(page: &[u8]) -> u32 {
+    let len = page.len();
+    let c1 = crc32c::crc32c(&page[FIL_PAGE_OFFSET..FIL_PAGE_FILE_FLUSH_LSN]);
+    let c2 = crc32c::crc32c(&page[FIL_PAGE_DATA..len - FIL_PAGE_END_LSN_OLD_CHKSUM]);
+    c1 ^ c2
+}
+
+/// buf_calc_page_new_checksum
+pub fn innodb_checksum(page: &[u8]) -> u32 {
+    let len = page.len();
+    let checksum = fold_binary(&page[FIL_PAGE_OFFSET..FIL_PAGE_FILE_FLUSH_LSN]).wrapping_add(
+        fold_binary(&page[FIL_PAGE_DATA..len - FIL_PAGE_END_LSN_OLD_CHKSUM]),
+    );
+    checksum as u32
+}
+
+/// buf_calc_page_old_checksum
+pub fn innodb_old_checksum(page: &[u8]) -> u32 {
+    fold_binary(&page[..FIL_PAGE_FILE_FLUSH_LSN]) as u32
+}
+
+/// ut_fold_binary
+fn fold_binary(buf: &[u8]) -> u64 {
+    buf.iter()
+        .fold(0u64, |fold, b| fold_ulint_pair(fold, *b as u64))
+}
+
+/// ut_fold_ulint_pair
+fn fold_ulint_pair(n1: u64, n2: u64) -> u64 {
+    ((((n1 ^ n2 ^ UT_HASH_RANDOM_MASK2) << 8).wrapping_add(n1)) ^ UT_HASH_RANDOM_MASK)
+        .wrapping_add(n2)
+}
+
+fn read_u32(buf: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes(buf[offset..offset + 4].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 内容按下标填充的16K页，页尾的LSN与页头一致
+    fn page() -> Vec<u8> {
+        let mut page: Vec<u8> = (0..16384).map(|i| (i * 7 % 251) as u8).collect();
+        let lsn_low = page[FIL_PAGE_LSN + 4..FIL_PAGE_LSN + 8].to_vec();
+        page[16380..].copy_from_slice(&lsn_low);
+        page
+    }
+
+    fn write_checksums(page: &mut [u8], field1: u32, field2: u32) {
+        page[..4].copy_from_slice(&field1.to_be_bytes());
+        page[16376..16380].copy_from_slice(&field2.to_be_bytes());
+    }
+
+    #[test]
+    fn crc32_page() {
+        let mut page = page();
+        assert_eq!(crc32_checksum(&page), 0xEB6F_F523);
+        write_checksums(&mut page, 0xEB6F_F523, 0xEB6F_F523);
+        assert_eq!(check_page(&page), PageChecksum::Crc32);
+        page[100] ^= 1;
+        assert_eq!(check_page(&page), PageChecksum::Invalid);
+    }
+
+    #[test]
+    fn innodb_page() {
+        let mut page = page();
+        assert_eq!(innodb_checksum(&page), 0x4319_0096);
+        // 旧校验和包括页头中的新校验和
+        write_checksums(&mut page, 0x4319_0096, 0);
+        assert_eq!(innodb_old_checksum(&page), 0xEA96_55A9);
+        write_checksums(&mut page, 0x4319_0096, 0xEA96_55A9);
+        assert_eq!(check_page(&page), PageChecksum::Innodb);
+    }
+
+    #[test]
+    fn special_pages() {
+        assert_eq!(check_page(&[0; 16384]), PageChecksum::Empty);
+        let mut page = page();
+        write_checksums(&mut page, BUF_NO_CHECKSUM_MAGIC, BUF_NO_CHECKSUM_MAGIC);
+        assert_eq!(check_page(&page), PageChecksum::None);
+        page[16383] ^= 1;
+        assert_eq!(check_page(&page), PageChecksum::LsnMismatch);
+        assert!(!PageChecksum::LsnMismatch.is_valid());
+    }
+}
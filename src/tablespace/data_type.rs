@@ -1,8 +1,9 @@
 use crate::tablespace::data_type::DataType::{
-    Bigint, Bit, Char, Date, Datetime, Decimal, Double, Float, Int, Longtext, Mediumint,
-    Mediumtext, Smallint, Text, Time, Timestamp, Tinyint, Tinytext, UnKnow, Varchar, Year,
+    Bigint, Bit, Char, Date, Datetime, Decimal, Double, Enum, Float, Geometry, Int, Json, Longtext,
+    Mbr, Mediumint, Mediumtext, Set, Smallint, Text, Time, Timestamp, Tinyint, Tinytext, UnKnow,
+    Varchar, Year,
 };
-use std::io;
+use crate::tablespace::temporal::frac_len;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 #[repr(u8)]
@@ -21,31 +22,43 @@ pub enum DataType {
     Bigint = 9,
     // 3字节
     Mediumint = 10,
-    // 4字节
-    Timestamp = 13,
+    // 4字节加小数秒，小数秒精度
+    Timestamp(usize) = 18,
     // 1字节
     Year = 14,
     // 3 字节
     Date = 15,
-    // 可变
-    Varchar = 16,
-    Bit = 17,
+    // 可变，最大字节数
+    Varchar(usize) = 16,
+    // 位数，每8位1字节
+    Bit(usize) = 17,
 
-    // 8字节
-    Datetime = 19,
-    //3字节
-    Time = 20,
+    // 5字节加小数秒，小数秒精度
+    Datetime(usize) = 19,
+    // 3字节加小数秒，小数秒精度
+    Time(usize) = 20,
+    // (精度,小数位数)，每9位数字4字节，剩余的数字
     // 1–2 1
     // 3–4 2
     // 5–6 3
     // 7–9 4
-    Decimal(usize) = 21,
+    Decimal(usize, usize) = 21,
+    // 成员个数，少于256个时1字节，否则2字节
+    Enum(usize) = 22,
+    // 成员个数，每8个1字节，超过4字节时为8字节
+    Set(usize) = 23,
     // 可变
     Tinytext = 24,
     Mediumtext = 25,
     Longtext = 26,
     Text = 27,
     Char(usize) = 29,
+    // 可变，SRID + WKB
+    Geometry = 30,
+    // 可变，MySQL 二进制 JSON 格式
+    Json = 31,
+    // R-tree 索引中的最小外包矩形 32字节
+    Mbr,
     // 隐藏主键 6字节
     DbRowId,
     // 隐藏事务id 6字节
@@ -56,6 +69,7 @@ pub enum DataType {
 }
 
 impl DataType {
+    /// 由类型编号和字节长度得到类型，ENUM、SET、BIT 只知道字节长度时按能存储的最大成员个数或位数处理
     pub fn new(value: u8, len: usize) -> Self {
         match value {
             2 => Tinyint,
@@ -65,19 +79,23 @@ impl DataType {
             6 => Double,
             9 => Bigint,
             10 => Mediumint,
-            13 => Timestamp,
             14 => Year,
             15 => Date,
-            16 => Varchar,
-            17 => Bit,
-            19 => Datetime,
-            20 => Time,
-            21 => Decimal(len),
+            16 => Varchar(len),
+            17 => Bit(len * 8),
+            // 只知道字节长度时，由小数秒的字节数推断精度
+            18 => Timestamp(len.saturating_sub(4) * 2),
+            19 => Datetime(len.saturating_sub(5) * 2),
+            20 => Time(len.saturating_sub(3) * 2),
+            22 => Enum(if len > 1 { 65535 } else { 255 }),
+            23 => Set(len * 8),
             24 => Tinytext,
             25 => Mediumtext,
             26 => Longtext,
             27 => Text,
             29 => Char(len),
+            30 => Geometry,
+            31 => Json,
             _ => UnKnow(value, len),
         }
     }
@@ -90,35 +108,72 @@ impl DataType {
             Double => 8,
             Bigint => 8,
             Mediumint => 3,
-            Timestamp => 4,
+            Timestamp(fsp) => 4 + frac_len(*fsp),
             Year => 1,
             Date => 3,
-            Varchar => 0,
-            Bit => 1,
-            Datetime => 8,
-            Time => 3,
-            Decimal(len) => *len,
+            Varchar(_) => 0,
+            Bit(width) => width.div_ceil(8),
+            Datetime(fsp) => 5 + frac_len(*fsp),
+            Time(fsp) => 3 + frac_len(*fsp),
+            Decimal(precision, scale) => decimal_bin_size(*precision, *scale),
+            Enum(count) => {
+                if *count < 256 {
+                    1
+                } else {
+                    2
+                }
+            }
+            Set(count) => match count.div_ceil(8) {
+                len @ 0..=4 => len,
+                _ => 8,
+            },
             Tinytext => 0,
             Mediumtext => 0,
             Longtext => 0,
             Text => 0,
             Char(len) => *len,
-            UnKnow(_, _) => panic!(),
+            Geometry => 0,
+            Json => 0,
+            Mbr => 32,
+            UnKnow(_, len) => *len,
             DataType::DbRowId => 6,
             DataType::DbTrxId => 6,
             DataType::DbRollPtr => 7,
         }
     }
-    pub fn is_var(&self) -> bool {
+    /// 列的最大字节数，BLOB、TEXT 等大对象为 usize::MAX
+    pub fn max_len(&self) -> usize {
         match self {
-            Varchar | Tinytext | Mediumtext | Longtext | Text => true,
-            _ => false,
+            Varchar(len) | Char(len) => *len,
+            Tinytext | Mediumtext | Longtext | Text | Geometry | Json => usize::MAX,
+            other => other.len(),
         }
     }
-    pub fn is_str(&self) -> bool {
+    pub fn is_var(&self) -> bool {
+        matches!(
+            self,
+            Varchar(_) | Tinytext | Mediumtext | Longtext | Text | Geometry | Json
+        )
+    }
+    /// 变长字段长度列表中长度可能占2字节的列：最大长度超过255字节，或者 BLOB、TEXT 等大对象
+    pub fn is_big(&self) -> bool {
         match self {
-            Varchar | Tinytext | Mediumtext | Longtext | Text | Char(_) => true,
+            Varchar(len) | Char(len) => *len > 255,
+            Tinytext | Mediumtext | Longtext | Text | Geometry | Json => true,
             _ => false,
         }
     }
+    pub fn is_str(&self) -> bool {
+        matches!(
+            self,
+            Varchar(_) | Tinytext | Mediumtext | Longtext | Text | Char(_)
+        )
+    }
+}
+
+/// DECIMAL 的存储字节数，整数部分和小数部分分别按每9位数字4字节存储
+pub fn decimal_bin_size(precision: usize, scale: usize) -> usize {
+    const DIG2BYTES: [usize; 10] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4];
+    let intg = precision.saturating_sub(scale);
+    (intg / 9) * 4 + DIG2BYTES[intg % 9] + (scale / 9) * 4 + DIG2BYTES[scale % 9]
 }
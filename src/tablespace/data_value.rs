@@ -1,60 +1,144 @@
+use crate::tablespace::data_type::{decimal_bin_size, DataType};
+use std::fmt;
 use std::io;
 
 #[derive(Debug)]
 pub enum DataValue {
-    // 1 字节
-    Tinyint(i8),
-    // 2字节
-    Smallint(i16),
-    // 4字节
-    Int(i32),
-    // 4字节
-    Float(f32),
-    // 8字节
-    Double(f64),
-    // 8字节
-    Bigint(i64),
-    // 3字节
-    Mediumint(i32),
-    // 4字节
-    Timestamp(u32),
-    // 1字节
-    Year(u8),
-    // 3 字节
-    Date(u32),
-    // 可变
-    Varchar(String),
-
-    // 8字节
-    Datetime(u64),
-    //3字节
-    Time(u32),
     Decimal(String),
-    // 可变
-    Tinytext(String),
-    Mediumtext(String),
-    Longtext(String),
-    Text(String),
-    Char(String),
-    // 隐藏主键 6字节
-    DbRowId(u64),
-    // 隐藏事务id 6字节
-    DbTrxId(u64),
-    //隐藏回滚指针 7字节
-    DbRollPtr(u64),
 }
 
-impl TryInto<i8> for DataValue {
-    type Error = io::Error;
+/// 每4字节存储的十进制位数
+const DIG_PER_DEC: usize = 9;
+/// 不足9位的数字占用的字节数
+const DIG2BYTES: [usize; 10] = [0, 1, 1, 2, 2, 3, 3, 4, 4, 4];
 
-    fn try_into(self) -> Result<i8, Self::Error> {
-        if let DataValue::Tinyint(val) = self {
-            Ok(val)
-        } else {
-            Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("not i8:{:?}", self),
-            ))
+impl DataValue {
+    /// 解析 DECIMAL(precision,scale) 的二进制格式，得到精确的十进制字符串。
+    ///
+    /// 整数部分和小数部分分别存储，每9位数字为一组占4字节，整数部分不足9位的数字在最前面，
+    /// 小数部分不足9位的数字在最后面，各组均为大端。
+    /// 第一个字节的最高位为符号位，正数为1；负数的所有字节按位取反
+    pub fn decimal(precision: usize, scale: usize, buf: &[u8]) -> io::Result<DataValue> {
+        if scale > precision || buf.len() != decimal_bin_size(precision, scale) || buf.is_empty() {
+            return Err(io::Error::other(format!(
+                "invalid decimal({},{}) length {}",
+                precision,
+                scale,
+                buf.len()
+            )));
         }
+        let intg = precision - scale;
+        let is_negative = buf[0] & 0x80 == 0;
+        let mask = if is_negative { 0xFF } else { 0 };
+        let mut buf = buf.to_vec();
+        buf[0] ^= 0x80;
+        let mut offset = 0;
+        let mut read = |len: usize| -> u32 {
+            let value = buf[offset..offset + len]
+                .iter()
+                .fold(0u32, |acc, x| (acc << 8) | (x ^ mask) as u32);
+            offset += len;
+            value
+        };
+        let mut int_part = String::new();
+        let lead_len = intg % DIG_PER_DEC;
+        if lead_len != 0 {
+            int_part.push_str(&read(DIG2BYTES[lead_len]).to_string());
+        }
+        for _ in 0..intg / DIG_PER_DEC {
+            int_part.push_str(&format!("{:09}", read(4)));
+        }
+        let int_part = int_part.trim_start_matches('0');
+        let mut frac_part = String::new();
+        for _ in 0..scale / DIG_PER_DEC {
+            frac_part.push_str(&format!("{:09}", read(4)));
+        }
+        let frac_len = scale % DIG_PER_DEC;
+        if frac_len != 0 {
+            let value = read(DIG2BYTES[frac_len]);
+            frac_part.push_str(&format!("{:0width$}", value, width = frac_len));
+        }
+        let mut value = String::new();
+        if is_negative {
+            value.push('-');
+        }
+        value.push_str(if int_part.is_empty() { "0" } else { int_part });
+        if scale > 0 {
+            value.push('.');
+            value.push_str(&frac_part);
+        }
+        Ok(DataValue::Decimal(value))
+    }
+}
+
+/// DECIMAL 列的值，其它类型返回 None
+pub fn format_decimal(data_type: DataType, data: &[u8]) -> Option<io::Result<String>> {
+    match data_type {
+        DataType::Decimal(precision, scale) => {
+            Some(DataValue::decimal(precision, scale, data).map(|x| x.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// ENUM 显示为带引号的成员名，SET 显示为带引号、以逗号分隔的成员名，BIT 显示为 b'0101'，其它类型返回 None。
+/// ENUM、SET 均为大端的无符号整数，ENUM 为从1开始的成员序号(0为无效值)，SET 的每一位对应一个成员
+pub fn format_elements(data_type: DataType, elements: &[String], data: &[u8]) -> Option<String> {
+    let value = data.iter().fold(0u64, |acc, x| (acc << 8) | *x as u64);
+    match data_type {
+        DataType::Enum(_) => {
+            let name = match value {
+                0 => String::new(),
+                index => elements
+                    .get(index as usize - 1)
+                    .cloned()
+                    .unwrap_or_else(|| index.to_string()),
+            };
+            Some(format!("{:?}", name))
+        }
+        DataType::Set(_) => {
+            let names = (0..data.len() * 8)
+                .filter(|i| value >> i & 1 == 1)
+                .map(|i| elements.get(i).cloned().unwrap_or_else(|| i.to_string()))
+                .collect::<Vec<_>>();
+            Some(format!("{:?}", names.join(",")))
+        }
+        DataType::Bit(width) => {
+            let bits: String = data.iter().map(|x| format!("{:08b}", x)).collect();
+            Some(format!("b'{}'", &bits[bits.len().saturating_sub(width)..]))
+        }
+        _ => None,
+    }
+}
+
+impl fmt::Display for DataValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataValue::Decimal(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_negative() {
+        // DECIMAL(5,2) -1.50：整数部分3位占2字节，小数部分2位占1字节，负数按位取反
+        let value = DataValue::decimal(5, 2, &[0x7F, 0xFE, 0xCD]).unwrap();
+        assert_eq!(value.to_string(), "-1.50");
+    }
+
+    #[test]
+    fn decimal_positive() {
+        // DECIMAL(10,4) 1234.5678
+        let value = DataValue::decimal(10, 4, &[0x80, 0x04, 0xD2, 0x16, 0x2E]).unwrap();
+        assert_eq!(value.to_string(), "1234.5678");
+    }
+
+    #[test]
+    fn decimal_invalid_length() {
+        assert!(DataValue::decimal(5, 2, &[0x80, 0x01]).is_err());
     }
 }
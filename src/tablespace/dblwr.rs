@@ -0,0 +1,293 @@
+use crate::file_header::FileHeader;
+use crate::page::base_page::BasePage;
+use crate::page::trx_sys_page::TrxSysPage;
+use crate::tablespace::checksum::{check_page, PageChecksum};
+use crate::tablespace::encryption::TablespaceKey;
+use crate::tablespace::{is_system_file, TableSpace};
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 页大小
+const PAGE_SIZE: usize = 16 * 1024;
+/// 系统表空间中 TRX_SYS 页的页号
+const TRX_SYS_PAGE_NO: u32 = 5;
+
+/// doublewrite 缓冲区中的一个页副本
+#[derive(Debug)]
+pub struct DblwrPage {
+    pub path: PathBuf,
+    /// 在文件中的页号
+    pub slot: u32,
+    pub buf: Bytes,
+}
+
+impl DblwrPage {
+    pub fn fil_header(&self) -> FileHeader<Bytes> {
+        FileHeader::new(self.buf.slice(..38))
+    }
+    pub fn checksum(&self, key: Option<&TablespaceKey>) -> PageChecksum {
+        checksum(&self.buf, key)
+    }
+}
+
+/// 加密的页先解密再计算校验和。MySQL 8.0.23 之后 doublewrite 中加密表空间的页也是加密的
+fn checksum(buf: &Bytes, key: Option<&TablespaceKey>) -> PageChecksum {
+    match key.map(|key| key.decrypt_page(buf.clone())) {
+        Some(Ok(page)) => check_page(&page),
+        _ => check_page(buf),
+    }
+}
+
+/// doublewrite 缓冲区：MySQL 8.0.20 之前位于 ibdata1 中 TRX_SYS 页记录的两个块，
+/// 之后为数据目录下的 #ib_16384_N.dblwr 文件。按页头中的 space_id 和页号保存页副本
+#[derive(Debug, Default)]
+pub struct DoublewriteBuffer {
+    pub files: Vec<PathBuf>,
+    pub pages: HashMap<(u32, u32), Vec<DblwrPage>>,
+}
+
+impl DoublewriteBuffer {
+    /// 使用指定的文件，ibdata开头的按系统表空间读取；未指定时从数据目录中查找
+    pub fn new(idb_file_path: &str, files: &[String]) -> io::Result<Self> {
+        let files: Vec<PathBuf> = if files.is_empty() {
+            find_files(Path::new(idb_file_path))?
+        } else {
+            files.iter().map(PathBuf::from).collect()
+        };
+        let mut dblwr = DoublewriteBuffer::default();
+        for path in files {
+            if is_system_file(&path) {
+                dblwr.read_system(&path)?;
+            } else {
+                dblwr.read_file(&path)?;
+            }
+            dblwr.files.push(path);
+        }
+        Ok(dblwr)
+    }
+    /// 系统表空间中 doublewrite 的两个块，共128页
+    fn read_system(&mut self, path: &Path) -> io::Result<()> {
+        let path_str = path.to_string_lossy();
+        let trx_sys: BasePage<TrxSysPage> =
+            BasePage::new(TableSpace::read_page_(&path_str, TRX_SYS_PAGE_NO)?);
+        let Some(doublewrite) = trx_sys.doublewrite else {
+            return Ok(());
+        };
+        for page_num in doublewrite.pages() {
+            let buf = TableSpace::read_page_(&path_str, page_num).map_err(|e| {
+                io::Error::other(format!(
+                    "doublewrite page {} in {}: {}",
+                    page_num,
+                    path.display(),
+                    e
+                ))
+            })?;
+            self.add(path, page_num, buf);
+        }
+        Ok(())
+    }
+    /// .dblwr 文件由若干段组成，每个缓冲池实例的 flush list 和 LRU 批量写各占一段，
+    /// 段中为连续的完整页副本，没有额外的头，按页顺序读取即可。
+    /// innodb_doublewrite=DETECT_ONLY 时写入的 .bdblwr 文件只有页头，不包含副本，不读取
+    fn read_file(&mut self, path: &Path) -> io::Result<()> {
+        let buf = Bytes::from(fs::read(path)?);
+        for slot in 0..buf.len() / PAGE_SIZE {
+            let start = slot * PAGE_SIZE;
+            self.add(path, slot as u32, buf.slice(start..start + PAGE_SIZE));
+        }
+        Ok(())
+    }
+    fn add(&mut self, path: &Path, slot: u32, buf: Bytes) {
+        if buf.iter().all(|b| *b == 0) {
+            return;
+        }
+        let fil_header = FileHeader::new(buf.slice(..38));
+        let key = (fil_header.space_id(), fil_header.offset());
+        self.pages.entry(key).or_default().push(DblwrPage {
+            path: path.to_path_buf(),
+            slot,
+            buf,
+        });
+    }
+    /// 校验和正确且LSN最大的副本，加密表空间的副本用 key 解密后校验
+    pub fn page(
+        &self,
+        space_id: u32,
+        page_num: u32,
+        key: Option<&TablespaceKey>,
+    ) -> Option<&DblwrPage> {
+        self.pages
+            .get(&(space_id, page_num))?
+            .iter()
+            .filter(|page| page.checksum(key).is_valid())
+            .max_by_key(|page| page.fil_header().lsn())
+    }
+    /// 所有副本
+    pub fn copies(&self, space_id: u32, page_num: u32) -> &[DblwrPage] {
+        self.pages
+            .get(&(space_id, page_num))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+/// 表空间中一个页的检查结果
+#[derive(Debug)]
+pub struct DblwrCheck<'a> {
+    pub page_num: u32,
+    pub checksum: PageChecksum,
+    pub lsn: u64,
+    /// 可以用来修复的副本
+    pub copy: Option<&'a DblwrPage>,
+    /// doublewrite 缓冲区中该页的副本数
+    pub copies: usize,
+}
+
+impl DoublewriteBuffer {
+    /// 检查表空间中的每一页，返回校验和错误的页。加密的表空间需要 key，否则所有加密的页都校验失败
+    pub fn check(
+        &self,
+        idb_file_path: &str,
+        key: Option<&TablespaceKey>,
+    ) -> io::Result<Vec<DblwrCheck<'_>>> {
+        let buf = Bytes::from(fs::read(idb_file_path)?);
+        if buf.len() < PAGE_SIZE {
+            return Err(io::Error::other(format!("{} is too small", idb_file_path)));
+        }
+        // 第0页损坏时页头中的 space_id 仍然大概率可用
+        let space_id = FileHeader::new(&buf[..38]).space_id();
+        let mut result = Vec::new();
+        for page_num in 0..buf.len() / PAGE_SIZE {
+            let page = buf.slice(page_num * PAGE_SIZE..(page_num + 1) * PAGE_SIZE);
+            let checksum = checksum(&page, key);
+            if checksum.is_valid() {
+                continue;
+            }
+            let page_num = page_num as u32;
+            result.push(DblwrCheck {
+                page_num,
+                checksum,
+                lsn: FileHeader::new(&page[..38]).lsn(),
+                copy: self.page(space_id, page_num, key),
+                copies: self.copies(space_id, page_num).len(),
+            });
+        }
+        Ok(result)
+    }
+    /// 用副本替换损坏的页，写到新文件中，返回修复的页数。副本与数据文件中的页一样是加密的，原样写入
+    pub fn repair(
+        &self,
+        idb_file_path: &str,
+        output: &str,
+        key: Option<&TablespaceKey>,
+    ) -> io::Result<usize> {
+        let checks = self.check(idb_file_path, key)?;
+        let mut buf = fs::read(idb_file_path)?;
+        let mut repaired = 0;
+        for check in checks {
+            if let Some(copy) = check.copy {
+                let start = check.page_num as usize * PAGE_SIZE;
+                buf[start..start + PAGE_SIZE].copy_from_slice(&copy.buf);
+                repaired += 1;
+            }
+        }
+        fs::write(output, buf)?;
+        Ok(repaired)
+    }
+}
+
+/// 数据目录下的 ibdata1 和 #ib_*.dblwr
+fn find_files(idb_file_path: &Path) -> io::Result<Vec<PathBuf>> {
+    // 系统表空间在数据目录下，用户表空间在数据目录下的库目录中
+    let data_dir = if is_system_file(idb_file_path) {
+        idb_file_path.parent()
+    } else {
+        idb_file_path.parent().and_then(Path::parent)
+    };
+    let Some(data_dir) = data_dir else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::new();
+    let system = data_dir.join("ibdata1");
+    if system.exists() {
+        files.push(system);
+    }
+    if data_dir.is_dir() {
+        let mut dblwr_files = Vec::new();
+        for entry in fs::read_dir(data_dir)? {
+            let path = entry?.path();
+            let is_dblwr = path
+                .file_name()
+                .and_then(|x| x.to_str())
+                .is_some_and(|name| name.starts_with("#ib_") && name.ends_with(".dblwr"));
+            if is_dblwr {
+                dblwr_files.push(path);
+            }
+        }
+        dblwr_files.sort();
+        files.extend(dblwr_files);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tablespace::checksum::crc32_checksum;
+
+    /// 校验和为 crc32 的页
+    fn page(space_id: u32, page_num: u32, lsn: u64) -> Vec<u8> {
+        let mut page = vec![0; PAGE_SIZE];
+        page[4..8].copy_from_slice(&page_num.to_be_bytes());
+        page[16..24].copy_from_slice(&lsn.to_be_bytes());
+        page[34..38].copy_from_slice(&space_id.to_be_bytes());
+        page[100..104].copy_from_slice(&page_num.to_be_bytes());
+        page[PAGE_SIZE - 4..].copy_from_slice(&(lsn as u32).to_be_bytes());
+        let checksum = crc32_checksum(&page).to_be_bytes();
+        page[..4].copy_from_slice(&checksum);
+        page[PAGE_SIZE - 8..PAGE_SIZE - 4].copy_from_slice(&checksum);
+        page
+    }
+
+    #[test]
+    fn repair_from_dblwr_file() {
+        let dir = std::env::temp_dir().join(format!("dblwr_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let ibd = dir.join("t.ibd");
+        let dblwr_path = dir.join("#ib_16384_0.dblwr");
+        let output = dir.join("t.repaired.ibd");
+
+        // 数据文件的第1页只写了一半，doublewrite 中有两个副本，LSN 较大的一个校验和错误
+        let mut corrupted = page(7, 1, 300);
+        corrupted[200] = 0xFF;
+        fs::write(&ibd, [page(7, 0, 100), corrupted].concat()).unwrap();
+        let mut newer = page(7, 1, 400);
+        newer[300] = 0xFF;
+        fs::write(
+            &dblwr_path,
+            [page(7, 1, 300), newer, vec![0; PAGE_SIZE]].concat(),
+        )
+        .unwrap();
+
+        let dblwr = DoublewriteBuffer::new(
+            ibd.to_str().unwrap(),
+            &[dblwr_path.to_string_lossy().into_owned()],
+        )
+        .unwrap();
+        assert_eq!(dblwr.copies(7, 1).len(), 2);
+        assert_eq!(dblwr.page(7, 1, None).unwrap().slot, 0);
+        let checks = dblwr.check(ibd.to_str().unwrap(), None).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].page_num, 1);
+        assert_eq!(checks[0].checksum, PageChecksum::Invalid);
+        let repaired = dblwr
+            .repair(ibd.to_str().unwrap(), output.to_str().unwrap(), None)
+            .unwrap();
+        assert_eq!(repaired, 1);
+        assert_eq!(fs::read(&output).unwrap()[PAGE_SIZE..], page(7, 1, 300));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
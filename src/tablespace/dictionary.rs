@@ -0,0 +1,533 @@
+use crate::frm::data_type::DataType as FrmDataType;
+use crate::page::base_page::BasePage;
+use crate::page::dict_hdr_page::{DictHdrPage, DICT_HDR_PAGE_NO};
+use crate::page::index_page::records::Row;
+use crate::tablespace::charset::{Charset, Collation};
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::table::{
+    build_indexes, child_page_num_column, Column, Index, KeyDef, KeyPart, Order, TableInfo,
+};
+use crate::tablespace::TableSpace;
+use bytes::Bytes;
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// 系统表的索引ID
+pub const SYS_TABLES_ID: u64 = 1;
+pub const SYS_TABLE_IDS_ID: u64 = 2;
+pub const SYS_COLUMNS_ID: u64 = 3;
+pub const SYS_INDEXES_ID: u64 = 4;
+pub const SYS_FIELDS_ID: u64 = 5;
+
+/// SYS_COLUMNS.MTYPE 主类型
+const DATA_VARCHAR: u32 = 1;
+const DATA_BINARY: u32 = 4;
+const DATA_BLOB: u32 = 5;
+const DATA_INT: u32 = 6;
+const DATA_SYS: u32 = 8;
+const DATA_FLOAT: u32 = 9;
+const DATA_DOUBLE: u32 = 10;
+const DATA_VARMYSQL: u32 = 12;
+const DATA_GEOMETRY: u32 = 14;
+
+/// SYS_COLUMNS.PRTYPE 标识位
+const DATA_NOT_NULL: u32 = 256;
+const DATA_VIRTUAL: u32 = 8192;
+
+/// 系统表中名称字段的最大字节数，即 `库名/表名` 的最大长度，索引名、列名也按此长度定义
+const MAX_FULL_NAME_LEN: usize = 398;
+
+/// SYS_INDEXES.TYPE 标识位
+const DICT_CLUSTERED: u32 = 1;
+const DICT_UNIQUE: u32 = 2;
+const DICT_FTS: u32 = 32;
+const DICT_SPATIAL: u32 = 64;
+
+/// SYS_TABLES.N_COLS 最高位表示行格式为 COMPACT 或更新的格式
+const DICT_N_COLS_COMPACT: u32 = 0x80000000;
+
+#[derive(Debug, Clone)]
+pub struct DictColumn {
+    pub name: String,
+    pub pos: u32,
+    pub mtype: u32,
+    pub prtype: u32,
+    pub len: u32,
+}
+
+impl DictColumn {
+    pub fn is_nullable(&self) -> bool {
+        self.prtype & DATA_NOT_NULL == 0
+    }
+    pub fn is_virtual(&self) -> bool {
+        self.prtype & DATA_VIRTUAL != 0
+    }
+    /// 字符集ID
+    pub fn charset(&self) -> u32 {
+        (self.prtype >> 16) & 0xFF
+    }
+    pub fn data_type(&self) -> DataType {
+        let len = self.len as usize;
+        let mysql_type = (self.prtype & 0xFF) as u8;
+        match self.mtype {
+            DATA_SYS => match mysql_type {
+                0 => DataType::DbRowId,
+                1 => DataType::DbTrxId,
+                _ => DataType::DbRollPtr,
+            },
+            // MYSQL_TYPE_YEAR
+            DATA_INT if mysql_type == 13 => DataType::Year,
+            DATA_INT => match len {
+                1 => DataType::Tinyint,
+                2 => DataType::Smallint,
+                3 => DataType::Mediumint,
+                4 => DataType::Int,
+                _ => DataType::Bigint,
+            },
+            DATA_FLOAT => DataType::Float,
+            DATA_DOUBLE => DataType::Double,
+            DATA_VARCHAR | DATA_BINARY | DATA_VARMYSQL => DataType::Varchar(len),
+            DATA_BLOB | DATA_GEOMETRY => match FrmDataType::from(mysql_type) {
+                FrmDataType::MysqlTypeJson => DataType::new(31, 0),
+                FrmDataType::MysqlTypeGeometry => DataType::new(30, 0),
+                // BLOB 的长度为长度字节数加上8字节指针
+                _ => match len {
+                    9 => DataType::Tinytext,
+                    11 => DataType::Mediumtext,
+                    12 => DataType::Longtext,
+                    _ => DataType::Text,
+                },
+            },
+            _ => match FrmDataType::from(mysql_type).dd_type() {
+                // SYS_COLUMNS 中只有 DECIMAL 的字节数，没有精度和小数位数，
+                // 按小数位数为0取该字节数能存储的最大精度，记录能正确切分，值按整数显示
+                21 => DataType::Decimal(decimal_precision(len), 0),
+                28 | 29 => DataType::Char(len),
+                dd_type => DataType::new(dd_type, len),
+            },
+        }
+    }
+}
+
+/// 小数位数为0时，DECIMAL 占用 len 字节能存储的最大位数：每4字节9位，剩余的1~3字节各2位
+fn decimal_precision(len: usize) -> usize {
+    len / 4 * 9 + len % 4 * 2
+}
+
+#[derive(Debug, Clone)]
+pub struct DictIndex {
+    pub id: u64,
+    pub name: String,
+    pub index_type: u32,
+    pub space: u32,
+    pub page_no: u32,
+    pub fields: Vec<String>,
+    /// 与 fields 一一对应的前缀字节数，0表示不是前缀
+    pub prefix_lens: Vec<usize>,
+}
+
+impl DictIndex {
+    pub fn is_clustered(&self) -> bool {
+        self.index_type & DICT_CLUSTERED != 0
+    }
+    pub fn is_unique(&self) -> bool {
+        self.index_type & DICT_UNIQUE != 0
+    }
+    pub fn is_spatial(&self) -> bool {
+        self.index_type & DICT_SPATIAL != 0
+    }
+}
+
+/// 数据字典中的表
+#[derive(Debug, Clone)]
+pub struct DictTable {
+    pub name: String,
+    pub id: u64,
+    pub n_cols: u32,
+    pub flags: u32,
+    pub space: u32,
+    pub columns: Vec<DictColumn>,
+    pub indexes: Vec<DictIndex>,
+}
+
+impl DictTable {
+    /// 是否为 COMPACT 及更新的行格式
+    pub fn is_compact(&self) -> bool {
+        self.n_cols & DICT_N_COLS_COMPACT != 0
+    }
+    /// 由数据字典构建表结构，索引ID和根页号取自 SYS_INDEXES
+    pub fn table_info(&self) -> TableInfo {
+        // 虚拟列只在二级索引中存储，SYS_COLUMNS 中没有生成表达式
+        let columns = &self.columns;
+        let table_columns: Vec<Column> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, col)| {
+                let mut column = Column::new(
+                    col.name.clone(),
+                    col.data_type(),
+                    col.is_nullable(),
+                    false,
+                    i as u16 + 1,
+                );
+                column.is_virtual = col.is_virtual();
+                column.charset = Charset::from_collation_id(col.charset());
+                column.collation = Some(Collation::from_id(col.charset()));
+                column
+            })
+            .collect();
+        // 全文索引的数据在辅助表中；隐藏聚簇索引没有字段定义
+        let dict_indexes: Vec<&DictIndex> = self
+            .indexes
+            .iter()
+            .filter(|index| index.index_type & DICT_FTS == 0)
+            .collect();
+        let keys: Vec<KeyDef> = dict_indexes
+            .iter()
+            .filter(|index| !(index.is_clustered() && index.fields.is_empty()))
+            .map(|index| KeyDef {
+                name: index.name.clone(),
+                is_primary: index.is_clustered(),
+                is_unique: index.is_unique(),
+                is_spatial: index.is_spatial(),
+                parts: index
+                    .fields
+                    .iter()
+                    .zip(&index.prefix_lens)
+                    .filter_map(|(field, &prefix_len)| {
+                        let column = columns.iter().position(|col| &col.name == field)?;
+                        Some(KeyPart {
+                            column,
+                            len: Some(prefix_len).filter(|&len| len > 0),
+                            order: Order::Asc,
+                        })
+                    })
+                    .collect(),
+                comment: String::new(),
+            })
+            .collect();
+        let mut indexes = HashMap::new();
+        for mut index in build_indexes(&table_columns, &keys) {
+            if let Some(dict_index) = dict_indexes.iter().find(|x| x.name == index.name) {
+                index.index_id = dict_index.id;
+                index.root_page_num = dict_index.page_no;
+                indexes.insert(index.index_id, index);
+            }
+        }
+        TableInfo {
+            name: self.name.clone(),
+            columns: table_columns,
+            indexes,
+            comment: String::new(),
+        }
+    }
+}
+
+impl Display for DictTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} id:{} space:{} n_cols:{} flags:{} compact:{}",
+            self.name,
+            self.id,
+            self.space,
+            self.n_cols & !DICT_N_COLS_COMPACT,
+            self.flags,
+            self.is_compact()
+        )?;
+        for col in &self.columns {
+            writeln!(
+                f,
+                " column {} pos:{} mtype:{} prtype:{} len:{} {:?}",
+                col.name,
+                col.pos,
+                col.mtype,
+                col.prtype,
+                col.len,
+                col.data_type()
+            )?;
+        }
+        for index in &self.indexes {
+            writeln!(
+                f,
+                " index {} id:{} type:{} space:{} root:{} fields:{:?}",
+                index.name, index.id, index.index_type, index.space, index.page_no, index.fields
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn sys_column(name: &str, data_type: DataType, ordinal_position: u16) -> Column {
+    Column::new(
+        String::from(name),
+        data_type,
+        false,
+        false,
+        ordinal_position,
+    )
+}
+
+/// key_len 为非叶子节点记录中子页号之前的字段数，二级索引包含主键字段
+fn sys_index(
+    index_id: u64,
+    root_page_num: u32,
+    name: &str,
+    key_len: usize,
+    elements: Vec<Column>,
+) -> Index {
+    let mut indexes: Vec<Column> = elements[..key_len].to_vec();
+    indexes.push(child_page_num_column(elements.len() as u16 + 1));
+    Index::new(
+        index_id,
+        root_page_num,
+        String::from(name),
+        index_id != SYS_TABLE_IDS_ID,
+        indexes,
+        elements,
+    )
+}
+
+/// 系统表 SYS_TABLES、SYS_COLUMNS、SYS_INDEXES、SYS_FIELDS 的索引，记录为 REDUNDANT 行格式
+pub fn sys_table_info(hdr: &DictHdrPage) -> TableInfo {
+    let trx_id = || sys_column("DB_TRX_ID", DataType::DbTrxId, 0);
+    let roll_ptr = || sys_column("DB_ROLL_PTR", DataType::DbRollPtr, 0);
+    let list = vec![
+        sys_index(
+            SYS_TABLES_ID,
+            hdr.tables,
+            "SYS_TABLES",
+            1,
+            vec![
+                sys_column("NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 1),
+                trx_id(),
+                roll_ptr(),
+                sys_column("ID", DataType::Bigint, 2),
+                sys_column("N_COLS", DataType::Int, 3),
+                sys_column("TYPE", DataType::Int, 4),
+                sys_column("MIX_ID", DataType::Bigint, 5),
+                sys_column("MIX_LEN", DataType::Int, 6),
+                sys_column("CLUSTER_NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 7),
+                sys_column("SPACE", DataType::Int, 8),
+            ],
+        ),
+        sys_index(
+            SYS_TABLE_IDS_ID,
+            hdr.table_ids,
+            "SYS_TABLE_IDS",
+            2,
+            vec![
+                sys_column("ID", DataType::Bigint, 2),
+                sys_column("NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 1),
+            ],
+        ),
+        sys_index(
+            SYS_COLUMNS_ID,
+            hdr.columns,
+            "SYS_COLUMNS",
+            2,
+            vec![
+                sys_column("TABLE_ID", DataType::Bigint, 1),
+                sys_column("POS", DataType::Int, 2),
+                trx_id(),
+                roll_ptr(),
+                sys_column("NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 3),
+                sys_column("MTYPE", DataType::Int, 4),
+                sys_column("PRTYPE", DataType::Int, 5),
+                sys_column("LEN", DataType::Int, 6),
+                sys_column("PREC", DataType::Int, 7),
+            ],
+        ),
+        sys_index(
+            SYS_INDEXES_ID,
+            hdr.indexes,
+            "SYS_INDEXES",
+            2,
+            vec![
+                sys_column("TABLE_ID", DataType::Bigint, 1),
+                sys_column("ID", DataType::Bigint, 2),
+                trx_id(),
+                roll_ptr(),
+                sys_column("NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 3),
+                sys_column("N_FIELDS", DataType::Int, 4),
+                sys_column("TYPE", DataType::Int, 5),
+                sys_column("SPACE", DataType::Int, 6),
+                sys_column("PAGE_NO", DataType::Int, 7),
+                sys_column("MERGE_THRESHOLD", DataType::Int, 8),
+            ],
+        ),
+        sys_index(
+            SYS_FIELDS_ID,
+            hdr.fields,
+            "SYS_FIELDS",
+            2,
+            vec![
+                sys_column("INDEX_ID", DataType::Bigint, 1),
+                sys_column("POS", DataType::Int, 2),
+                trx_id(),
+                roll_ptr(),
+                sys_column("COL_NAME", DataType::Varchar(MAX_FULL_NAME_LEN), 3),
+            ],
+        ),
+    ];
+    TableInfo {
+        name: String::from("SYS"),
+        columns: Vec::new(),
+        indexes: list.into_iter().map(|x| (x.index_id, x)).collect(),
+        comment: String::new(),
+    }
+}
+
+/// 读取系统表空间第7页的数据字典头
+pub fn read_dict_hdr(ibdata_path: &str) -> io::Result<BasePage<DictHdrPage>> {
+    let buf = TableSpace::read_page_(ibdata_path, DICT_HDR_PAGE_NO)?;
+    Ok(BasePage::new(buf))
+}
+
+/// 系统表空间，表结构为数据字典中的系统表
+pub fn sys_tablespace(ibdata_path: &str) -> io::Result<TableSpace> {
+    let hdr = read_dict_hdr(ibdata_path)?;
+    Ok(TableSpace {
+        table_info: sys_table_info(&hdr),
+        idb_file_path: ibdata_path.to_string(),
+        roll_forward: None,
+        key: None,
+    })
+}
+
+/// 遍历索引所有叶子页中未删除的记录
+fn records(tablespace: &TableSpace, index_id: u64) -> io::Result<Vec<Row<Bytes>>> {
+    let index = tablespace
+        .index(index_id)
+        .ok_or_else(|| io::Error::other(format!("index {} not found", index_id)))?;
+    let mut rows = Vec::new();
+    for page_num in tablespace.leaf_pages(index)? {
+        let page = tablespace.page(page_num)?;
+        let index_page = match page {
+            crate::page::PageEnums::Index(page) => page.page,
+            _ => continue,
+        };
+        for row in index_page.user_records {
+            if row.delete_mask() == 0 {
+                rows.push(row);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+fn col_u32(row: &Row<Bytes>, index: usize) -> u32 {
+    let (data, _) = row.col(index);
+    data.try_into().map(u32::from_be_bytes).unwrap_or(0)
+}
+
+fn col_u64(row: &Row<Bytes>, index: usize) -> u64 {
+    let (data, _) = row.col(index);
+    data.try_into().map(u64::from_be_bytes).unwrap_or(0)
+}
+
+fn col_str(row: &Row<Bytes>, index: usize) -> String {
+    let (data, _) = row.col(index);
+    String::from_utf8_lossy(data).to_string()
+}
+
+/// 读取 SYS_TABLES、SYS_COLUMNS、SYS_INDEXES、SYS_FIELDS 中的所有表定义
+pub fn read_dictionary(ibdata_path: &str) -> io::Result<Vec<DictTable>> {
+    let tablespace = sys_tablespace(ibdata_path)?;
+    let mut tables = Vec::new();
+    for row in records(&tablespace, SYS_TABLES_ID)? {
+        tables.push(DictTable {
+            name: col_str(&row, 0),
+            id: col_u64(&row, 3),
+            n_cols: col_u32(&row, 4),
+            flags: col_u32(&row, 5),
+            space: col_u32(&row, 9),
+            columns: Vec::new(),
+            indexes: Vec::new(),
+        });
+    }
+    let mut by_id: HashMap<u64, usize> = HashMap::new();
+    for (i, table) in tables.iter().enumerate() {
+        by_id.insert(table.id, i);
+    }
+    for row in records(&tablespace, SYS_COLUMNS_ID)? {
+        if let Some(&i) = by_id.get(&col_u64(&row, 0)) {
+            tables[i].columns.push(DictColumn {
+                pos: col_u32(&row, 1),
+                name: col_str(&row, 4),
+                mtype: col_u32(&row, 5),
+                prtype: col_u32(&row, 6),
+                len: col_u32(&row, 7),
+            });
+        }
+    }
+    let mut index_of: HashMap<u64, (usize, usize)> = HashMap::new();
+    for row in records(&tablespace, SYS_INDEXES_ID)? {
+        if let Some(&i) = by_id.get(&col_u64(&row, 0)) {
+            let index = DictIndex {
+                id: col_u64(&row, 1),
+                name: col_str(&row, 4),
+                index_type: col_u32(&row, 6),
+                space: col_u32(&row, 7),
+                page_no: col_u32(&row, 8),
+                fields: Vec::new(),
+                prefix_lens: Vec::new(),
+            };
+            index_of.insert(index.id, (i, tables[i].indexes.len()));
+            tables[i].indexes.push(index);
+        }
+    }
+    for row in records(&tablespace, SYS_FIELDS_ID)? {
+        if let Some(&(i, j)) = index_of.get(&col_u64(&row, 0)) {
+            // 索引中有前缀字段时 POS 为 (位置 << 16) | 前缀长度，第一个字段的位置为0
+            let index = &mut tables[i].indexes[j];
+            let pos = col_u32(&row, 1);
+            let prefix_len = if index.fields.is_empty() || pos > 0xFFFF {
+                pos & 0xFFFF
+            } else {
+                0
+            };
+            index.fields.push(col_str(&row, 4));
+            index.prefix_lens.push(prefix_len as usize);
+        }
+    }
+    Ok(tables)
+}
+
+/// 系统表空间的表结构：系统表的索引，加上存储在系统表空间中的表的索引
+pub fn read_system_table_info(ibdata_path: &str) -> io::Result<TableInfo> {
+    let mut table_info = sys_tablespace(ibdata_path)?.table_info;
+    // MySQL 8.0 的数据字典在 mysql.ibd 中，系统表空间中没有可用的系统表
+    if let Ok(tables) = read_dictionary(ibdata_path) {
+        for table in tables {
+            if table.space == 0 {
+                table_info.indexes.extend(table.table_info().indexes);
+            }
+        }
+    }
+    Ok(table_info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tablespace::data_type::decimal_bin_size;
+
+    #[test]
+    fn decimal_column() {
+        // DECIMAL(10,2)：mtype 为 DATA_FIXBINARY(3)，prtype 低8位为 MYSQL_TYPE_NEWDECIMAL
+        let column = DictColumn {
+            name: String::from("d"),
+            pos: 0,
+            mtype: 3,
+            prtype: 246 | DATA_NOT_NULL,
+            len: 5,
+        };
+        assert!(matches!(column.data_type(), DataType::Decimal(11, 0)));
+        for len in 1..=16 {
+            assert_eq!(decimal_bin_size(decimal_precision(len), 0), len);
+        }
+    }
+}
@@ -0,0 +1,368 @@
+use crate::file_header::page_type::PageType;
+use crate::page::base_page::BasePage;
+use crate::page::hdr_page::encryption::EncryptionInfo;
+use crate::page::hdr_page::page::FspHdrPage;
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::Aes256;
+use bytes::Bytes;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// keyring_file 插件数据文件的文件头
+const KEYRING_FILE_VERSION_1: &[u8] = b"Keyring file version:1.0";
+const KEYRING_FILE_VERSION_2: &[u8] = b"Keyring file version:2.0";
+const KEYRING_FILE_EOF: &[u8] = b"EOF";
+/// 2.0 版本在 EOF 之后有文件内容的 SHA256
+const KEYRING_FILE_DIGEST_LEN: usize = 32;
+/// keyring_file 插件在内存和文件中保存的密钥都与该字符串异或
+const KEYRING_OBFUSCATE_STR: &[u8] = b"*305=Ljt0*!@$Hnm(*-9-w;:";
+/// master_key_id 为0时使用的主密钥
+const DEFAULT_MASTER_KEY: &str = "DefaultMasterKey";
+const MASTER_KEY_PREFIX: &str = "INNODBKey";
+const ENCRYPTION_KEY_LEN: usize = 32;
+const AES_BLOCK_SIZE: usize = 16;
+const FSP_FLAGS_POS_ENCRYPTION: u32 = 13;
+const FIL_PAGE_TYPE: usize = 24;
+const FIL_PAGE_ORIGINAL_TYPE_V1: usize = 28;
+const FIL_PAGE_COMPRESS_SIZE_V1: usize = 32;
+const FIL_PAGE_DATA: usize = 38;
+
+/// keyring 中的一个密钥
+#[derive(Debug)]
+pub struct KeyringKey {
+    pub key_id: String,
+    pub data: Vec<u8>,
+}
+
+/// keyring_file 插件或 component_keyring_file 组件的数据文件
+#[derive(Debug, Default)]
+pub struct Keyring {
+    pub keys: Vec<KeyringKey>,
+}
+
+impl Keyring {
+    /// 按文件内容识别格式：插件的二进制文件以版本字符串开头，组件的文件为JSON
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let buf = fs::read(path)?;
+        let keyring =
+            if buf.starts_with(KEYRING_FILE_VERSION_1) || buf.starts_with(KEYRING_FILE_VERSION_2) {
+                Keyring::parse_file(&buf)
+            } else {
+                Keyring::parse_component(&buf)
+            };
+        keyring.map_err(|e| io::Error::other(format!("keyring {}: {}", path.display(), e)))
+    }
+    /// keyring_file 插件：文件头之后是连续的密钥，以 EOF 结束
+    ///
+    /// 名称 |长度|描述
+    /// ------------------|:--------------------|:------------------
+    /// pod_size |8| 该密钥占用的字节数，包含对齐到8字节的填充
+    /// key_id_len |8|
+    /// key_type_len |8|
+    /// user_id_len |8|
+    /// key_len |8|
+    /// key_id |key_id_len| 密钥名称，如 INNODBKey-{server_uuid}-{id}
+    /// key_type |key_type_len| AES
+    /// user_id |user_id_len|
+    /// key |key_len| 与 KEYRING_OBFUSCATE_STR 异或后的密钥
+    fn parse_file(buf: &[u8]) -> io::Result<Self> {
+        let digest_len = if buf.starts_with(KEYRING_FILE_VERSION_2) {
+            KEYRING_FILE_DIGEST_LEN
+        } else {
+            0
+        };
+        let end = buf
+            .len()
+            .checked_sub(KEYRING_FILE_EOF.len() + digest_len)
+            .filter(|end| buf[*end..].starts_with(KEYRING_FILE_EOF))
+            .ok_or_else(|| io::Error::other("EOF not found"))?;
+        let read_len = |pos: usize| -> io::Result<usize> {
+            let bytes = buf
+                .get(pos..pos + 8)
+                .ok_or_else(|| io::Error::other(format!("truncated key at {}", pos)))?;
+            Ok(u64::from_le_bytes(bytes.try_into().unwrap()) as usize)
+        };
+        let mut keyring = Keyring::default();
+        let mut pos = KEYRING_FILE_VERSION_1.len();
+        while pos < end {
+            let pod_size = read_len(pos)?;
+            let lens = [
+                read_len(pos + 8)?,
+                read_len(pos + 16)?,
+                read_len(pos + 24)?,
+                read_len(pos + 32)?,
+            ];
+            let mut start = pos + 40;
+            let mut fields = Vec::with_capacity(4);
+            for len in lens {
+                let field = buf
+                    .get(start..start + len)
+                    .filter(|_| start + len <= end)
+                    .ok_or_else(|| io::Error::other(format!("truncated key at {}", pos)))?;
+                fields.push(field);
+                start += len;
+            }
+            if pod_size < start - pos {
+                return Err(io::Error::other(format!("invalid key size at {}", pos)));
+            }
+            let data = fields[3]
+                .iter()
+                .zip(KEYRING_OBFUSCATE_STR.iter().cycle())
+                .map(|(b, x)| b ^ x)
+                .collect();
+            keyring.keys.push(KeyringKey {
+                key_id: String::from_utf8_lossy(fields[0]).to_string(),
+                data,
+            });
+            pos += pod_size;
+        }
+        Ok(keyring)
+    }
+    /// component_keyring_file：{"version":"1.0","elements":[{"user","data_id","data_type","data"}]}，
+    /// data 为十六进制的密钥
+    fn parse_component(buf: &[u8]) -> io::Result<Self> {
+        let value: serde_json::Value = serde_json::from_slice(buf)
+            .map_err(|e| io::Error::other(format!("not a keyring file: {}", e)))?;
+        let elements = value["elements"]
+            .as_array()
+            .ok_or_else(|| io::Error::other("elements not found"))?;
+        let field = |element: &serde_json::Value, name: &str| {
+            element[name].as_str().unwrap_or_default().to_string()
+        };
+        let mut keyring = Keyring::default();
+        for element in elements {
+            let data = hex::decode(field(element, "data"))
+                .map_err(|e| io::Error::other(format!("invalid key data: {}", e)))?;
+            keyring.keys.push(KeyringKey {
+                key_id: field(element, "data_id"),
+                data,
+            });
+        }
+        Ok(keyring)
+    }
+    pub fn key(&self, key_id: &str) -> Option<&KeyringKey> {
+        self.keys.iter().find(|key| key.key_id == key_id)
+    }
+    /// Encryption::get_master_key：INNODBKey-{server_uuid}-{master_key_id}；
+    /// 版本1的名称中是 server_id，离线时无法得知，按 master_key_id 匹配
+    pub fn master_key<B: AsRef<[u8]>>(&self, info: &EncryptionInfo<B>) -> io::Result<&KeyringKey> {
+        let master_key_id = info.master_key_id();
+        let key = if master_key_id == 0 {
+            self.key(DEFAULT_MASTER_KEY)
+        } else if let Some(uuid) = info.server_uuid() {
+            self.key(&format!("{}-{}-{}", MASTER_KEY_PREFIX, uuid, master_key_id))
+        } else {
+            let suffix = format!("-{}", master_key_id);
+            self.keys.iter().find(|key| {
+                key.key_id.starts_with(MASTER_KEY_PREFIX) && key.key_id.ends_with(&suffix)
+            })
+        };
+        let key = key.ok_or_else(|| {
+            io::Error::other(format!(
+                "master key {} (server uuid {}) not found in keyring",
+                master_key_id,
+                info.server_uuid().unwrap_or_default()
+            ))
+        })?;
+        if key.data.len() != ENCRYPTION_KEY_LEN {
+            return Err(io::Error::other(format!(
+                "master key {} has invalid length {}",
+                key.key_id,
+                key.data.len()
+            )));
+        }
+        Ok(key)
+    }
+}
+
+/// 表空间密钥，用于解密页
+pub struct TablespaceKey {
+    cipher: Aes256,
+    iv: [u8; AES_BLOCK_SIZE],
+}
+
+impl TablespaceKey {
+    /// 用主密钥解密第0页中的表空间密钥和IV，并用其中的校验和确认主密钥正确
+    pub fn new<B: AsRef<[u8]>>(info: &EncryptionInfo<B>, keyring: &Keyring) -> io::Result<Self> {
+        let master_key = keyring.master_key(info)?;
+        let mut key_info = info.key_info().to_vec();
+        let master = Aes256::new(GenericArray::from_slice(&master_key.data));
+        for block in key_info.chunks_exact_mut(AES_BLOCK_SIZE) {
+            master.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+        if crc32c::crc32c(&key_info) != info.checksum() {
+            return Err(io::Error::other(format!(
+                "tablespace key checksum mismatch, wrong master key {}",
+                master_key.key_id
+            )));
+        }
+        let key = &key_info[..ENCRYPTION_KEY_LEN];
+        Ok(Self {
+            cipher: Aes256::new(GenericArray::from_slice(key)),
+            iv: key_info[ENCRYPTION_KEY_LEN..ENCRYPTION_KEY_LEN + AES_BLOCK_SIZE]
+                .try_into()
+                .unwrap(),
+        })
+    }
+    /// 表空间标记为加密时从第0页读取密钥，未加密的表空间返回 None
+    pub fn load(
+        idb_file_path: &str,
+        page0: Bytes,
+        keyring: Option<&Keyring>,
+    ) -> io::Result<Option<Self>> {
+        let page: BasePage<FspHdrPage> = BasePage::new(page0);
+        if page.fsp_header.space_flags() >> FSP_FLAGS_POS_ENCRYPTION & 1 == 0 {
+            return Ok(None);
+        }
+        let info = page
+            .encryption
+            .as_ref()
+            .ok_or_else(|| io::Error::other(format!("{} has no encryption info", idb_file_path)))?;
+        let keyring = keyring.ok_or_else(|| {
+            io::Error::other(format!(
+                "{} is encrypted, use --keyring to provide the keyring file",
+                idb_file_path
+            ))
+        })?;
+        Ok(Some(TablespaceKey::new(info, keyring)?))
+    }
+    /// Encryption::decrypt：页头之后的数据用 AES-256-CBC 加密，长度不是16的倍数时，
+    /// 最后32字节单独加密，解密后恢复原来的页类型。未加密的页原样返回
+    pub fn decrypt_page(&self, buf: Bytes) -> io::Result<Bytes> {
+        let page_type = PageType::from(u16::from_be_bytes([buf[24], buf[25]]));
+        let data_len = match page_type {
+            PageType::FilPageEncrypted | PageType::FilPageEncryptedRtree => {
+                buf.len() - FIL_PAGE_DATA
+            }
+            PageType::FilPageCompressedAndEncrypted => {
+                let offset = FIL_PAGE_COMPRESS_SIZE_V1;
+                u16::from_be_bytes([buf[offset], buf[offset + 1]]) as usize
+            }
+            _ => return Ok(buf),
+        };
+        if data_len < AES_BLOCK_SIZE * 2 || FIL_PAGE_DATA + data_len > buf.len() {
+            return Err(io::Error::other(format!(
+                "invalid encrypted data length {}",
+                data_len
+            )));
+        }
+        let mut page = buf.to_vec();
+        let data = &mut page[FIL_PAGE_DATA..FIL_PAGE_DATA + data_len];
+        let main_len = data_len / AES_BLOCK_SIZE * AES_BLOCK_SIZE;
+        if main_len != data_len {
+            // 先解密最后两个块，其中包含了主体最后一个块的后半部分
+            let remain_len = AES_BLOCK_SIZE * 2;
+            self.decrypt_cbc(&mut data[data_len - remain_len..]);
+        }
+        self.decrypt_cbc(&mut data[..main_len]);
+        let original_type = match page_type {
+            PageType::FilPageEncrypted => {
+                let offset = FIL_PAGE_ORIGINAL_TYPE_V1;
+                let original_type = [page[offset], page[offset + 1]];
+                page[offset..offset + 2].fill(0);
+                original_type
+            }
+            PageType::FilPageEncryptedRtree => (PageType::FilPageRtree as u16).to_be_bytes(),
+            _ => (PageType::FilPageCompressed as u16).to_be_bytes(),
+        };
+        page[FIL_PAGE_TYPE..FIL_PAGE_TYPE + 2].copy_from_slice(&original_type);
+        Ok(Bytes::from(page))
+    }
+    fn decrypt_cbc(&self, data: &mut [u8]) {
+        let mut prev = self.iv;
+        for block in data.chunks_exact_mut(AES_BLOCK_SIZE) {
+            let cipher_block: [u8; AES_BLOCK_SIZE] = (*block).try_into().unwrap();
+            self.cipher
+                .decrypt_block(GenericArray::from_mut_slice(block));
+            block.iter_mut().zip(prev).for_each(|(b, p)| *b ^= p);
+            prev = cipher_block;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UUID: &str = "3e11fa47-71ca-11e1-9e33-c80aa9429562";
+    /// 表空间密钥 100..132 和 IV 200..232 用主密钥 0..32 以 AES-256-ECB 加密的结果
+    const KEY_INFO: &str = "93d81120b620bb4d7e8dbfabe6bf90dc4a5fdf6acb953ceefd636acc79b2d0d3\
+                            a973879da73d9a7058d60ff21892f7fe545da16a6168df25df33806f0d287f19";
+    /// 50字节的 i * 7 % 251 按 Encryption::encrypt 加密：前48字节 AES-256-CBC，再加密最后32字节
+    const DATA: &str = "91c0c77acf069757f857ab95eb16e635001d86891b543b3b8e75a45924b87969\
+                        bf807729c350c868945552be4bb4bb7b9e3c";
+
+    fn encryption_info() -> EncryptionInfo<Vec<u8>> {
+        let plain: Vec<u8> = (100..132).chain(200..232).collect();
+        let mut buf = b"lCC".to_vec();
+        buf.extend(1_u32.to_be_bytes());
+        buf.extend(UUID.as_bytes());
+        buf.extend(hex::decode(KEY_INFO).unwrap());
+        buf.extend(crc32c::crc32c(&plain).to_be_bytes());
+        EncryptionInfo::new(buf)
+    }
+
+    fn keyring(master_key: Vec<u8>) -> Keyring {
+        Keyring {
+            keys: vec![KeyringKey {
+                key_id: format!("{}-{}-1", MASTER_KEY_PREFIX, UUID),
+                data: master_key,
+            }],
+        }
+    }
+
+    #[test]
+    fn decrypt_page() {
+        let key = TablespaceKey::new(&encryption_info(), &keyring((0..32).collect())).unwrap();
+        let mut page = vec![0; FIL_PAGE_DATA];
+        page[FIL_PAGE_TYPE..FIL_PAGE_TYPE + 2]
+            .copy_from_slice(&(PageType::FilPageEncrypted as u16).to_be_bytes());
+        page[FIL_PAGE_ORIGINAL_TYPE_V1..FIL_PAGE_ORIGINAL_TYPE_V1 + 2]
+            .copy_from_slice(&(PageType::FilPageIndex as u16).to_be_bytes());
+        page.extend(hex::decode(DATA).unwrap());
+        let page = key.decrypt_page(Bytes::from(page)).unwrap();
+        let page_type = u16::from_be_bytes([page[FIL_PAGE_TYPE], page[FIL_PAGE_TYPE + 1]]);
+        assert_eq!(page_type, PageType::FilPageIndex as u16);
+        assert_eq!(
+            page[FIL_PAGE_ORIGINAL_TYPE_V1..FIL_PAGE_ORIGINAL_TYPE_V1 + 2],
+            [0, 0]
+        );
+        let data: Vec<u8> = (0..50).map(|i| (i * 7 % 251) as u8).collect();
+        assert_eq!(page[FIL_PAGE_DATA..], data);
+    }
+
+    #[test]
+    fn wrong_master_key() {
+        let result = TablespaceKey::new(&encryption_info(), &keyring(vec![1; 32]));
+        assert!(result.is_err_and(|e| e.to_string().contains("checksum mismatch")));
+        let result = TablespaceKey::new(&encryption_info(), &keyring(vec![1; 16]));
+        assert!(result.is_err_and(|e| e.to_string().contains("invalid length")));
+    }
+
+    #[test]
+    fn keyring_file() {
+        let key_id = b"INNODBKey-uuid-1";
+        let key: Vec<u8> = (0..32).collect();
+        let mut pod = Vec::new();
+        for len in [key_id.len(), 3, 0, key.len()] {
+            pod.extend((len as u64).to_le_bytes());
+        }
+        pod.extend(key_id);
+        pod.extend(b"AES");
+        pod.extend(
+            key.iter()
+                .zip(KEYRING_OBFUSCATE_STR.iter().cycle())
+                .map(|(b, x)| b ^ x),
+        );
+        pod.resize(pod.len().next_multiple_of(8) + 8, 0);
+        let mut buf = KEYRING_FILE_VERSION_1.to_vec();
+        buf.extend((pod.len() as u64 + 8).to_le_bytes());
+        buf.extend(pod);
+        buf.extend(KEYRING_FILE_EOF);
+        let keyring = Keyring::parse_file(&buf).unwrap();
+        assert_eq!(keyring.keys.len(), 1);
+        assert_eq!(keyring.key("INNODBKey-uuid-1").unwrap().data, key);
+    }
+}
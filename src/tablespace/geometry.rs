@@ -0,0 +1,319 @@
+use crate::tablespace::data_type::DataType;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// R-tree 索引记录中 MBR 的长度，4个 double
+pub const DATA_MBR_LEN: usize = 32;
+
+/// 最小外包矩形，R-tree 索引记录的键
+///
+/// 名称 |长度|描述
+/// ------------------|:--------------------|:------------------
+/// xmin |8| 小端 double
+/// xmax |8|
+/// ymin |8|
+/// ymax |8|
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Mbr {
+    pub xmin: f64,
+    pub xmax: f64,
+    pub ymin: f64,
+    pub ymax: f64,
+}
+
+impl Mbr {
+    pub fn new(buf: &[u8]) -> io::Result<Mbr> {
+        if buf.len() != DATA_MBR_LEN {
+            return Err(io::Error::other(format!(
+                "invalid mbr length {}",
+                buf.len()
+            )));
+        }
+        let read = |i: usize| f64::from_le_bytes(buf[i * 8..i * 8 + 8].try_into().unwrap());
+        Ok(Mbr {
+            xmin: read(0),
+            xmax: read(1),
+            ymin: read(2),
+            ymax: read(3),
+        })
+    }
+    /// 包含两个矩形的最小矩形
+    pub fn union(&self, other: &Mbr) -> Mbr {
+        Mbr {
+            xmin: self.xmin.min(other.xmin),
+            xmax: self.xmax.max(other.xmax),
+            ymin: self.ymin.min(other.ymin),
+            ymax: self.ymax.max(other.ymax),
+        }
+    }
+    pub fn contains(&self, other: &Mbr) -> bool {
+        self.xmin <= other.xmin
+            && self.xmax >= other.xmax
+            && self.ymin <= other.ymin
+            && self.ymax >= other.ymax
+    }
+}
+
+impl Display for Mbr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "BOX({} {},{} {})",
+            self.xmin, self.ymin, self.xmax, self.ymax
+        )
+    }
+}
+
+/// WKB 中的几何对象
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Point(f64, f64),
+    LineString(Vec<(f64, f64)>),
+    Polygon(Vec<Vec<(f64, f64)>>),
+    MultiPoint(Vec<Shape>),
+    MultiLineString(Vec<Shape>),
+    MultiPolygon(Vec<Shape>),
+    GeometryCollection(Vec<Shape>),
+}
+
+impl Shape {
+    fn name(&self) -> &'static str {
+        match self {
+            Shape::Point(..) => "POINT",
+            Shape::LineString(_) => "LINESTRING",
+            Shape::Polygon(_) => "POLYGON",
+            Shape::MultiPoint(_) => "MULTIPOINT",
+            Shape::MultiLineString(_) => "MULTILINESTRING",
+            Shape::MultiPolygon(_) => "MULTIPOLYGON",
+            Shape::GeometryCollection(_) => "GEOMETRYCOLLECTION",
+        }
+    }
+    /// WKT 中类型名之后的部分
+    fn write_body(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let points = |f: &mut Formatter<'_>, points: &[(f64, f64)]| {
+            write!(f, "(")?;
+            for (i, (x, y)) in points.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write!(f, "{} {}", x, y)?;
+            }
+            write!(f, ")")
+        };
+        match self {
+            Shape::Point(x, y) => write!(f, "({} {})", x, y),
+            Shape::LineString(line) => points(f, line),
+            Shape::Polygon(rings) => {
+                write!(f, "(")?;
+                for (i, ring) in rings.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    points(f, ring)?;
+                }
+                write!(f, ")")
+            }
+            Shape::MultiPoint(shapes)
+            | Shape::MultiLineString(shapes)
+            | Shape::MultiPolygon(shapes) => {
+                write!(f, "(")?;
+                for (i, shape) in shapes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    shape.write_body(f)?;
+                }
+                write!(f, ")")
+            }
+            Shape::GeometryCollection(shapes) => {
+                write!(f, "(")?;
+                for (i, shape) in shapes.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", shape)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+        self.write_body(f)
+    }
+}
+
+/// GEOMETRY 列的存储格式：4字节小端 SRID，之后为 WKB
+#[derive(Debug, Clone, PartialEq)]
+pub struct Geometry {
+    pub srid: u32,
+    pub shape: Shape,
+}
+
+impl Geometry {
+    pub fn new(buf: &[u8]) -> io::Result<Geometry> {
+        let srid = buf
+            .get(..4)
+            .map(|x| u32::from_le_bytes(x.try_into().unwrap()))
+            .ok_or_else(|| io::Error::other("geometry too short"))?;
+        let mut reader = WkbReader {
+            buf: &buf[4..],
+            pos: 0,
+            little_endian: true,
+        };
+        let shape = reader.shape()?;
+        if reader.pos != reader.buf.len() {
+            return Err(io::Error::other(format!(
+                "{} trailing bytes after geometry",
+                reader.buf.len() - reader.pos
+            )));
+        }
+        Ok(Geometry { srid, shape })
+    }
+}
+
+impl Display for Geometry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "SRID={};{}", self.srid, self.shape)
+    }
+}
+
+struct WkbReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn bytes<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| io::Error::other(format!("wkb truncated at {}", self.pos)))?;
+        self.pos += N;
+        Ok(bytes.try_into().unwrap())
+    }
+    fn u32(&mut self) -> io::Result<u32> {
+        let bytes = self.bytes::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+    fn f64(&mut self) -> io::Result<f64> {
+        let bytes = self.bytes::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+    fn point(&mut self) -> io::Result<(f64, f64)> {
+        Ok((self.f64()?, self.f64()?))
+    }
+    fn points(&mut self) -> io::Result<Vec<(f64, f64)>> {
+        let n = self.u32()? as usize;
+        // 每个点16字节，先检查长度，避免损坏的数据申请过大的内存
+        if n > (self.buf.len() - self.pos) / 16 {
+            return Err(io::Error::other(format!("invalid point count {}", n)));
+        }
+        (0..n).map(|_| self.point()).collect()
+    }
+    fn shapes(&mut self) -> io::Result<Vec<Shape>> {
+        let n = self.u32()?;
+        (0..n).map(|_| self.shape()).collect()
+    }
+    /// 每个几何对象以字节序(1为小端)和类型开头，集合中的每个元素都是完整的 WKB
+    fn shape(&mut self) -> io::Result<Shape> {
+        let [byte_order] = self.bytes::<1>()?;
+        self.little_endian = byte_order == 1;
+        let shape = match self.u32()? {
+            1 => {
+                let (x, y) = self.point()?;
+                Shape::Point(x, y)
+            }
+            2 => Shape::LineString(self.points()?),
+            3 => {
+                let n = self.u32()?;
+                let rings = (0..n).map(|_| self.points()).collect::<io::Result<_>>()?;
+                Shape::Polygon(rings)
+            }
+            4 => Shape::MultiPoint(self.shapes()?),
+            5 => Shape::MultiLineString(self.shapes()?),
+            6 => Shape::MultiPolygon(self.shapes()?),
+            7 => Shape::GeometryCollection(self.shapes()?),
+            x => return Err(io::Error::other(format!("unknown wkb type {}", x))),
+        };
+        Ok(shape)
+    }
+}
+
+/// GEOMETRY 列显示为 WKT，R-tree 索引中的 MBR 显示为矩形，其它类型返回 None
+pub fn format_geometry(data_type: DataType, data: &[u8]) -> Option<io::Result<String>> {
+    match data_type {
+        DataType::Geometry => Some(Geometry::new(data).map(|x| x.to_string())),
+        DataType::Mbr => Some(Mbr::new(data).map(|x| x.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mbr_bytes(values: [f64; 4]) -> Vec<u8> {
+        values.iter().flat_map(|x| x.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn mbr() {
+        let a = Mbr::new(&mbr_bytes([0.0, 2.0, 0.0, 1.0])).unwrap();
+        let b = Mbr::new(&mbr_bytes([1.0, 3.0, -1.0, 0.5])).unwrap();
+        assert_eq!(a.to_string(), "BOX(0 0,2 1)");
+        let parent = a.union(&b);
+        assert_eq!(parent.to_string(), "BOX(0 -1,3 1)");
+        assert!(parent.contains(&a) && parent.contains(&b));
+        assert!(!a.contains(&b));
+        assert!(Mbr::new(&[0; 16]).is_err());
+    }
+
+    #[test]
+    fn wkb() {
+        // SRID 4326 的 POINT(1 2)
+        let mut point = 4326_u32.to_le_bytes().to_vec();
+        point.extend([1, 1, 0, 0, 0]);
+        point.extend(1.0_f64.to_le_bytes());
+        point.extend(2.0_f64.to_le_bytes());
+        assert_eq!(
+            format_geometry(DataType::Geometry, &point)
+                .unwrap()
+                .unwrap(),
+            "SRID=4326;POINT(1 2)"
+        );
+
+        // 集合中的元素各自带字节序：大端的 POLYGON 和小端的 LINESTRING
+        let mut collection = vec![0, 0, 0, 0, 1, 7, 0, 0, 0, 2, 0, 0, 0];
+        collection.extend([0, 0, 0, 0, 3, 0, 0, 0, 1, 0, 0, 0, 4]);
+        for (x, y) in [(0.0_f64, 0.0_f64), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)] {
+            collection.extend(x.to_be_bytes());
+            collection.extend(y.to_be_bytes());
+        }
+        collection.extend([1, 2, 0, 0, 0, 2, 0, 0, 0]);
+        for value in [0.5_f64, 1.5, 2.5, 3.5] {
+            collection.extend(value.to_le_bytes());
+        }
+        assert_eq!(
+            Geometry::new(&collection).unwrap().to_string(),
+            "SRID=0;GEOMETRYCOLLECTION(POLYGON((0 0,1 0,1 1,0 0)),LINESTRING(0.5 1.5,2.5 3.5))"
+        );
+
+        assert!(Geometry::new(&point[..point.len() - 1]).is_err());
+        point.push(0);
+        assert!(Geometry::new(&point).is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::data_value::DataValue;
+use crate::tablespace::temporal::{format_datetime, format_time};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::Value;
+use std::io;
+
+// 值类型
+const SMALL_OBJECT: u8 = 0x00;
+const LARGE_OBJECT: u8 = 0x01;
+const SMALL_ARRAY: u8 = 0x02;
+const LARGE_ARRAY: u8 = 0x03;
+const LITERAL: u8 = 0x04;
+const INT16: u8 = 0x05;
+const UINT16: u8 = 0x06;
+const INT32: u8 = 0x07;
+const UINT32: u8 = 0x08;
+const INT64: u8 = 0x09;
+const UINT64: u8 = 0x0a;
+const DOUBLE: u8 = 0x0b;
+const STRING: u8 = 0x0c;
+const OPAQUE: u8 = 0x0f;
+
+// 字面量
+const LITERAL_NULL: u8 = 0x00;
+const LITERAL_TRUE: u8 = 0x01;
+const LITERAL_FALSE: u8 = 0x02;
+
+/// 对象和数组的最大嵌套层数，与 MySQL 的 JSON_DOCUMENT_MAX_DEPTH 相同
+const MAX_DEPTH: usize = 100;
+
+// OPAQUE 值中的 MySQL 字段类型
+const MYSQL_TYPE_TIMESTAMP: u8 = 7;
+const MYSQL_TYPE_DATE: u8 = 10;
+const MYSQL_TYPE_TIME: u8 = 11;
+const MYSQL_TYPE_DATETIME: u8 = 12;
+const MYSQL_TYPE_NEWDECIMAL: u8 = 246;
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::other(format!("invalid binary json: {}", msg))
+}
+
+/// JSON 列的值，非 JSON 类型返回 None
+pub fn format_json(data_type: DataType, data: &[u8]) -> Option<io::Result<String>> {
+    match data_type {
+        DataType::Json => Some(parse(data)),
+        _ => None,
+    }
+}
+
+/// 解析 MySQL 二进制 JSON 并转换为 JSON 文本，第一个字节为值类型，之后为值。空值为 JSON null。
+/// 对象的键按存储顺序输出，数字按原样输出(DECIMAL 不会损失精度)
+///
+/// 对象和数组分为小(偏移量和大小2字节)和大(4字节)两种格式：
+///
+/// 名称 |长度|描述
+/// ---|---|---
+/// element_count|2/4|元素个数
+/// size|2/4|对象或数组的总字节数
+/// key_entry|(2/4+2)*n|对象才有，键的偏移量和长度
+/// value_entry|(1+2/4)*n|值类型和偏移量，字面量和较短的整数直接存放在偏移量处
+/// key|可变|键
+/// value|可变|值
+///
+/// 偏移量均相对对象或数组开始处(值类型之后)，指向键和值所在的区域
+///
+/// 溢出页中的 JSON 需要读取 LOB 页，目前不解析，调用方只会传入页内存储的完整值
+pub fn parse(buf: &[u8]) -> io::Result<String> {
+    match buf.split_first() {
+        None => Ok(String::from("null")),
+        Some((&value_type, data)) => parse_value(value_type, data, 0),
+    }
+}
+
+fn parse_value(value_type: u8, data: &[u8], depth: usize) -> io::Result<String> {
+    match value_type {
+        SMALL_OBJECT => parse_container(data, false, true, depth),
+        LARGE_OBJECT => parse_container(data, true, true, depth),
+        SMALL_ARRAY => parse_container(data, false, false, depth),
+        LARGE_ARRAY => parse_container(data, true, false, depth),
+        LITERAL => match read(data, 0, 1)?[0] {
+            LITERAL_NULL => Ok(String::from("null")),
+            LITERAL_TRUE => Ok(String::from("true")),
+            LITERAL_FALSE => Ok(String::from("false")),
+            other => Err(invalid(&format!("literal {}", other))),
+        },
+        INT16 => Ok(i16::from_le_bytes(array(data)?).to_string()),
+        UINT16 => Ok(u16::from_le_bytes(array(data)?).to_string()),
+        INT32 => Ok(i32::from_le_bytes(array(data)?).to_string()),
+        UINT32 => Ok(u32::from_le_bytes(array(data)?).to_string()),
+        INT64 => Ok(i64::from_le_bytes(array(data)?).to_string()),
+        UINT64 => Ok(u64::from_le_bytes(array(data)?).to_string()),
+        DOUBLE => Ok(Value::from(f64::from_le_bytes(array(data)?)).to_string()),
+        STRING => {
+            let (len, offset) = read_var_len(data)?;
+            let value = read(data, offset, len)?;
+            String::from_utf8(value.to_vec())
+                .map(quote)
+                .map_err(|_| invalid("string is not utf8"))
+        }
+        OPAQUE => {
+            let field_type = read(data, 0, 1)?[0];
+            let (len, offset) = read_var_len(&data[1..])?;
+            parse_opaque(field_type, read(data, offset + 1, len)?)
+        }
+        other => Err(invalid(&format!("value type {}", other))),
+    }
+}
+
+fn parse_container(data: &[u8], large: bool, is_object: bool, depth: usize) -> io::Result<String> {
+    if depth >= MAX_DEPTH {
+        return Err(invalid("nested too deep"));
+    }
+    let offset_size = if large { 4 } else { 2 };
+    let read_offset = |offset: usize| -> io::Result<usize> {
+        let buf = read(data, offset, offset_size)?;
+        Ok(buf
+            .iter()
+            .rev()
+            .fold(0usize, |acc, x| (acc << 8) | *x as usize))
+    };
+    let count = read_offset(0)?;
+    let size = read_offset(offset_size)?;
+    let data = read(data, 0, size)?;
+    let header_len = 2 * offset_size;
+    let key_entry_size = offset_size + 2;
+    let value_entry_size = 1 + offset_size;
+    let value_entries = header_len + if is_object { count * key_entry_size } else { 0 };
+    // 键和值只能存放在头部和各个条目之后，指向头部以内的偏移量说明数据已损坏
+    let entries_end = value_entries + count * value_entry_size;
+    if entries_end > data.len() {
+        return Err(invalid("element count out of range"));
+    }
+    let check_offset = |offset: usize| {
+        if offset < entries_end {
+            Err(invalid("offset points into header"))
+        } else {
+            Ok(offset)
+        }
+    };
+    let mut values = Vec::with_capacity(count);
+    for i in 0..count {
+        let entry = value_entries + i * value_entry_size;
+        let value_type = read(data, entry, 1)?[0];
+        let inlined = matches!(value_type, LITERAL | INT16 | UINT16)
+            || (large && matches!(value_type, INT32 | UINT32));
+        let value = if inlined {
+            parse_value(value_type, read(data, entry + 1, offset_size)?, depth + 1)?
+        } else {
+            let offset = check_offset(read_offset(entry + 1)?)?;
+            let value = data
+                .get(offset..)
+                .ok_or_else(|| invalid("offset out of range"))?;
+            parse_value(value_type, value, depth + 1)?
+        };
+        values.push(value);
+    }
+    if !is_object {
+        return Ok(format!("[{}]", values.join(", ")));
+    }
+    let mut members = Vec::with_capacity(count);
+    for (i, value) in values.into_iter().enumerate() {
+        let entry = header_len + i * key_entry_size;
+        let key_offset = check_offset(read_offset(entry)?)?;
+        let key_len = u16::from_le_bytes(array(read(data, entry + offset_size, 2)?)?) as usize;
+        let key = String::from_utf8(read(data, key_offset, key_len)?.to_vec())
+            .map_err(|_| invalid("key is not utf8"))?;
+        members.push(format!("{}: {}", quote(key), value));
+    }
+    Ok(format!("{{{}}}", members.join(", ")))
+}
+
+/// 转义后加上引号的 JSON 字符串
+fn quote(value: String) -> String {
+    Value::String(value).to_string()
+}
+
+/// OPAQUE 值：DECIMAL 和时间类型转换为数字和字符串，其它类型显示为 base64:type字段类型:数据
+fn parse_opaque(field_type: u8, data: &[u8]) -> io::Result<String> {
+    match field_type {
+        MYSQL_TYPE_NEWDECIMAL if data.len() > 2 => {
+            let value = DataValue::decimal(data[0] as usize, data[1] as usize, &data[2..])?;
+            Ok(value.to_string())
+        }
+        MYSQL_TYPE_DATE | MYSQL_TYPE_DATETIME | MYSQL_TYPE_TIMESTAMP | MYSQL_TYPE_TIME
+            if data.len() == 8 =>
+        {
+            let packed = i64::from_le_bytes(array(data)?);
+            let value = match field_type {
+                MYSQL_TYPE_TIME => format_time(packed, 6),
+                MYSQL_TYPE_DATE => format_datetime(packed >> 24, 0, 0)[..10].to_string(),
+                _ => format_datetime(packed >> 24, packed & 0xFF_FFFF, 6),
+            };
+            Ok(quote(value))
+        }
+        _ => Ok(quote(format!(
+            "base64:type{}:{}",
+            field_type,
+            STANDARD.encode(data)
+        ))),
+    }
+}
+
+/// 变长的长度，每字节低7位为值，最高位为1表示后面还有字节，低位在前
+fn read_var_len(data: &[u8]) -> io::Result<(usize, usize)> {
+    let mut len = 0;
+    for (i, x) in data.iter().take(5).enumerate() {
+        len |= ((x & 0x7F) as usize) << (7 * i);
+        if x & 0x80 == 0 {
+            return Ok((len, i + 1));
+        }
+    }
+    Err(invalid("variable length"))
+}
+
+fn read(data: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+    data.get(offset..offset + len)
+        .ok_or_else(|| invalid("offset out of range"))
+}
+
+fn array<const N: usize>(data: &[u8]) -> io::Result<[u8; N]> {
+    data.get(..N)
+        .and_then(|x| x.try_into().ok())
+        .ok_or_else(|| invalid("value too short"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_object() {
+        // {"a": 1, "b": {"c": [true, null]}}
+        let buf = [
+            0x00, // 小对象
+            0x02, 0x00, 0x2A, 0x00, // 2个元素，42字节
+            0x12, 0x00, 0x01, 0x00, 0x13, 0x00, 0x01, 0x00, // 键 "a"、"b"
+            0x05, 0x01, 0x00, 0x00, 0x14, 0x00, // INT16 1 直接存放，"b" 为小对象
+            b'a', b'b', // 键
+            0x01, 0x00, 0x16, 0x00, // 1个元素，22字节
+            0x0B, 0x00, 0x01, 0x00, // 键 "c"
+            0x02, 0x0C, 0x00, // 小数组
+            b'c', // 键
+            0x02, 0x00, 0x0A, 0x00, // 2个元素，10字节
+            0x04, 0x01, 0x00, 0x04, 0x00, 0x00, // true、null
+        ];
+        assert_eq!(
+            parse(&buf).unwrap(),
+            r#"{"a": 1, "b": {"c": [true, null]}}"#
+        );
+    }
+
+    #[test]
+    fn offset_out_of_range() {
+        // 值的偏移量超出对象
+        let buf = [
+            0x00, 0x01, 0x00, 0x0C, 0x00, 0x0B, 0x00, 0x01, 0x00, 0x0C, 0x20, 0x00, b'a',
+        ];
+        assert!(parse(&buf).is_err());
+    }
+}
@@ -1,31 +1,135 @@
 use crate::file_header::page_type::PageType;
+use crate::file_header::FileHeader;
 use crate::page;
 use crate::page::base_page::BasePage;
+use crate::page::compressed_page;
 use crate::page::hdr_page::page::FspHdrPage;
+use crate::page::ibuf_bitmap_page::{IbufBitmapEntry, IBUF_BITMAP_PAGES};
 use crate::page::PageEnums;
-use crate::tablespace::table::{read_table_info, Index, TableInfo};
+use crate::redo::apply::{PageRecovery, RollForward};
+use crate::tablespace::dictionary::{read_dictionary, read_system_table_info};
+use crate::tablespace::encryption::{Keyring, TablespaceKey};
+use crate::tablespace::table::{
+    read_frm_table_info, read_schema_table_info, read_table_info, Index, TableInfo,
+};
 use bytes::Bytes;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io;
 use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
 
+pub mod charset;
+pub mod checksum;
 pub mod data_type;
 pub mod data_value;
+pub mod dblwr;
+pub mod dictionary;
+pub mod encryption;
+pub mod geometry;
+pub mod json;
+pub mod rtree;
+pub mod schema;
 pub mod table;
+pub mod temporal;
+pub mod undo;
 
 pub struct TableSpace {
     pub table_info: TableInfo,
     pub idb_file_path: String,
+    /// 设置后读取的页会应用redo日志
+    pub roll_forward: Option<RollForward>,
+    /// 加密表空间的密钥，读取的页会先解密
+    pub key: Option<TablespaceKey>,
 }
 
 impl TableSpace {
-    pub fn new(idb_file_path: String) -> io::Result<Self> {
-        // todo 从fsp页中读取sdi页号
-        let table_info = read_table_info(&idb_file_path, 3)?;
+    pub fn new(idb_file_path: String, keyring: Option<&Keyring>) -> io::Result<Self> {
+        let page0 = TableSpace::read_page_(&idb_file_path, 0)?;
+        let space_id = FileHeader::new(page0.slice(..38)).space_id();
+        let key = TablespaceKey::load(&idb_file_path, page0, keyring)?;
+        if space_id == 0 {
+            // 系统表空间，表结构来自数据字典
+            let table_info = read_system_table_info(&idb_file_path)?;
+            return Ok(Self {
+                table_info,
+                idb_file_path,
+                roll_forward: None,
+                key,
+            });
+        }
+        if is_undo_file(Path::new(&idb_file_path)) {
+            // undo表空间没有表结构
+            return Ok(Self {
+                table_info: TableInfo {
+                    name: idb_file_path.clone(),
+                    columns: Vec::new(),
+                    indexes: HashMap::new(),
+                    comment: String::new(),
+                },
+                idb_file_path,
+                roll_forward: None,
+                key,
+            });
+        }
+        let buf = TableSpace::read_logical_page_(&idb_file_path, 3, key.as_ref())?;
+        let table_info = if FileHeader::new(buf.slice(..38)).page_type() == PageType::FilPageSdi {
+            // todo 从fsp页中读取sdi页号
+            read_table_info(&idb_file_path, 3, key.as_ref())?
+        } else {
+            // MySQL 5.7 没有sdi，从同目录下的.frm文件读取表结构，
+            // 没有.frm时从数据目录下 ibdata1 的数据字典中按 space_id 查找
+            let path = Path::new(&idb_file_path);
+            let frm_path = path.with_extension("frm");
+            let ibdata_path = path
+                .parent()
+                .and_then(Path::parent)
+                .map(|dir| dir.join("ibdata1"));
+            if frm_path.exists() {
+                read_frm_table_info(&idb_file_path, &frm_path, key.as_ref())?
+            } else if let Some(ibdata_path) = ibdata_path.filter(|x| x.exists()) {
+                let table = read_dictionary(&ibdata_path.to_string_lossy())?
+                    .into_iter()
+                    .find(|table| table.space == space_id)
+                    .ok_or_else(|| {
+                        io::Error::other(format!(
+                            "space_id {} not found in {}",
+                            space_id,
+                            ibdata_path.display()
+                        ))
+                    })?;
+                table.table_info()
+            } else {
+                return Err(io::Error::other(format!(
+                    "{} has no sdi and {} not found, use --schema to provide the table definition",
+                    idb_file_path,
+                    frm_path.display()
+                )));
+            }
+        };
         Ok(Self {
             table_info,
             idb_file_path,
+            roll_forward: None,
+            key,
+        })
+    }
+    /// 使用建表语句作为表结构，用于sdi损坏或缺少.frm的表空间
+    pub fn with_schema(
+        idb_file_path: String,
+        schema_path: &Path,
+        index_ids: &HashMap<String, u64>,
+        keyring: Option<&Keyring>,
+    ) -> io::Result<Self> {
+        let page0 = TableSpace::read_page_(&idb_file_path, 0)?;
+        let key = TablespaceKey::load(&idb_file_path, page0, keyring)?;
+        let table_info =
+            read_schema_table_info(&idb_file_path, schema_path, index_ids, key.as_ref())?;
+        Ok(Self {
+            table_info,
+            idb_file_path,
+            roll_forward: None,
+            key,
         })
     }
 }
@@ -39,9 +143,20 @@ impl TableSpace {
         let buf = self.read_page(page_num)?;
         Ok(page::page(buf, &self.table_info))
     }
+    /// change buffer 位图中有标志的页，位图页为每 16384 个页的第2页
+    pub fn ibuf_bitmap(&self) -> io::Result<Vec<(u32, IbufBitmapEntry)>> {
+        let size = self.fsp_page()?.fsp_header.size();
+        let mut entries = Vec::new();
+        for bitmap_page_num in (1..size).step_by(IBUF_BITMAP_PAGES as usize) {
+            if let PageEnums::IbufBitmap(page) = self.page(bitmap_page_num)? {
+                entries.extend(page.entries().filter(|(page_num, _)| *page_num < size));
+            }
+        }
+        Ok(entries)
+    }
     pub fn index_roots(&self) -> Vec<(String, u32)> {
         let mut v = Vec::new();
-        for (_, index) in &self.table_info.indexes {
+        for index in self.table_info.indexes.values() {
             v.push((index.name.clone(), index.root_page_num));
         }
         v
@@ -49,17 +164,160 @@ impl TableSpace {
 }
 
 impl TableSpace {
+    /// 从根节点沿着最左边的子页找到最左叶子页，再沿着next遍历所有叶子页
+    pub fn leaf_pages(&self, index: &Index) -> io::Result<Vec<u32>> {
+        let mut page_num = index.root_page_num;
+        loop {
+            let page = self.page(page_num)?;
+            let index_page = page.index_page().ok_or_else(|| {
+                io::Error::other(format!("page {} is not an index page", page_num))
+            })?;
+            if index_page.index_header.level() == 0 {
+                break;
+            }
+            page_num = index_page
+                .user_records
+                .first()
+                .and_then(|row| index_page.child_page_num(row))
+                .ok_or_else(|| io::Error::other(format!("page {} has no child", page_num)))?;
+        }
+        let mut pages = Vec::new();
+        let mut visited = HashSet::new();
+        while page_num != u32::MAX {
+            if !visited.insert(page_num) {
+                return Err(io::Error::other(format!("leaf page {} loops", page_num)));
+            }
+            pages.push(page_num);
+            let buf = self.read_page(page_num)?;
+            page_num = FileHeader::new(buf.slice(..38)).next();
+        }
+        Ok(pages)
+    }
+    /// 扫描所有索引页，取每个 index_id 层级最高的页作为根页，按 index_id 排序
+    pub fn scan_index_roots(
+        idb_file_path: &str,
+        key: Option<&TablespaceKey>,
+    ) -> io::Result<Vec<(u64, u32)>> {
+        let mut file = File::open(idb_file_path)?;
+        let page_count = file.metadata()?.len() / (16 * 1024);
+        let mut roots: HashMap<u64, (u16, u32)> = HashMap::new();
+        let mut buf = vec![0; 16 * 1024];
+        for page_num in 0..page_count as u32 {
+            file.read_exact(&mut buf)?;
+            buf = TableSpace::logical_page(Bytes::from(buf), key)?.to_vec();
+            let page_type = PageType::from(u16::from_be_bytes([buf[24], buf[25]]));
+            if page_type != PageType::FilPageIndex && page_type != PageType::FilPageRtree {
+                continue;
+            }
+            let level = u16::from_be_bytes([buf[64], buf[65]]);
+            let index_id = u64::from_be_bytes(buf[66..74].try_into().unwrap());
+            let root = roots.entry(index_id).or_insert((level, page_num));
+            if level > root.0 {
+                *root = (level, page_num);
+            }
+        }
+        let mut roots: Vec<(u64, u32)> = roots
+            .into_iter()
+            .map(|(index_id, (_, page_num))| (index_id, page_num))
+            .collect();
+        roots.sort();
+        Ok(roots)
+    }
     pub fn index(&self, index_id: u64) -> Option<&Index> {
         self.table_info.indexes.get(&index_id)
     }
     pub fn read_page(&self, page_num: u32) -> io::Result<Bytes> {
-        TableSpace::read_page_(&self.idb_file_path, page_num)
+        Ok(self.recover_page(page_num)?.0)
+    }
+    /// 读取页并应用redo日志，返回前滚后的页和前滚结果
+    pub fn recover_page(&self, page_num: u32) -> io::Result<(Bytes, Option<PageRecovery>)> {
+        let buf = TableSpace::read_logical_page_(&self.idb_file_path, page_num, self.key.as_ref())?;
+        let Some(roll_forward) = &self.roll_forward else {
+            return Ok((buf, None));
+        };
+        let mut buf = buf.to_vec();
+        let recovery = roll_forward.apply(page_num, &mut buf);
+        Ok((Bytes::from(buf), Some(recovery)))
+    }
+    /// 读取页，有密钥时解密，压缩的页解压
+    pub fn read_logical_page_(
+        idb_file_path: &str,
+        page_num: u32,
+        key: Option<&TablespaceKey>,
+    ) -> io::Result<Bytes> {
+        let buf = TableSpace::read_page_(idb_file_path, page_num)?;
+        TableSpace::logical_page(buf, key)
+    }
+    /// 文件中的页先解密再解压，得到 InnoDB 在缓冲池中看到的页
+    pub fn logical_page(buf: Bytes, key: Option<&TablespaceKey>) -> io::Result<Bytes> {
+        let buf = match key {
+            Some(key) => key.decrypt_page(buf)?,
+            None => buf,
+        };
+        compressed_page::decompress(buf)
     }
     pub fn read_page_(idb_file_path: &str, page_num: u32) -> io::Result<Bytes> {
-        let mut file = File::open(idb_file_path)?;
-        let _ = file.seek(SeekFrom::Start(page_num as u64 * 16 * 1024))?;
-        let mut buf = vec![0; 16 * 1024];
-        file.read_exact(&mut buf)?;
-        Ok(Bytes::from(buf))
+        let mut path = PathBuf::from(idb_file_path);
+        let mut page_num = page_num as u64;
+        loop {
+            let mut file = File::open(&path)?;
+            let pages = file.metadata()?.len() / (16 * 1024);
+            // 系统表空间可以由多个数据文件组成，页号在 ibdata1、ibdata2... 之间连续编号
+            if page_num >= pages {
+                if let Some(next) = next_data_file(&path) {
+                    page_num -= pages;
+                    path = next;
+                    continue;
+                }
+            }
+            let _ = file.seek(SeekFrom::Start(page_num * 16 * 1024))?;
+            let mut buf = vec![0; 16 * 1024];
+            file.read_exact(&mut buf)?;
+            return Ok(Bytes::from(buf));
+        }
+    }
+}
+
+/// 系统表空间的下一个数据文件，ibdata1 的下一个文件为同目录下的 ibdata2
+fn next_data_file(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let num: u32 = name.strip_prefix("ibdata")?.parse().ok()?;
+    let next = path.with_file_name(format!("ibdata{}", num + 1));
+    next.exists().then_some(next)
+}
+
+/// undo表空间：MySQL 5.7 的 undo001，MySQL 8.0 的 undo_001 和 .ibu。
+/// undo 之后只能是序号，undo_history.ibd 这样的用户表不是 undo 表空间
+pub fn is_undo_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|x| x.to_str())
+        .is_some_and(|name| {
+            let num = name
+                .strip_prefix("undo")
+                .map(|x| x.strip_prefix('_').unwrap_or(x));
+            num.is_some_and(|x| !x.is_empty() && x.bytes().all(|b| b.is_ascii_digit()))
+                || name.ends_with(".ibu")
+        })
+}
+
+/// 系统表空间的数据文件：ibdata1、ibdata2...
+pub fn is_system_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|x| x.to_str())
+        .is_some_and(|name| name.starts_with("ibdata"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_file_name() {
+        for name in ["undo001", "undo_002", "undo_003.ibu", "my_undo.ibu"] {
+            assert!(is_undo_file(Path::new(name)), "{}", name);
+        }
+        for name in ["undo_history.ibd", "undo.ibd", "undo_", "undo_1a"] {
+            assert!(!is_undo_file(Path::new(name)), "{}", name);
+        }
     }
 }
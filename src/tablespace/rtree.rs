@@ -0,0 +1,98 @@
+use crate::page::PageEnums;
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::geometry::Mbr;
+use crate::tablespace::table::Index;
+use crate::tablespace::TableSpace;
+use std::collections::HashSet;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// 空间索引中节点指针与子页不一致
+#[derive(Debug)]
+pub struct RtreeIssue {
+    pub page_num: u32,
+    pub child_page_num: u32,
+    /// 节点指针中的 MBR
+    pub mbr: Option<Mbr>,
+    /// 子页中所有记录的外包矩形
+    pub child_mbr: Option<Mbr>,
+    pub reason: &'static str,
+}
+
+impl Display for RtreeIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let show = |mbr: Option<Mbr>| mbr.map_or("none".to_string(), |x| x.to_string());
+        write!(
+            f,
+            "page_num:{} -> child:{} {} mbr:{} child_mbr:{}",
+            self.page_num,
+            self.child_page_num,
+            self.reason,
+            show(self.mbr),
+            show(self.child_mbr)
+        )
+    }
+}
+
+impl Index {
+    /// 空间索引的第一个字段为 MBR
+    pub fn is_spatial(&self) -> bool {
+        self.indexes
+            .first()
+            .is_some_and(|column| column.data_type == DataType::Mbr)
+    }
+}
+
+impl TableSpace {
+    /// 从根页开始遍历空间索引，检查每个节点指针的 MBR 是否包含子页中的全部记录，
+    /// 返回遍历的页数和发现的问题
+    pub fn check_rtree(&self, index: &Index) -> io::Result<(usize, Vec<RtreeIssue>)> {
+        let mut issues = Vec::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![index.root_page_num];
+        while let Some(page_num) = pending.pop() {
+            if !visited.insert(page_num) {
+                return Err(io::Error::other(format!("rtree page {} loops", page_num)));
+            }
+            let PageEnums::Rtree(page) = self.page(page_num)? else {
+                return Err(io::Error::other(format!(
+                    "page {} is not an rtree page",
+                    page_num
+                )));
+            };
+            let level = page.index_header.level();
+            if level == 0 {
+                continue;
+            }
+            for (row, mbr) in page.user_records.iter().zip(page.mbrs()) {
+                let Some(child_page_num) = page.child_page_num(row) else {
+                    continue;
+                };
+                let issue = |child_mbr, reason| RtreeIssue {
+                    page_num,
+                    child_page_num,
+                    mbr,
+                    child_mbr,
+                    reason,
+                };
+                let PageEnums::Rtree(child) = self.page(child_page_num)? else {
+                    issues.push(issue(None, "child is not an rtree page"));
+                    continue;
+                };
+                let child_mbr = child.mbr();
+                if child.index_header.level() + 1 != level {
+                    issues.push(issue(child_mbr, "level mismatch"));
+                } else if child.index_header.index_id() != index.index_id {
+                    issues.push(issue(child_mbr, "index_id mismatch"));
+                } else if let (Some(mbr), Some(child_mbr)) = (mbr, child_mbr) {
+                    if !mbr.contains(&child_mbr) {
+                        issues.push(issue(Some(child_mbr), "mbr not enclosing child"));
+                    }
+                }
+                pending.push(child_page_num);
+            }
+        }
+        Ok((visited.len(), issues))
+    }
+}
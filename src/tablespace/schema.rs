@@ -0,0 +1,658 @@
+use crate::tablespace::charset::{Charset, Collation};
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::table::{Column, KeyDef, KeyPart, Order};
+use std::io;
+
+/// 从建表语句解析出的表结构
+pub struct Schema {
+    pub name: String,
+    pub columns: Vec<Column>,
+    pub keys: Vec<KeyDef>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// 标识符或关键字，反引号括起的标识符不区分关键字
+    Ident(String, bool),
+    Str(String),
+    Number(String),
+    Punct(char),
+}
+
+impl Token {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Ident(s, false) if s.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(sql: &str) -> io::Result<Vec<Token>> {
+    let chars: Vec<char> = sql.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' || (c == '-' && chars.get(i + 1) == Some(&'-')) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            // 可执行注释 /*!50100 ... */ 的内容按普通语句处理
+            if chars.get(i + 2) == Some(&'!') {
+                i += 3;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                continue;
+            }
+            i += 2;
+            while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '*' && chars.get(i + 1) == Some(&'/') {
+            i += 2;
+        } else if c == '`' || c == '\'' || c == '"' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                let Some(&x) = chars.get(i) else {
+                    return Err(io::Error::other("unterminated quoted string in schema"));
+                };
+                i += 1;
+                if x == c {
+                    if chars.get(i) == Some(&c) {
+                        value.push(c);
+                        i += 1;
+                        continue;
+                    }
+                    break;
+                }
+                if x == '\\' && c != '`' {
+                    if let Some(&next) = chars.get(i) {
+                        value.push(match next {
+                            'n' => '\n',
+                            't' => '\t',
+                            '0' => '\0',
+                            other => other,
+                        });
+                        i += 1;
+                        continue;
+                    }
+                }
+                value.push(x);
+            }
+            tokens.push(if c == '`' {
+                Token::Ident(value, true)
+            } else {
+                Token::Str(value)
+            });
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            tokens.push(Token::Number(chars[start..i].iter().collect()));
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect(), false));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.peek().is_some_and(|t| t.is_keyword(keyword))
+    }
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+    fn expect_punct(&mut self, c: char) -> io::Result<()> {
+        if self.eat_punct(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+    fn expect_keyword(&mut self, keyword: &str) -> io::Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected {}", keyword)))
+        }
+    }
+    fn ident(&mut self) -> io::Result<String> {
+        match self.next() {
+            Some(Token::Ident(s, _)) | Some(Token::Str(s)) => Ok(s),
+            _ => {
+                self.pos -= 1;
+                Err(self.error("expected identifier"))
+            }
+        }
+    }
+    fn error(&self, message: &str) -> io::Error {
+        io::Error::other(format!(
+            "schema parse error at token {} ({:?}): {}",
+            self.pos,
+            self.peek(),
+            message
+        ))
+    }
+    /// 跳过一对括号及其中的内容，当前位置必须是左括号
+    fn skip_parens(&mut self) -> io::Result<()> {
+        self.expect_punct('(')?;
+        let mut depth = 1;
+        while depth > 0 {
+            match self.next() {
+                Some(Token::Punct('(')) => depth += 1,
+                Some(Token::Punct(')')) => depth -= 1,
+                Some(_) => {}
+                None => return Err(self.error("unbalanced parentheses")),
+            }
+        }
+        Ok(())
+    }
+    /// 跳到当前定义结束处，即顶层的逗号或右括号
+    fn skip_definition(&mut self) -> io::Result<()> {
+        loop {
+            match self.peek() {
+                Some(Token::Punct(',')) | Some(Token::Punct(')')) | None => return Ok(()),
+                Some(Token::Punct('(')) => self.skip_parens()?,
+                _ => self.pos += 1,
+            }
+        }
+    }
+    /// 括号中的内容还原为文本，当前位置必须是左括号
+    fn parens_text(&mut self) -> io::Result<String> {
+        let start = self.pos;
+        self.skip_parens()?;
+        Ok(tokens_text(&self.tokens[start + 1..self.pos - 1]))
+    }
+    /// 带括号的参数列表，如 (10,2)、('a','b')
+    fn args(&mut self) -> io::Result<Vec<Token>> {
+        let mut args = Vec::new();
+        if !self.eat_punct('(') {
+            return Ok(args);
+        }
+        loop {
+            match self.next() {
+                Some(Token::Punct(')')) => return Ok(args),
+                Some(Token::Punct(',')) => {}
+                Some(token) => args.push(token),
+                None => return Err(self.error("unbalanced parentheses")),
+            }
+        }
+    }
+}
+
+/// 由记号还原的文本，只在相邻的单词之间加空格
+fn tokens_text(tokens: &[Token]) -> String {
+    let mut text = String::new();
+    for token in tokens {
+        let word = match token {
+            Token::Ident(s, true) => format!("`{}`", s),
+            Token::Ident(s, false) | Token::Number(s) => s.clone(),
+            Token::Str(s) => format!("'{}'", s.replace('\'', "''")),
+            Token::Punct(c) => c.to_string(),
+        };
+        if !matches!(token, Token::Punct(_))
+            && text.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '`' || c == '\'')
+        {
+            text.push(' ');
+        }
+        text.push_str(&word);
+    }
+    text
+}
+
+struct ColumnDef {
+    name: String,
+    type_name: String,
+    args: Vec<Token>,
+    charset: Option<String>,
+    collation: Option<String>,
+    is_nullable: bool,
+    /// 生成列的表达式
+    generation_expression: Option<String>,
+    is_virtual: bool,
+}
+
+/// 建表语句中的索引：(索引名, 是否主键, 是否唯一, 是否空间索引, 字段名列表)
+/// 索引字段：(字段名,前缀字符数,排序方向)
+type KeyColumn = (String, Option<usize>, Order);
+type ParsedKey = (Option<String>, bool, bool, bool, Vec<KeyColumn>);
+
+/// 解析 CREATE TABLE 语句
+pub fn parse_create_table(sql: &str) -> io::Result<Schema> {
+    let mut parser = Parser {
+        tokens: tokenize(sql)?,
+        pos: 0,
+    };
+    parser.expect_keyword("CREATE")?;
+    parser.eat_keyword("TEMPORARY");
+    parser.expect_keyword("TABLE")?;
+    if parser.eat_keyword("IF") {
+        parser.expect_keyword("NOT")?;
+        parser.expect_keyword("EXISTS")?;
+    }
+    let mut name = parser.ident()?;
+    if parser.eat_punct('.') {
+        name = parser.ident()?;
+    }
+    parser.expect_punct('(')?;
+
+    let mut defs: Vec<ColumnDef> = Vec::new();
+    let mut keys: Vec<ParsedKey> = Vec::new();
+    loop {
+        if parser.eat_keyword("CONSTRAINT")
+            && !parser.peek_keyword("PRIMARY")
+            && !parser.peek_keyword("UNIQUE")
+            && !parser.peek_keyword("FOREIGN")
+            && !parser.peek_keyword("CHECK")
+        {
+            parser.ident()?;
+        }
+        if parser.eat_keyword("PRIMARY") {
+            parser.expect_keyword("KEY")?;
+            skip_index_name(&mut parser)?;
+            keys.push((None, true, true, false, key_columns(&mut parser)?));
+            parser.skip_definition()?;
+        } else if parser.eat_keyword("UNIQUE") {
+            let _ = parser.eat_keyword("KEY") || parser.eat_keyword("INDEX");
+            let key_name = skip_index_name(&mut parser)?;
+            keys.push((key_name, false, true, false, key_columns(&mut parser)?));
+            parser.skip_definition()?;
+        } else if parser.eat_keyword("KEY") || parser.eat_keyword("INDEX") {
+            let key_name = skip_index_name(&mut parser)?;
+            keys.push((key_name, false, false, false, key_columns(&mut parser)?));
+            parser.skip_definition()?;
+        } else if parser.peek_keyword("FULLTEXT")
+            || parser.peek_keyword("FOREIGN")
+            || parser.peek_keyword("CHECK")
+        {
+            // 全文索引没有B+树，外键和检查约束不影响存储
+            parser.skip_definition()?;
+        } else if parser.eat_keyword("SPATIAL") {
+            let _ = parser.eat_keyword("KEY") || parser.eat_keyword("INDEX");
+            let key_name = skip_index_name(&mut parser)?;
+            keys.push((key_name, false, false, true, key_columns(&mut parser)?));
+            parser.skip_definition()?;
+        } else {
+            let def = column_definition(&mut parser, &mut keys)?;
+            defs.push(def);
+        }
+        if parser.eat_punct(')') {
+            break;
+        }
+        parser.expect_punct(',')?;
+    }
+
+    // 表选项中的默认字符集和排序规则
+    let mut table_charset = None;
+    let mut table_collation = None;
+    while let Some(token) = parser.next() {
+        if token.is_keyword("CHARSET") || token.is_keyword("CHARACTER") {
+            parser.eat_keyword("SET");
+            parser.eat_punct('=');
+            table_charset = Some(parser.ident()?);
+        } else if token.is_keyword("COLLATE") {
+            parser.eat_punct('=');
+            let collation = parser.ident()?;
+            if table_charset.is_none() {
+                table_charset = collation.split('_').next().map(|x| x.to_string());
+            }
+            table_collation = Some(collation);
+        }
+    }
+
+    let mut columns = Vec::with_capacity(defs.len());
+    for (i, def) in defs.iter().enumerate() {
+        let charset = def
+            .charset
+            .as_deref()
+            .or(table_charset.as_deref())
+            .unwrap_or("utf8mb4");
+        let data_type = data_type(def, charset)?;
+        let mut column = Column::new(
+            def.name.clone(),
+            data_type,
+            def.is_nullable,
+            false,
+            i as u16 + 1,
+        );
+        column.is_virtual = def.is_virtual;
+        if matches!(data_type, DataType::Enum(_) | DataType::Set(_)) {
+            column.elements = def
+                .args
+                .iter()
+                .map(|x| match x {
+                    Token::Str(s) => s.clone(),
+                    other => tokens_text(std::slice::from_ref(other)),
+                })
+                .collect();
+        }
+        column.generation_expression = def.generation_expression.clone();
+        column.charset = match def.type_name.as_str() {
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => {
+                Some(Charset::Binary)
+            }
+            "nchar" | "nvarchar" => Some(Charset::Utf8mb3),
+            _ => Charset::from_name(charset),
+        };
+        // 列上指定了字符集而没有排序规则时使用字符集的默认排序规则
+        let collation = match (&def.collation, &def.charset) {
+            (Some(collation), _) => Some(collation),
+            (None, None) => table_collation.as_ref(),
+            (None, Some(_)) => None,
+        };
+        column.collation = match column.charset {
+            Some(Charset::Binary) => Some(Collation::from_id(63)),
+            charset => collation
+                .map(|x| Collation::from_name(x))
+                .or_else(|| charset.map(|x| Collation::from_id(x.default_collation_id()))),
+        };
+        column.column_type = Some(if def.args.is_empty() {
+            def.type_name.clone()
+        } else {
+            let args: Vec<String> = def
+                .args
+                .iter()
+                .map(|x| tokens_text(std::slice::from_ref(x)))
+                .collect();
+            format!("{}({})", def.type_name, args.join(","))
+        });
+        columns.push(column);
+    }
+    let find_column = |name: &str| -> io::Result<usize> {
+        defs.iter()
+            .position(|def| def.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| io::Error::other(format!("key column {} not found", name)))
+    };
+    let mut key_defs: Vec<KeyDef> = Vec::with_capacity(keys.len());
+    for (key_name, is_primary, is_unique, is_spatial, key_columns) in keys {
+        let mut parts = Vec::with_capacity(key_columns.len());
+        for (column, prefix_len, order) in &key_columns {
+            let index = find_column(column)?;
+            // 主键字段隐式为 NOT NULL
+            if is_primary {
+                columns[index].is_nullable = false;
+            }
+            // 前缀长度为字符数
+            let mbmaxlen = columns[index].charset.map_or(1, |x| x.mbmaxlen());
+            parts.push(KeyPart {
+                column: index,
+                len: prefix_len.map(|len| len * mbmaxlen),
+                order: *order,
+            });
+        }
+        let name = if is_primary {
+            String::from("PRIMARY")
+        } else {
+            // 未命名的索引以第一个字段命名，重名时追加 _2、_3
+            key_name.unwrap_or_else(|| {
+                let base = key_columns[0].0.clone();
+                let mut name = base.clone();
+                let mut n = 2;
+                while key_defs.iter().any(|k| k.name.eq_ignore_ascii_case(&name)) {
+                    name = format!("{}_{}", base, n);
+                    n += 1;
+                }
+                name
+            })
+        };
+        key_defs.push(KeyDef {
+            name,
+            is_primary,
+            is_unique,
+            is_spatial,
+            parts,
+            comment: String::new(),
+        });
+    }
+    // 与 MySQL 的 sort_keys 相同：唯一索引在前，其中字段都不为空的、主键、不含前缀字段的依次靠前；
+    // 之后为普通索引，其余按定义顺序排列。全文索引排在最后，但它没有B+树，解析时已经跳过
+    key_defs.sort_by_key(|k| {
+        let nullable = k.parts.iter().any(|p| columns[p.column].is_nullable);
+        let prefix = k.parts.iter().any(|p| p.len.is_some());
+        (
+            !k.is_unique,
+            k.is_unique && nullable,
+            !k.is_primary,
+            k.is_unique && prefix,
+        )
+    });
+    Ok(Schema {
+        name,
+        columns,
+        keys: key_defs,
+    })
+}
+
+/// 跳过可选的索引名和 USING BTREE，返回索引名
+fn skip_index_name(parser: &mut Parser) -> io::Result<Option<String>> {
+    let mut name = None;
+    if !matches!(parser.peek(), Some(Token::Punct('('))) && !parser.peek_keyword("USING") {
+        name = Some(parser.ident()?);
+    }
+    if parser.eat_keyword("USING") {
+        parser.ident()?;
+    }
+    Ok(name)
+}
+
+/// 索引字段列表：字段名、前缀长度(字符数)和排序方向
+fn key_columns(parser: &mut Parser) -> io::Result<Vec<KeyColumn>> {
+    parser.expect_punct('(')?;
+    let mut columns = Vec::new();
+    loop {
+        if matches!(parser.peek(), Some(Token::Punct('('))) {
+            return Err(parser.error("functional key parts are not supported"));
+        }
+        let name = parser.ident()?;
+        let prefix_len = match parser.args()?.first() {
+            Some(Token::Number(n)) => n.parse().ok(),
+            _ => None,
+        };
+        let order = if parser.eat_keyword("DESC") {
+            Order::Desc
+        } else {
+            parser.eat_keyword("ASC");
+            Order::Asc
+        };
+        columns.push((name, prefix_len, order));
+        if parser.eat_punct(')') {
+            return Ok(columns);
+        }
+        parser.expect_punct(',')?;
+    }
+}
+
+fn column_definition(parser: &mut Parser, keys: &mut Vec<ParsedKey>) -> io::Result<ColumnDef> {
+    let name = parser.ident()?;
+    let type_name = parser.ident()?.to_ascii_lowercase();
+    let type_name = match type_name.as_str() {
+        // DOUBLE PRECISION、CHAR VARYING、NATIONAL CHAR 等多个单词的类型名
+        "double" => {
+            parser.eat_keyword("PRECISION");
+            type_name
+        }
+        "national" => parser.ident()?.to_ascii_lowercase(),
+        "char" | "character" if parser.eat_keyword("VARYING") => String::from("varchar"),
+        "long" if parser.eat_keyword("VARCHAR") => String::from("mediumtext"),
+        "long" if parser.eat_keyword("VARBINARY") => String::from("mediumblob"),
+        "long" => String::from("mediumtext"),
+        _ => type_name,
+    };
+    let args = parser.args()?;
+    let mut def = ColumnDef {
+        name,
+        type_name,
+        args,
+        charset: None,
+        collation: None,
+        is_nullable: true,
+        generation_expression: None,
+        is_virtual: false,
+    };
+    loop {
+        match parser.peek() {
+            Some(Token::Punct(',')) | Some(Token::Punct(')')) | None => break,
+            _ => {}
+        }
+        if parser.eat_keyword("NOT") {
+            parser.expect_keyword("NULL")?;
+            def.is_nullable = false;
+        } else if parser.eat_keyword("NULL") {
+            def.is_nullable = true;
+        } else if parser.eat_keyword("CHARACTER") {
+            parser.expect_keyword("SET")?;
+            def.charset = Some(parser.ident()?);
+        } else if parser.eat_keyword("CHARSET") {
+            def.charset = Some(parser.ident()?);
+        } else if parser.eat_keyword("COLLATE") {
+            let collation = parser.ident()?;
+            if def.charset.is_none() {
+                def.charset = collation.split('_').next().map(|x| x.to_string());
+            }
+            def.collation = Some(collation);
+        } else if parser.eat_keyword("GENERATED") || parser.eat_keyword("AS") {
+            // [GENERATED ALWAYS] AS (expr) [VIRTUAL | STORED]，默认为虚拟列
+            if parser.eat_keyword("ALWAYS") {
+                parser.expect_keyword("AS")?;
+            }
+            def.generation_expression = Some(parser.parens_text()?);
+            def.is_virtual = true;
+        } else if parser.eat_keyword("VIRTUAL") {
+            def.is_virtual = true;
+        } else if parser.eat_keyword("STORED") {
+            def.is_virtual = false;
+        } else if parser.eat_keyword("PRIMARY") {
+            parser.expect_keyword("KEY")?;
+            keys.push((
+                None,
+                true,
+                true,
+                false,
+                vec![(def.name.clone(), None, Order::Asc)],
+            ));
+        } else if parser.eat_keyword("UNIQUE") {
+            parser.eat_keyword("KEY");
+            keys.push((
+                None,
+                false,
+                true,
+                false,
+                vec![(def.name.clone(), None, Order::Asc)],
+            ));
+        } else if parser.eat_keyword("KEY") {
+            // 列定义中的 KEY 即 PRIMARY KEY
+            keys.push((
+                None,
+                true,
+                true,
+                false,
+                vec![(def.name.clone(), None, Order::Asc)],
+            ));
+        } else if matches!(parser.peek(), Some(Token::Punct('('))) {
+            parser.skip_parens()?;
+        } else {
+            parser.pos += 1;
+        }
+    }
+    Ok(def)
+}
+
+fn data_type(def: &ColumnDef, charset: &str) -> io::Result<DataType> {
+    let arg = |index: usize| -> Option<usize> {
+        match def.args.get(index) {
+            Some(Token::Number(n)) => n.parse().ok(),
+            _ => None,
+        }
+    };
+    // 字符集单个字符的最大字节数，未知的字符集按单字节处理
+    let mbmaxlen = Charset::from_name(charset).map_or(1, |x| x.mbmaxlen());
+    let data_type = match def.type_name.as_str() {
+        "tinyint" | "bool" | "boolean" => DataType::Tinyint,
+        "smallint" => DataType::Smallint,
+        "mediumint" => DataType::Mediumint,
+        "int" | "integer" => DataType::Int,
+        "bigint" | "serial" => DataType::Bigint,
+        "float" => DataType::Float,
+        "double" | "real" => DataType::Double,
+        "decimal" | "numeric" | "dec" | "fixed" => {
+            let precision = arg(0).unwrap_or(10);
+            let scale = arg(1).unwrap_or(0);
+            DataType::Decimal(precision, scale)
+        }
+        "date" => DataType::Date,
+        "time" => DataType::Time(arg(0).unwrap_or(0)),
+        "datetime" => DataType::Datetime(arg(0).unwrap_or(0)),
+        "timestamp" => DataType::Timestamp(arg(0).unwrap_or(0)),
+        "year" => DataType::Year,
+        "char" | "character" | "nchar" => {
+            let mbmaxlen = if def.type_name == "nchar" {
+                3
+            } else {
+                mbmaxlen
+            };
+            DataType::Char(arg(0).unwrap_or(1) * mbmaxlen)
+        }
+        "binary" => DataType::Char(arg(0).unwrap_or(1)),
+        "varchar" => DataType::Varchar(arg(0).unwrap_or(0) * mbmaxlen),
+        "nvarchar" => DataType::Varchar(arg(0).unwrap_or(0) * 3),
+        "varbinary" => DataType::Varchar(arg(0).unwrap_or(0)),
+        "tinytext" | "tinyblob" => DataType::Tinytext,
+        "text" | "blob" => DataType::Text,
+        "mediumtext" | "mediumblob" => DataType::Mediumtext,
+        "longtext" | "longblob" => DataType::Longtext,
+        "bit" => DataType::Bit(arg(0).unwrap_or(1)),
+        "enum" => DataType::Enum(def.args.len()),
+        "set" => DataType::Set(def.args.len()),
+        "json" => DataType::new(31, 0),
+        "geometry" | "point" | "linestring" | "polygon" | "multipoint" | "multilinestring"
+        | "multipolygon" | "geometrycollection" | "geomcollection" => DataType::new(30, 0),
+        other => {
+            return Err(io::Error::other(format!(
+                "unsupported column type {} of {}",
+                other, def.name
+            )))
+        }
+    };
+    Ok(data_type)
+}
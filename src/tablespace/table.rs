@@ -1,15 +1,39 @@
+use crate::frm::table::Table as FrmTable;
 use crate::page::base_page::BasePage;
-use crate::page::index_page::page::IndexPage;
-use crate::page::index_page::records::Row;
 use crate::page::sdi_blob_page::SdiBlobPage;
 use crate::page::sdi_page::SdiPage;
+use crate::tablespace::charset::{Charset, Collation};
 use crate::tablespace::data_type::DataType;
+use crate::tablespace::encryption::TablespaceKey;
+use crate::tablespace::schema::parse_create_table;
 use crate::tablespace::TableSpace;
-use bytes::Bytes;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use serde_json::Value;
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
 use std::io;
 use std::io::Read;
+use std::path::Path;
+use std::rc::Rc;
+
+/// ALGORITHM=INSTANT 添加的列的默认值，加列之前插入的记录中没有该列，读取时使用默认值
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstantDefault {
+    Null,
+    /// InnoDB 存储格式的值
+    Value(Vec<u8>),
+}
+
+/// 索引字段的排序方向，8.0 起支持降序索引
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    Asc,
+    Desc,
+}
 
 #[derive(Debug, Clone)]
 pub struct Column {
@@ -18,6 +42,35 @@ pub struct Column {
     pub is_nullable: bool,
     pub is_hidden: bool,
     pub ordinal_position: u16,
+    /// 添加该列时的行版本，0表示建表时就有
+    pub version_added: u8,
+    /// 删除该列时的行版本，0表示没有被删除
+    pub version_dropped: u8,
+    /// 在聚簇索引记录中的位置，有行版本的表才有
+    pub physical_pos: Option<u16>,
+    pub instant_default: Option<InstantDefault>,
+    /// 虚拟生成列，不存储在聚簇索引记录中，只在二级索引中物化
+    pub is_virtual: bool,
+    /// 生成列(虚拟或存储)的表达式
+    pub generation_expression: Option<String>,
+    /// 建表语句中的类型，如 varchar(20)，未知时由 data_type 推断
+    pub column_type: Option<String>,
+    /// 字符串列的字符集
+    pub charset: Option<Charset>,
+    /// 字符串列的排序规则，比较索引键时使用
+    pub collation: Option<Collation>,
+    /// TIMESTAMP 列显示时使用的时区，相对 UTC 的秒数
+    pub time_zone: Option<i32>,
+    /// ENUM、SET 的成员，按定义顺序
+    pub elements: Rc<[String]>,
+    /// 索引中的列才有，前缀索引的前缀字节数
+    pub prefix_len: Option<usize>,
+    /// 索引中的列才有，排序方向
+    pub order: Order,
+    /// 建表语句中的默认值，字符串已加引号，如 NULL、CURRENT_TIMESTAMP、'abc'
+    pub default: Option<String>,
+    /// 列注释，没有时为空
+    pub comment: String,
 }
 
 impl Column {
@@ -34,8 +87,130 @@ impl Column {
             is_nullable,
             is_hidden,
             ordinal_position,
+            version_added: 0,
+            version_dropped: 0,
+            physical_pos: None,
+            instant_default: None,
+            is_virtual: false,
+            generation_expression: None,
+            column_type: None,
+            charset: None,
+            collation: None,
+            time_zone: None,
+            elements: Rc::from([]),
+            prefix_len: None,
+            order: Order::Asc,
+            default: None,
+            comment: String::new(),
+        }
+    }
+    /// 作为索引字段的列，字节数小于列的最大字节数时为前缀索引
+    pub fn key_part(&self, len: usize, order: Order) -> Column {
+        Column {
+            prefix_len: Some(len).filter(|&len| len < self.data_type.max_len()),
+            order,
+            ..self.clone()
+        }
+    }
+    /// 定长列在记录中的字节数，前缀索引中只存储前缀
+    pub fn fixed_len(&self) -> usize {
+        let len = self.data_type.len();
+        self.prefix_len
+            .map_or(len, |prefix_len| len.min(prefix_len))
+    }
+    /// 行版本为 version 的记录中是否存有该列
+    pub fn in_version(&self, version: u8) -> bool {
+        self.version_added <= version
+            && (self.version_dropped == 0 || self.version_dropped > version)
+    }
+    /// COMPACT 及之后的行格式中长度是否存放在变长字段长度列表中。
+    /// 变长字符集(如 utf8mb4)的 CHAR(n) 按实际字节数存储，至少 n 字节，不足时以空格填充
+    pub fn is_var(&self) -> bool {
+        match self.data_type {
+            DataType::Char(_) => self.charset.is_some_and(|x| x.mbminlen() != x.mbmaxlen()),
+            data_type => data_type.is_var(),
         }
     }
+    /// 没有标记位的记录中是否存有该列，INSTANT 添加的列都不在其中
+    pub fn in_core(&self) -> bool {
+        self.in_version(0) && self.instant_default.is_none()
+    }
+    /// 解析 SDI 中列的 se_private_data，如 default=3132;physical_pos=3;version_added=1;
+    fn parse_se_private_data(&mut self, se_private_data: &str) {
+        for item in se_private_data.split(';') {
+            let Some((n, v)) = item.split_once('=') else {
+                continue;
+            };
+            match n {
+                "version_added" => self.version_added = v.parse().unwrap_or(0),
+                "version_dropped" => self.version_dropped = v.parse().unwrap_or(0),
+                "physical_pos" => self.physical_pos = v.parse().ok(),
+                "default_null" => self.instant_default = Some(InstantDefault::Null),
+                "default" => {
+                    self.instant_default = hex::decode(v).ok().map(InstantDefault::Value);
+                }
+                _ => {}
+            }
+        }
+    }
+    /// 二级索引的记录没有行版本，去掉 INSTANT 相关信息
+    fn without_instant(&self) -> Column {
+        Column {
+            version_added: 0,
+            version_dropped: 0,
+            physical_pos: None,
+            instant_default: None,
+            ..self.clone()
+        }
+    }
+    /// 建表语句中的类型，未知时由 data_type 推断
+    fn type_name(&self) -> String {
+        if let Some(column_type) = &self.column_type {
+            return column_type.clone();
+        }
+        let elements = || {
+            self.elements
+                .iter()
+                .map(|x| format!("'{}'", x.replace('\'', "''")))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match self.data_type {
+            DataType::Enum(_) if !self.elements.is_empty() => format!("enum({})", elements()),
+            DataType::Set(_) if !self.elements.is_empty() => format!("set({})", elements()),
+            data_type => format!("{:?}", data_type).to_lowercase(),
+        }
+    }
+    /// 建表语句中的索引字段，前缀长度为字符数
+    pub fn key_part_definition(&self) -> String {
+        let mut definition = format!("`{}`", self.name);
+        if let Some(prefix_len) = self.prefix_len {
+            let mbmaxlen = self.charset.map_or(1, |x| x.mbmaxlen());
+            definition.push_str(&format!("({})", prefix_len / mbmaxlen));
+        }
+        if self.order == Order::Desc {
+            definition.push_str(" DESC");
+        }
+        definition
+    }
+    /// 建表语句中的列定义
+    pub fn definition(&self) -> String {
+        let mut definition = format!("`{}` {}", self.name, self.type_name());
+        if !self.is_nullable {
+            definition.push_str(" NOT NULL");
+        }
+        if let Some(expression) = &self.generation_expression {
+            let kind = if self.is_virtual { "VIRTUAL" } else { "STORED" };
+            definition.push_str(&format!(" GENERATED ALWAYS AS ({}) {}", expression, kind));
+        }
+        if let Some(default) = &self.default {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if !self.comment.is_empty() {
+            definition.push_str(&format!(" COMMENT {}", quote(&self.comment)));
+        }
+        definition
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +223,13 @@ pub struct Index {
     pub indexes: Vec<Column>,
     // 叶子页值，顺序为物理存储顺序
     pub elements: Vec<Column>,
+    /// 索引定义中的字段数，非叶子节点记录中之后为追加的主键字段和子页号
+    pub n_key_parts: usize,
+    /// 索引定义中第 i 个字段在 elements 中的下标。undo 记录中的字段号按索引定义的顺序编号，
+    /// 有行版本的表 elements 按 physical_pos 排列，两者不同
+    pub field_order: Vec<usize>,
+    /// 索引注释，没有时为空
+    pub comment: String,
 }
 
 impl Index {
@@ -59,7 +241,8 @@ impl Index {
         indexes: Vec<Column>,
         elements: Vec<Column>,
     ) -> Self {
-        // let index_
+        let n_key_parts = indexes.len().saturating_sub(1);
+        let field_order = (0..elements.len()).collect();
         Self {
             index_id,
             root_page_num,
@@ -67,19 +250,145 @@ impl Index {
             is_primary,
             indexes,
             elements,
+            n_key_parts,
+            field_order,
+            comment: String::new(),
         }
     }
-    pub fn elements_size(&self) -> usize {
-        self.elements.len()
-    }
-    pub fn index_size(&self) -> usize {
-        self.indexes.len()
+    /// 表结构中不存在的索引
+    pub fn unknown(index_id: u64) -> Self {
+        Self::new(
+            index_id,
+            u32::MAX,
+            format!("unknown_{}", index_id),
+            false,
+            Vec::new(),
+            Vec::new(),
+        )
     }
 }
 
 pub struct TableInfo {
     pub name: String,
+    /// 表中的全部列，包括虚拟列和隐藏列
+    pub columns: Vec<Column>,
     pub indexes: HashMap<u64, Index>,
+    /// 表注释，没有时为空
+    pub comment: String,
+}
+
+impl TableInfo {
+    /// 设置 TIMESTAMP 列显示时使用的时区
+    pub fn set_time_zone(&mut self, time_zone: i32) {
+        let columns = self
+            .indexes
+            .values_mut()
+            .flat_map(|index| index.indexes.iter_mut().chain(index.elements.iter_mut()));
+        for column in self.columns.iter_mut().chain(columns) {
+            column.time_zone = Some(time_zone);
+        }
+    }
+}
+
+/// 由表结构还原的建表语句，索引只区分主键、空间索引和普通索引
+impl Display for TableInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "CREATE TABLE `{}` (", self.name)?;
+        let mut lines: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|x| !x.is_hidden)
+            .map(|x| x.definition())
+            .collect();
+        let mut indexes: Vec<&Index> = self.indexes.values().collect();
+        indexes.sort_by_key(|x| x.index_id);
+        for index in indexes {
+            // 非叶子节点记录的前 n_key_parts 列为索引定义中的字段
+            let columns: Vec<String> = index.indexes[..index.n_key_parts]
+                .iter()
+                .filter(|x| !x.is_hidden)
+                .map(|x| x.key_part_definition())
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+            let columns = columns.join(",");
+            let mut line = if index.is_primary {
+                format!("PRIMARY KEY ({})", columns)
+            } else if index.is_spatial() {
+                format!("SPATIAL KEY `{}` ({})", index.name, columns)
+            } else {
+                format!("KEY `{}` ({})", index.name, columns)
+            };
+            if !index.comment.is_empty() {
+                line.push_str(&format!(" COMMENT {}", quote(&index.comment)));
+            }
+            lines.push(line);
+        }
+        for (i, line) in lines.iter().enumerate() {
+            let sep = if i + 1 < lines.len() { "," } else { "" };
+            writeln!(f, "  {}{}", line, sep)?;
+        }
+        if self.comment.is_empty() {
+            writeln!(f, ")")
+        } else {
+            writeln!(f, ") COMMENT={}", quote(&self.comment))
+        }
+    }
+}
+
+/// 建表语句中的字符串字面量，单引号转义为两个单引号
+pub fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// 按索引字段比较两个键，NULL 最小。字符串按列的排序规则比较(见 [`Collation::sort_key`])，
+/// 按排序规则相等时再按字节比较，使顺序确定；其它值按存储的字节比较(整数、日期等的存储格式按字节有序)。
+/// 降序字段的比较结果取反
+pub fn cmp_key(columns: &[Column], a: &[Option<Vec<u8>>], b: &[Option<Vec<u8>>]) -> Ordering {
+    for ((column, a), b) in columns.iter().zip(a).zip(b) {
+        let ordering = cmp_value(column, a, b).then_with(|| a.cmp(b));
+        let ordering = match column.order {
+            Order::Asc => ordering,
+            Order::Desc => ordering.reverse(),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// 按排序规则比较字符串列的值，无法解码时按字节比较
+fn cmp_value(column: &Column, a: &Option<Vec<u8>>, b: &Option<Vec<u8>>) -> Ordering {
+    let charset = column
+        .charset
+        .filter(|x| *x != Charset::Binary && column.data_type.is_str());
+    if let (Some(a), Some(b), Some(charset), Some(collation)) = (a, b, charset, column.collation) {
+        if let (Ok(a), Ok(b)) = (charset.decode(a), charset.decode(b)) {
+            return collation.sort_key(&a).cmp(&collation.sort_key(&b));
+        }
+    }
+    a.cmp(b)
+}
+
+/// SDI 中索引字段的 order，1为未指定，2为升序，3为降序
+fn sdi_order(val: &Value) -> Order {
+    match val.get("order").and_then(|x| x.as_u64()) {
+        Some(3) => Order::Desc,
+        _ => Order::Asc,
+    }
+}
+
+/// 非叶子节点记录末尾4字节的子页号
+pub fn child_page_num_column(ordinal_position: u16) -> Column {
+    Column::new(
+        String::from("child_page_num"),
+        DataType::Int,
+        false,
+        false,
+        ordinal_position,
+    )
 }
 
 pub fn sdi_index(root_page_num: u32) -> Index {
@@ -107,21 +416,15 @@ pub fn sdi_index(root_page_num: u32) -> Index {
         5,
     );
     let c6 = Column::new(String::from("sdi_comp_len"), DataType::Int, false, false, 6);
+    // sdi_value 为 BLOB
     let c7 = Column::new(
         String::from("sdi_value"),
-        DataType::Varchar,
+        DataType::Longtext,
         false,
         false,
         7,
     );
-    let c_page_num = Column::new(
-        String::from("child_page_num"),
-        DataType::Int,
-        false,
-        false,
-        8,
-    );
-    let indexes = vec![c1.clone(), c2.clone(), c_page_num];
+    let indexes = vec![c1.clone(), c2.clone(), child_page_num_column(8)];
     let sdi_elements = vec![c1, c2, c3, c4, c5, c6, c7];
     Index::new(
         18446744073709551615,
@@ -133,167 +436,518 @@ pub fn sdi_index(root_page_num: u32) -> Index {
     )
 }
 
-pub fn read_table_info(idb_file_path: &str, page_num: u32) -> io::Result<TableInfo> {
+/// dd::Index::IT_SPATIAL
+const DD_INDEX_SPATIAL: u64 = 5;
+
+pub fn read_table_info(
+    idb_file_path: &str,
+    page_num: u32,
+    key: Option<&TablespaceKey>,
+) -> io::Result<TableInfo> {
     // 解析sdi数据
     let mut child_page_num = page_num;
     let mut index_page = 'a: loop {
-        let buf = TableSpace::read_page_(idb_file_path, child_page_num)?;
+        let buf = TableSpace::read_logical_page_(idb_file_path, child_page_num, key)?;
         let index_page = BasePage::<SdiPage>::new(buf);
         //找到最左边的叶子
         while index_page.index_header.level() != 0 {
             for row in &index_page.user_records {
-                match row {
-                    Row::New(row) => {
-                        if row.header().delete_mask() == 1 {
-                            continue;
-                        }
-                        let (data, _) = row.col(2);
-                        child_page_num = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
-
-                        continue 'a;
-                    }
+                if row.delete_mask() == 1 {
+                    continue;
                 }
+                let (data, _) = row.col(2);
+                child_page_num = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+
+                continue 'a;
             }
         }
         break index_page;
     };
     loop {
         for row in &index_page.user_records {
-            match row {
-                Row::New(row) => {
-                    if row.header().delete_mask() == 1 {
-                        continue;
+            if row.delete_mask() == 1 {
+                continue;
+            }
+            let (data, _) = row.col(0);
+            let tmp: [u8; 4] = data.try_into().unwrap();
+            let sdi_type = u32::from_be_bytes(tmp);
+            if sdi_type == 1 {
+                let (str_bytes, overflow_ptr) = row.col(6);
+                let value_bytes = if let Some(ptr) = overflow_ptr {
+                    // 读取sdi溢出页
+                    let mut bytes = Vec::with_capacity(ptr.len() as usize);
+                    bytes.extend_from_slice(str_bytes);
+
+                    let mut next_page_num = ptr.page_num();
+
+                    while next_page_num != u32::MAX {
+                        let buf =
+                            TableSpace::read_logical_page_(idb_file_path, next_page_num, key)?;
+                        let sdi_blob_page = BasePage::<SdiBlobPage>::new(buf);
+                        bytes.extend_from_slice(&sdi_blob_page.data);
+                        next_page_num = sdi_blob_page.next_page_num;
                     }
-                    let (data, _) = row.col(0);
-                    let tmp: [u8; 4] = data.try_into().unwrap();
-                    let sdi_type = u32::from_be_bytes(tmp);
-                    if sdi_type == 1 {
-                        let (str_bytes, overflow_ptr) = row.col(6);
-                        let value_bytes = if let Some(ptr) = overflow_ptr {
-                            // 读取sdi溢出页
-                            let mut bytes = Vec::with_capacity(ptr.len() as usize);
-                            bytes.extend_from_slice(str_bytes);
-
-                            let mut next_page_num = ptr.page_num();
-
-                            while next_page_num != u32::MAX {
-                                let buf = TableSpace::read_page_(idb_file_path, next_page_num)?;
-                                let sdi_blob_page = BasePage::<SdiBlobPage>::new(buf);
-                                bytes.extend_from_slice(&sdi_blob_page.data);
-                                next_page_num = sdi_blob_page.next_page_num;
+                    bytes
+                } else {
+                    str_bytes.to_vec()
+                };
+                let mut decoder = flate2::read::ZlibDecoder::new(value_bytes.as_slice());
+                let mut out = String::new();
+                decoder.read_to_string(&mut out).unwrap();
+                let value: Value = serde_json::from_str(&out).unwrap();
+                let table_val = value.get("dd_object").unwrap();
+                let table_name = table_val.get("name").unwrap().as_str().unwrap();
+                let columns = table_val.get("columns").unwrap();
+                let mut map = HashMap::new();
+                let mut column_list = Vec::new();
+                let mut map_col = HashMap::new();
+                for val in columns.as_array().unwrap() {
+                    let name = val.get("name").unwrap().as_str().unwrap().to_string();
+
+                    let char_length = val.get("char_length").unwrap().as_u64().unwrap() as usize;
+                    // ENUM、SET 的成员名为 base64 编码
+                    let elements: Vec<String> = val
+                        .get("elements")
+                        .and_then(|x| x.as_array())
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|x| x.get("name").and_then(|x| x.as_str()))
+                        .map(|x| {
+                            let name = STANDARD.decode(x).unwrap_or_default();
+                            String::from_utf8_lossy(&name).into_owned()
+                        })
+                        .collect();
+                    let is_nullable = val.get("is_nullable").unwrap().as_bool().unwrap();
+                    let is_hidden = val.get("hidden").unwrap().as_u64().unwrap() == 2;
+                    // 被 INSTANT 删除的列也是隐藏列，名称如 !hidden!_dropped_v1_p3_c2，保留原来的类型
+                    let data_type = match name.as_str() {
+                        "DB_TRX_ID" if is_hidden => DataType::DbTrxId,
+                        "DB_ROW_ID" if is_hidden => DataType::DbRowId,
+                        "DB_ROLL_PTR" if is_hidden => DataType::DbRollPtr,
+                        _ => {
+                            let data_type = val.get("type").unwrap().as_u64().unwrap() as u8;
+                            let numeric = |key: &str| {
+                                val.get(key).and_then(|x| x.as_u64()).unwrap_or(0) as usize
+                            };
+                            match data_type {
+                                21 => DataType::Decimal(
+                                    numeric("numeric_precision"),
+                                    numeric("numeric_scale"),
+                                ),
+                                18 => DataType::Timestamp(numeric("datetime_precision")),
+                                19 => DataType::Datetime(numeric("datetime_precision")),
+                                20 => DataType::Time(numeric("datetime_precision")),
+                                17 => DataType::Bit(numeric("numeric_precision")),
+                                22 => DataType::Enum(elements.len()),
+                                23 => DataType::Set(elements.len()),
+                                _ => DataType::new(data_type, char_length),
                             }
-                            bytes
+                        }
+                    };
+                    let ordinal_position =
+                        val.get("ordinal_position").unwrap().as_u64().unwrap() as u16;
+                    let mut col =
+                        Column::new(name, data_type, is_nullable, is_hidden, ordinal_position);
+                    if let Some(se_private_data) =
+                        val.get("se_private_data").and_then(|x| x.as_str())
+                    {
+                        col.parse_se_private_data(se_private_data);
+                    }
+                    col.is_virtual = val.get("is_virtual").and_then(|x| x.as_bool()) == Some(true);
+                    col.generation_expression = val
+                        .get("generation_expression_utf8")
+                        .and_then(|x| x.as_str())
+                        .filter(|x| !x.is_empty())
+                        .map(|x| x.to_string());
+                    let collation_id = val
+                        .get("collation_id")
+                        .and_then(|x| x.as_u64())
+                        .map(|x| x as u32);
+                    col.charset = collation_id.and_then(Charset::from_collation_id);
+                    col.collation = collation_id.map(Collation::from_id);
+                    col.elements = Rc::from(elements);
+                    col.comment = sdi_comment(val);
+                    col.column_type = val
+                        .get("column_type_utf8")
+                        .and_then(|x| x.as_str())
+                        .filter(|x| !x.is_empty())
+                        .map(|x| x.to_string());
+                    map_col.insert(ordinal_position - 1, col.clone());
+                    column_list.push(col);
+                }
+                let indexes_v = table_val.get("indexes").unwrap().as_array().unwrap();
+                for val in indexes_v {
+                    let name = val.get("name").unwrap().as_str().unwrap().to_string();
+                    let mut index_id = u64::MAX;
+                    let mut root_page_num = u32::MAX;
+                    let se_private_data = val
+                        .get("se_private_data")
+                        .unwrap()
+                        .as_str()
+                        .unwrap()
+                        .split(";");
+                    for item in se_private_data {
+                        if item.is_empty() {
+                            continue;
+                        }
+                        let mut s = item.split("=");
+                        let n = s.next().unwrap();
+                        let v = s.next().unwrap();
+                        if n == "id" {
+                            index_id = v.parse::<u64>().unwrap();
+                        } else if n == "root" {
+                            root_page_num = v.parse::<u32>().unwrap();
+                        }
+                    }
+                    let is_primary = val.get("type").unwrap().as_u64().unwrap() == 1;
+                    let elements_v = val.get("elements").unwrap().as_array().unwrap();
+                    let mut indexes = Vec::new();
+                    let mut elements = Vec::new();
+                    let mut n_key_parts = 0;
+                    for val in elements_v {
+                        let len = val.get("length").unwrap().as_u64().unwrap() as u32;
+                        let column_opx = val.get("column_opx").unwrap().as_u64().unwrap() as u16;
+                        let col = map_col.get(&column_opx).unwrap();
+                        // 虚拟列不存储在聚簇索引中
+                        if is_primary && col.is_virtual {
+                            continue;
+                        }
+                        let mut col = if is_primary {
+                            col.clone()
                         } else {
-                            str_bytes.to_vec()
+                            col.without_instant()
                         };
-                        let mut decoder = flate2::read::ZlibDecoder::new(value_bytes.as_slice());
-                        let mut out = String::new();
-                        decoder.read_to_string(&mut out).unwrap();
-                        let mut value: Value = serde_json::from_str(&out).unwrap();
-                        let table_val = value.get("dd_object").unwrap();
-                        let table_name = table_val.get("name").unwrap().as_str().unwrap();
-                        let columns = table_val.get("columns").unwrap();
-                        let mut map = HashMap::new();
-                        let mut column_list = Vec::new();
-                        let mut map_col = HashMap::new();
-                        for val in columns.as_array().unwrap() {
-                            let name = val.get("name").unwrap().as_str().unwrap().to_string();
-
-                            let char_length =
-                                val.get("char_length").unwrap().as_u64().unwrap() as usize;
-                            let is_nullable = val.get("is_nullable").unwrap().as_bool().unwrap();
-                            let is_hidden = val.get("hidden").unwrap().as_u64().unwrap() == 2;
-                            let data_type = if is_hidden {
-                                match name.as_str() {
-                                    "DB_TRX_ID" => DataType::DbTrxId,
-                                    "DB_ROW_ID" => DataType::DbRowId,
-                                    "DB_ROLL_PTR" => DataType::DbRollPtr,
-                                    _ => {
-                                        panic!()
-                                    }
-                                }
-                            } else {
-                                let data_type = val.get("type").unwrap().as_u64().unwrap() as u8;
-                                DataType::new(data_type, char_length)
-                            };
-                            let ordinal_position =
-                                val.get("ordinal_position").unwrap().as_u64().unwrap() as u16;
-                            let col = Column::new(
-                                name,
-                                data_type,
-                                is_nullable,
-                                is_hidden,
-                                ordinal_position,
-                            );
-                            map_col.insert(ordinal_position - 1, col.clone());
-                            column_list.push(col);
+                        // 用户定义的索引字段的 length 为字段在索引中的字节数，隐式添加的为 u32::MAX
+                        if len < u32::MAX {
+                            col = col.key_part(len as usize, sdi_order(val));
+                            n_key_parts += 1;
                         }
-                        let indexes_v = table_val.get("indexes").unwrap().as_array().unwrap();
-                        for val in indexes_v {
-                            let name = val.get("name").unwrap().as_str().unwrap().to_string();
-                            let mut index_id = u64::MAX;
-                            let mut root_page_num = u32::MAX;
-                            let mut se_private_data = val
-                                .get("se_private_data")
-                                .unwrap()
-                                .as_str()
-                                .unwrap()
-                                .split(";");
-                            for item in se_private_data {
-                                if item.is_empty() {
-                                    continue;
-                                }
-                                let mut s = item.split("=");
-                                let n = s.next().unwrap();
-                                let v = s.next().unwrap();
-                                if n == "id" {
-                                    index_id = v.parse::<u64>().unwrap();
-                                } else if n == "root" {
-                                    root_page_num = v.parse::<u32>().unwrap();
-                                }
-                            }
-                            let is_primary = val.get("type").unwrap().as_u64().unwrap() == 1;
-                            let elements_v = val.get("elements").unwrap().as_array().unwrap();
-                            let mut indexes = Vec::new();
-                            let mut elements = Vec::new();
-                            for val in elements_v {
-                                let len = val.get("length").unwrap().as_u64().unwrap() as u32;
-                                let column_opx =
-                                    val.get("column_opx").unwrap().as_u64().unwrap() as u16;
-                                let col = map_col.get(&column_opx).unwrap();
-                                if len < u32::MAX {
-                                    indexes.push(col.clone());
-                                }
-                                elements.push(col.clone());
-                            }
-                            let index = Index::new(
-                                index_id,
-                                root_page_num,
-                                name,
-                                is_primary,
-                                indexes,
-                                elements,
-                            );
-                            map.insert(index_id, index);
+                        // 二级索引非叶子节点记录中也包含隐式添加的主键字段
+                        if len < u32::MAX || !is_primary {
+                            indexes.push(col.clone());
                         }
-                        // println!("{:?}", table_name);
-                        // println!("{:?}", map);
-                        return Ok(TableInfo {
-                            name: table_name.to_string(),
-                            indexes: map,
-                        });
+                        elements.push(col);
+                    }
+                    let field_order = if is_primary {
+                        instant_elements(&mut elements, &column_list)
+                    } else {
+                        (0..elements.len()).collect()
+                    };
+                    indexes.push(child_page_num_column(elements.len() as u16 + 1));
+                    let mut index =
+                        Index::new(index_id, root_page_num, name, is_primary, indexes, elements);
+                    index.n_key_parts = n_key_parts;
+                    index.field_order = field_order;
+                    if val.get("type").unwrap().as_u64().unwrap() == DD_INDEX_SPATIAL {
+                        index.elements[0] = mbr_column(&index.elements[0]);
+                        index = spatial_index(index.name, index.elements);
+                        index.index_id = index_id;
+                        index.root_page_num = root_page_num;
                     }
+                    index.comment = sdi_comment(val);
+                    map.insert(index_id, index);
                 }
+                // println!("{:?}", table_name);
+                // println!("{:?}", map);
+                return Ok(TableInfo {
+                    name: table_name.to_string(),
+                    columns: column_list,
+                    indexes: map,
+                    comment: sdi_comment(table_val),
+                });
             }
         }
         let next = index_page.fil_header.next();
         if next == u32::MAX {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "not fount sdi_type = 1",
-            ));
+            return Err(io::Error::other("not fount sdi_type = 1"));
         }
-        let buf = TableSpace::read_page_(idb_file_path, next)?;
+        let buf = TableSpace::read_logical_page_(idb_file_path, next, key)?;
         index_page = BasePage::<SdiPage>::new(buf);
     }
 }
+
+/// SDI 中表、列或索引的注释
+fn sdi_comment(value: &Value) -> String {
+    value
+        .get("comment")
+        .and_then(|x| x.as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 有行版本的表，聚簇索引记录中还保留着已删除的列，各列按 physical_pos 排列。
+/// 已删除的列追加在索引定义的最后，返回索引定义中各字段排序后的下标
+fn instant_elements(elements: &mut Vec<Column>, columns: &[Column]) -> Vec<usize> {
+    for column in columns {
+        if column.version_dropped > 0
+            && !elements
+                .iter()
+                .any(|x| x.ordinal_position == column.ordinal_position)
+        {
+            elements.push(column.clone());
+        }
+    }
+    let mut order: Vec<usize> = (0..elements.len()).collect();
+    if elements.iter().all(|x| x.physical_pos.is_some()) {
+        order.sort_by_key(|&i| elements[i].physical_pos);
+        let sorted: Vec<Column> = order.iter().map(|&i| elements[i].clone()).collect();
+        *elements = sorted;
+    }
+    // order[j] 为排序后第 j 列在索引定义中的序号，取其逆映射
+    let mut field_order = vec![0; order.len()];
+    for (pos, &field) in order.iter().enumerate() {
+        field_order[field] = pos;
+    }
+    field_order
+}
+
+/// 索引定义，columns 为字段在表中的下标（从0开始）
+pub struct KeyDef {
+    pub name: String,
+    pub is_primary: bool,
+    pub is_unique: bool,
+    /// 空间索引，B+树为 R-tree
+    pub is_spatial: bool,
+    pub parts: Vec<KeyPart>,
+    /// 索引注释，没有时为空
+    pub comment: String,
+}
+
+/// 索引中的一个字段
+#[derive(Debug, Clone)]
+pub struct KeyPart {
+    /// 字段下标(从0开始)
+    pub column: usize,
+    /// 字段在索引中的字节数，未指定前缀时为 None
+    pub len: Option<usize>,
+    pub order: Order,
+}
+
+impl KeyPart {
+    fn column(&self, columns: &[Column]) -> Column {
+        let column = &columns[self.column];
+        column.key_part(self.len.unwrap_or(usize::MAX), self.order)
+    }
+}
+
+/// 按InnoDB的规则由字段和索引定义生成索引，聚簇索引在最前面，其余按定义顺序排列。
+/// 返回的索引没有 index_id 和根页号，需要调用方填充
+pub fn build_indexes(columns: &[Column], keys: &[KeyDef]) -> Vec<Index> {
+    // 聚簇索引：主键，其次为第一个字段都不为空的唯一索引，都没有时使用隐藏的DB_ROW_ID
+    let clustered = keys.iter().position(|k| k.is_primary).or_else(|| {
+        keys.iter()
+            .position(|k| k.is_unique && k.parts.iter().all(|p| !columns[p.column].is_nullable))
+    });
+    let hidden = |name: &str, data_type: DataType, offset: u16| {
+        Column::new(
+            String::from(name),
+            data_type,
+            false,
+            true,
+            columns.len() as u16 + offset,
+        )
+    };
+    let row_id = hidden("DB_ROW_ID", DataType::DbRowId, 3);
+    let pk: Vec<Column> = match clustered {
+        Some(i) => keys[i].parts.iter().map(|p| p.column(columns)).collect(),
+        None => vec![row_id],
+    };
+    let mut elements = pk.clone();
+    elements.push(hidden("DB_TRX_ID", DataType::DbTrxId, 1));
+    elements.push(hidden("DB_ROLL_PTR", DataType::DbRollPtr, 2));
+    // 只有前缀索引的主键字段仍要存储完整的列
+    let contains = |elements: &[Column], column: &Column| {
+        elements
+            .iter()
+            .any(|x| x.ordinal_position == column.ordinal_position && x.prefix_len.is_none())
+    };
+    for column in columns {
+        if !column.is_virtual && !contains(&pk, column) {
+            elements.push(column.clone());
+        }
+    }
+    let mut indexes = pk.clone();
+    indexes.push(child_page_num_column(elements.len() as u16 + 1));
+    let name = clustered
+        .map(|i| keys[i].name.clone())
+        .unwrap_or_else(|| String::from("GEN_CLUST_INDEX"));
+    let mut clustered_index = Index::new(u64::MAX, u32::MAX, name, true, indexes, elements);
+    if let Some(i) = clustered {
+        clustered_index.comment = keys[i].comment.clone();
+    }
+    let mut result = vec![clustered_index];
+
+    for (i, key) in keys.iter().enumerate() {
+        if Some(i) == clustered {
+            continue;
+        }
+        if key.is_spatial {
+            let mut elements = vec![mbr_column(&columns[key.parts[0].column])];
+            elements.extend(pk.iter().cloned());
+            let mut index = spatial_index(key.name.clone(), elements);
+            index.comment = key.comment.clone();
+            result.push(index);
+            continue;
+        }
+        let mut elements: Vec<Column> = key.parts.iter().map(|p| p.column(columns)).collect();
+        // 二级索引记录中追加不在索引中的主键字段
+        for column in &pk {
+            if !contains(&elements, column) {
+                elements.push(column.clone());
+            }
+        }
+        // 二级索引的非叶子节点记录包含全部字段(唯一索引的键也可能因 NULL 重复)和子页号
+        let mut indexes = elements.clone();
+        indexes.push(child_page_num_column(elements.len() as u16 + 1));
+        let mut index = Index::new(
+            u64::MAX,
+            u32::MAX,
+            key.name.clone(),
+            false,
+            indexes,
+            elements,
+        );
+        index.n_key_parts = key.parts.len();
+        index.comment = key.comment.clone();
+        result.push(index);
+    }
+    result
+}
+
+/// R-tree 索引记录中空间字段的键为 MBR
+pub fn mbr_column(column: &Column) -> Column {
+    let mut column = column.clone();
+    column.data_type = DataType::Mbr;
+    column.is_nullable = false;
+    column
+}
+
+/// 空间索引，叶子节点为 MBR 和主键字段，非叶子节点只有 MBR 和子页号
+pub fn spatial_index(name: String, elements: Vec<Column>) -> Index {
+    let indexes = vec![
+        elements[0].clone(),
+        child_page_num_column(elements.len() as u16 + 1),
+    ];
+    Index::new(u64::MAX, u32::MAX, name, false, indexes, elements)
+}
+
+/// 为索引分配 index_id 和根页号，index_ids 中指定了 id 的索引使用指定值，
+/// 其余索引按创建顺序依次使用表空间中剩余的 index_id
+pub fn assign_index_roots(
+    indexes: Vec<Index>,
+    roots: &[(u64, u32)],
+    index_ids: &HashMap<String, u64>,
+) -> HashMap<u64, Index> {
+    let root_of = |index_id: u64| {
+        roots
+            .iter()
+            .find(|(id, _)| *id == index_id)
+            .map(|(_, page_num)| *page_num)
+            .unwrap_or(u32::MAX)
+    };
+    let mut remaining = roots
+        .iter()
+        .filter(|(id, _)| !index_ids.values().any(|x| x == id));
+    let mut map = HashMap::new();
+    for (i, mut index) in indexes.into_iter().enumerate() {
+        if let Some(index_id) = index_ids.get(&index.name) {
+            index.index_id = *index_id;
+            index.root_page_num = root_of(*index_id);
+        } else if let Some((index_id, root_page_num)) = remaining.next() {
+            index.index_id = *index_id;
+            index.root_page_num = *root_page_num;
+        } else {
+            index.index_id = u64::MAX - 1 - i as u64;
+        }
+        map.insert(index.index_id, index);
+    }
+    map
+}
+
+/// 由用户提供的建表语句构建表结构
+pub fn read_schema_table_info(
+    idb_file_path: &str,
+    schema_path: &Path,
+    index_ids: &HashMap<String, u64>,
+    key: Option<&TablespaceKey>,
+) -> io::Result<TableInfo> {
+    let sql = std::fs::read_to_string(schema_path)?;
+    let schema = parse_create_table(&sql)?;
+    let roots = TableSpace::scan_index_roots(idb_file_path, key)?;
+    Ok(TableInfo {
+        name: schema.name,
+        indexes: assign_index_roots(
+            build_indexes(&schema.columns, &schema.keys),
+            &roots,
+            index_ids,
+        ),
+        columns: schema.columns,
+        comment: String::new(),
+    })
+}
+
+/// MySQL 5.7 的表空间中没有sdi，从 .frm 文件读取表结构
+pub fn read_frm_table_info(
+    idb_file_path: &str,
+    frm_path: &Path,
+    key: Option<&TablespaceKey>,
+) -> io::Result<TableInfo> {
+    let table = FrmTable::open(frm_path)?;
+    let columns: Vec<Column> = table
+        .columns
+        .iter()
+        .enumerate()
+        .map(|(i, col)| {
+            let data_type = match col.data_type.dd_type() {
+                21 => DataType::Decimal(col.precision() as usize, col.decimals as usize),
+                18 => DataType::Timestamp(col.decimals as usize),
+                19 => DataType::Datetime(col.decimals as usize),
+                20 => DataType::Time(col.decimals as usize),
+                17 => DataType::Bit(col.len as usize),
+                22 => DataType::Enum(col.elements.len()),
+                23 => DataType::Set(col.elements.len()),
+                dd_type => DataType::new(dd_type, col.innodb_len()),
+            };
+            let mut column = Column::new(
+                col.name.clone(),
+                data_type,
+                col.is_null,
+                false,
+                i as u16 + 1,
+            );
+            column.charset = Charset::from_collation_id(col.charset as u32);
+            column.collation = Some(Collation::from_id(col.charset as u32));
+            column.column_type = Some(col.column_type());
+            column.elements = Rc::from(col.elements.clone());
+            column.default = col.default.clone();
+            column.comment = col.comment.clone();
+            column
+        })
+        .collect();
+    let keys: Vec<KeyDef> = table
+        .innodb_keys()
+        .map(|key| KeyDef {
+            name: key.name.clone(),
+            is_primary: key.is_primary(),
+            is_unique: key.is_unique,
+            is_spatial: key.is_spatial(),
+            parts: key
+                .parts
+                .iter()
+                .map(|&(column, len)| KeyPart {
+                    column,
+                    len: Some(len as usize),
+                    order: Order::Asc,
+                })
+                .collect(),
+            comment: key.comment.clone(),
+        })
+        .collect();
+    let roots = TableSpace::scan_index_roots(idb_file_path, key)?;
+    Ok(TableInfo {
+        name: table.name,
+        indexes: assign_index_roots(build_indexes(&columns, &keys), &roots, &HashMap::new()),
+        columns,
+        comment: table.comment,
+    })
+}
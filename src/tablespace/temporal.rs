@@ -0,0 +1,221 @@
+use crate::tablespace::data_type::DataType;
+use std::io;
+
+/// 小数秒占用的字节数，精度每2位1字节
+pub fn frac_len(fsp: usize) -> usize {
+    fsp.min(6).div_ceil(2)
+}
+
+/// 读取大端整数
+fn be(buf: &[u8]) -> i64 {
+    buf.iter().fold(0i64, |acc, x| (acc << 8) | *x as i64)
+}
+
+/// 读取小数秒并转换为微秒，1~2位精度以百分之一秒存储，3~4位以万分之一秒存储
+fn read_frac(buf: &[u8]) -> i64 {
+    let value = be(buf);
+    match buf.len() {
+        1 => value * 10000,
+        2 => value * 100,
+        _ => value,
+    }
+}
+
+/// 微秒按精度截取的小数部分，如 .123
+fn format_frac(micro: i64, fsp: usize) -> String {
+    if fsp == 0 {
+        String::new()
+    } else {
+        format!(".{}", &format!("{:06}", micro)[..fsp.min(6)])
+    }
+}
+
+fn check_len(name: &str, fsp: usize, base: usize, buf: &[u8]) -> io::Result<()> {
+    if buf.len() == base + frac_len(fsp) {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "invalid {}({}) length {}",
+            name,
+            fsp,
+            buf.len()
+        )))
+    }
+}
+
+/// DATETIME2：5字节整数部分加小数秒，整数部分减去 0x8000000000 后为
+///
+/// 符号 |年*13+月|日|时|分|秒
+/// ---|---|---|---|---|---
+/// 1 bit|17 bit|5 bit|5 bit|6 bit|6 bit
+pub fn datetime(fsp: usize, buf: &[u8]) -> io::Result<String> {
+    check_len("datetime", fsp, 5, buf)?;
+    let int_part = be(&buf[..5]) - 0x80_0000_0000;
+    let micro = read_frac(&buf[5..]);
+    Ok(format_datetime(int_part, micro, fsp))
+}
+
+/// 由 DATETIME2 的整数部分和微秒得到日期时间字符串
+pub fn format_datetime(int_part: i64, micro: i64, fsp: usize) -> String {
+    let ymd = int_part >> 17;
+    let hms = int_part & 0x1FFFF;
+    let (year, month) = ((ymd >> 5) / 13, (ymd >> 5) % 13);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        year,
+        month,
+        ymd & 0x1F,
+        hms >> 12,
+        (hms >> 6) & 0x3F,
+        hms & 0x3F,
+        format_frac(micro, fsp)
+    )
+}
+
+/// TIME2：3字节整数部分加小数秒，减去 0x800000 后为
+///
+/// 符号 |未使用|时|分|秒
+/// ---|---|---|---|---
+/// 1 bit|1 bit|10 bit|6 bit|6 bit
+///
+/// 负数的小数秒按 0x100(或0x10000)减去小数部分存储，整数部分相应减1，以保证按字节比较的顺序；
+/// 5~6位精度时整数部分和小数部分一起作为6字节整数存储
+pub fn time(fsp: usize, buf: &[u8]) -> io::Result<String> {
+    check_len("time", fsp, 3, buf)?;
+    let packed = if fsp > 4 {
+        be(buf) - 0x8000_0000_0000
+    } else {
+        let mut int_part = be(&buf[..3]) - 0x80_0000;
+        let mut micro = read_frac(&buf[3..]);
+        if int_part < 0 && micro != 0 {
+            int_part += 1;
+            micro -= match buf.len() - 3 {
+                1 => 0x100 * 10000,
+                _ => 0x10000 * 100,
+            };
+        }
+        (int_part << 24) + micro
+    };
+    Ok(format_time(packed, fsp))
+}
+
+/// 由整数部分左移24位加微秒的时间值得到时间字符串
+pub fn format_time(packed: i64, fsp: usize) -> String {
+    let sign = if packed < 0 { "-" } else { "" };
+    let packed = packed.abs();
+    let hms = packed >> 24;
+    format!(
+        "{}{:02}:{:02}:{:02}{}",
+        sign,
+        (hms >> 12) & 0x3FF,
+        (hms >> 6) & 0x3F,
+        hms & 0x3F,
+        format_frac(packed & 0xFF_FFFF, fsp)
+    )
+}
+
+/// TIMESTAMP2：4字节 UTC 秒数加小数秒。time_zone 为相对 UTC 的秒数，未指定时按 UTC 显示
+pub fn timestamp(fsp: usize, time_zone: Option<i32>, buf: &[u8]) -> io::Result<String> {
+    check_len("timestamp", fsp, 4, buf)?;
+    let seconds = be(&buf[..4]);
+    let micro = read_frac(&buf[4..]);
+    let frac = format_frac(micro, fsp);
+    if seconds == 0 {
+        return Ok(format!("0000-00-00T00:00:00{}", frac));
+    }
+    let offset = time_zone.unwrap_or(0) as i64;
+    let local = seconds + offset;
+    let (year, month, day) = civil_from_days(local.div_euclid(86400));
+    let secs = local.rem_euclid(86400);
+    let zone = match time_zone {
+        None | Some(0) => String::from("Z"),
+        Some(_) => format!(
+            "{}{:02}:{:02}",
+            if offset < 0 { '-' } else { '+' },
+            offset.abs() / 3600,
+            offset.abs() % 3600 / 60
+        ),
+    };
+    Ok(format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        frac,
+        zone
+    ))
+}
+
+/// 1970-01-01 起的天数转换为公历日期
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// 解析时区偏移，如 +08:00、-05:30、UTC
+pub fn parse_time_zone(value: &str) -> Result<i32, String> {
+    if value.eq_ignore_ascii_case("utc") || value == "Z" {
+        return Ok(0);
+    }
+    let invalid = || format!("invalid time zone {}, expected +HH:MM", value);
+    let (sign, rest) = match value.as_bytes().first() {
+        Some(b'+') => (1, &value[1..]),
+        Some(b'-') => (-1, &value[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hour, minute) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hour: i32 = hour.parse().map_err(|_| invalid())?;
+    let minute: i32 = minute.parse().map_err(|_| invalid())?;
+    if hour > 14 || minute >= 60 {
+        return Err(invalid());
+    }
+    Ok(sign * (hour * 3600 + minute * 60))
+}
+
+/// 时间类型列的值，非时间类型返回 None
+pub fn format_temporal(
+    data_type: DataType,
+    time_zone: Option<i32>,
+    data: &[u8],
+) -> Option<io::Result<String>> {
+    match data_type {
+        DataType::Datetime(fsp) => Some(datetime(fsp, data)),
+        DataType::Time(fsp) => Some(time(fsp, data)),
+        DataType::Timestamp(fsp) => Some(timestamp(fsp, time_zone, data)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_negative_fraction() {
+        // TIME(3) -00:00:00.5：整数部分为-1，小数部分为 0x10000 - 5000
+        let value = time(3, &[0x7F, 0xFF, 0xFF, 0xEC, 0x78]).unwrap();
+        assert_eq!(value, "-00:00:00.500");
+    }
+
+    #[test]
+    fn time_positive() {
+        // TIME 12:34:56
+        assert_eq!(time(0, &[0x80, 0xC8, 0xB8]).unwrap(), "12:34:56");
+    }
+
+    #[test]
+    fn time_invalid_length() {
+        assert!(time(3, &[0x80, 0x00, 0x00]).is_err());
+    }
+}
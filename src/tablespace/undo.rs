@@ -0,0 +1,236 @@
+use crate::page::index_page::page::IndexPage;
+use crate::page::index_page::records::Row;
+use crate::page::undo_page::record::{RollPtr, UndoField, UndoRecord};
+use crate::tablespace::data_type::DataType;
+use crate::tablespace::{is_system_file, is_undo_file, TableSpace};
+use bytes::Bytes;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 沿着undo链最多回溯的版本数，防止损坏的回滚指针形成环
+const MAX_VERSIONS: usize = 10000;
+/// info bits 中的删除标记
+const REC_INFO_DELETED_FLAG: u8 = 0x20;
+
+/// 记录对某个事务可见的版本
+#[derive(Debug)]
+pub struct RowVersion {
+    /// 产生该版本的事务ID
+    pub trx_id: u64,
+    pub deleted: bool,
+    /// 按聚簇索引叶子节点的列顺序
+    pub values: Vec<UndoField>,
+}
+
+/// undo记录所在的文件：系统表空间(ibdata1)和独立的undo表空间(undo_001、.ibu)
+#[derive(Debug, Default)]
+pub struct UndoFiles {
+    pub system: Option<PathBuf>,
+    pub undo: Vec<PathBuf>,
+}
+
+impl UndoFiles {
+    /// 使用指定的文件，ibdata开头的为系统表空间，其余按顺序为undo表空间；
+    /// 未指定时从数据目录中查找
+    pub fn new(idb_file_path: &str, files: &[String]) -> io::Result<Self> {
+        if !files.is_empty() {
+            let mut undo_files = UndoFiles::default();
+            for file in files {
+                let path = PathBuf::from(file);
+                if is_system_file(&path) {
+                    undo_files.system = Some(path);
+                } else {
+                    undo_files.undo.push(path);
+                }
+            }
+            return Ok(undo_files);
+        }
+        let path = Path::new(idb_file_path);
+        // 系统表空间在数据目录下，用户表空间在数据目录下的库目录中
+        let data_dir = if is_system_file(path) {
+            path.parent()
+        } else {
+            path.parent().and_then(Path::parent)
+        };
+        let Some(data_dir) = data_dir else {
+            return Ok(UndoFiles::default());
+        };
+        let system = data_dir.join("ibdata1");
+        let mut undo = Vec::new();
+        if data_dir.is_dir() {
+            for entry in fs::read_dir(data_dir)? {
+                let path = entry?.path();
+                if is_undo_file(&path) {
+                    undo.push(path);
+                }
+            }
+        }
+        undo.sort();
+        Ok(Self {
+            system: system.exists().then_some(system),
+            undo,
+        })
+    }
+    /// 回滚段所在的文件。0号回滚段在系统表空间，其余回滚段轮流分配到各个undo表空间
+    pub fn path(&self, rseg_id: u8) -> Option<&PathBuf> {
+        if rseg_id == 0 || self.undo.is_empty() {
+            self.system.as_ref()
+        } else {
+            self.undo.get(rseg_id as usize % self.undo.len())
+        }
+    }
+    /// 读取回滚指针指向的undo记录
+    pub fn record(&self, roll_ptr: &RollPtr, n_unique: Option<usize>) -> io::Result<UndoRecord> {
+        let path = self.path(roll_ptr.rseg_id).ok_or_else(|| {
+            io::Error::other(format!("undo file of rseg {} not found", roll_ptr.rseg_id))
+        })?;
+        let buf = TableSpace::read_page_(&path.to_string_lossy(), roll_ptr.page_num)?;
+        UndoRecord::new(&buf.slice(38..buf.len() - 8), roll_ptr.offset, n_unique)
+    }
+    /// 打印聚簇索引叶子页中每条记录的回滚指针和它指向的上一个版本
+    pub fn print_versions(&self, page: &IndexPage) {
+        let columns = page.columns();
+        let Some(pos) = columns
+            .iter()
+            .position(|col| col.data_type == DataType::DbRollPtr)
+        else {
+            return;
+        };
+        // 聚簇索引的唯一列，即非叶子节点中除子页号外的列
+        let n_unique = page.index.indexes.len().checked_sub(1);
+        println!("previous versions:");
+        for row in &page.user_records {
+            if pos >= row.col_info().len() {
+                continue;
+            }
+            let (data, _) = row.col(pos);
+            let Some(roll_ptr) = RollPtr::from_bytes(data) else {
+                continue;
+            };
+            let location = self
+                .path(roll_ptr.rseg_id)
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "?".to_string());
+            println!(
+                " heap_no:{} roll_ptr:({}) file:{}",
+                row.heap_no(),
+                roll_ptr,
+                location
+            );
+            match self.record(&roll_ptr, n_unique) {
+                Ok(record) => println!("   {}", record),
+                Err(e) => println!("   {}", e),
+            }
+        }
+    }
+    /// 从记录的当前版本开始沿着回滚指针回溯，直到 DB_TRX_ID 不大于 as_of_trx 的版本；
+    /// 该事务时记录还未插入时返回 None
+    pub fn version_as_of(
+        &self,
+        page: &IndexPage,
+        row: &Row<Bytes>,
+        as_of_trx: u64,
+    ) -> io::Result<Option<RowVersion>> {
+        let columns = page.columns();
+        let position = |data_type| {
+            columns
+                .iter()
+                .position(|col| col.data_type == data_type)
+                .filter(|pos| *pos < row.col_info().len())
+                .ok_or_else(|| io::Error::other("not a clustered index leaf record"))
+        };
+        let trx_pos = position(DataType::DbTrxId)?;
+        let roll_pos = position(DataType::DbRollPtr)?;
+        let n_unique = page.index.indexes.len().checked_sub(1);
+
+        let mut values: Vec<UndoField> = (0..row.col_info().len())
+            .map(|index| {
+                let (data, ptr) = row.col(index);
+                if row.is_null(index) {
+                    UndoField::Null
+                } else if let Some(ptr) = ptr {
+                    UndoField::Extern([data, ptr.buf].concat())
+                } else {
+                    UndoField::Data(data.to_vec())
+                }
+            })
+            .collect();
+        let mut deleted = row.delete_mask() == 1;
+        for _ in 0..MAX_VERSIONS {
+            let (UndoField::Data(trx_id), UndoField::Data(roll_ptr)) =
+                (&values[trx_pos], &values[roll_pos])
+            else {
+                return Err(io::Error::other("invalid DB_TRX_ID or DB_ROLL_PTR"));
+            };
+            let trx_id = trx_id.iter().fold(0u64, |acc, b| acc << 8 | *b as u64);
+            if trx_id <= as_of_trx {
+                return Ok(Some(RowVersion {
+                    trx_id,
+                    deleted,
+                    values,
+                }));
+            }
+            let roll_ptr = RollPtr::from_bytes(roll_ptr)
+                .ok_or_else(|| io::Error::other("invalid DB_ROLL_PTR"))?;
+            // 插入产生的undo记录之前没有版本
+            if roll_ptr.is_insert {
+                return Ok(None);
+            }
+            let record = self.record(&roll_ptr, n_unique)?;
+            let (Some(info_bits), Some(prev_trx_id), Some(prev_roll_ptr)) =
+                (record.info_bits, record.trx_id, record.roll_ptr)
+            else {
+                return Ok(None);
+            };
+            // 字段号为索引定义中的序号，有行版本的表记录中的列按 physical_pos 排列
+            for (field_no, field) in record.update {
+                let Some(&index) = page.index.field_order.get(field_no as usize) else {
+                    continue;
+                };
+                if let Some(value) = values.get_mut(index) {
+                    *value = field;
+                }
+            }
+            deleted = info_bits & REC_INFO_DELETED_FLAG != 0;
+            values[trx_pos] = UndoField::Data(prev_trx_id.to_be_bytes()[2..].to_vec());
+            let prev_roll_ptr = prev_roll_ptr.value();
+            values[roll_pos] = UndoField::Data(prev_roll_ptr.to_be_bytes()[1..].to_vec());
+        }
+        Err(io::Error::other("too many versions in undo chain"))
+    }
+    /// 打印聚簇索引叶子页中每条记录对事务 as_of_trx 可见的版本
+    pub fn print_as_of(&self, page: &IndexPage, as_of_trx: u64) {
+        if !page
+            .columns()
+            .iter()
+            .any(|col| col.data_type == DataType::DbRollPtr)
+        {
+            return;
+        }
+        println!("as of trx {}:", as_of_trx);
+        print!(" heap_no / trx_id / deleted /");
+        for col in page.columns() {
+            print!(" {} /", col.name);
+        }
+        println!();
+        for row in &page.user_records {
+            match self.version_as_of(page, row, as_of_trx) {
+                Ok(Some(version)) => {
+                    print!(
+                        " heap_no:{} trx_id:{} deleted:{} /",
+                        row.heap_no(),
+                        version.trx_id,
+                        version.deleted
+                    );
+                    for value in &version.values {
+                        print!(" {} /", value);
+                    }
+                    println!();
+                }
+                Ok(None) => println!(" heap_no:{} not inserted yet", row.heap_no()),
+                Err(e) => println!(" heap_no:{} {}", row.heap_no(), e),
+            }
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use crate::file_header::FileHeader;
+use crate::page::index_page::records::Row;
+use crate::page::PageEnums;
+use crate::tablespace::TableSpace;
+use console::{pad_str, style, Alignment, Key, Term};
+use std::io;
+
+/// 交互式页浏览器
+///
+/// 左侧为页列表或页内记录，右侧为选中记录、页结构或十六进制内容，
+/// 跳转过的页记录在历史栈中，可以逐级返回
+pub struct Navigator<'a> {
+    tablespace: &'a TableSpace,
+    term: Term,
+    page_count: u32,
+    view: View,
+    history: Vec<View>,
+    pane: Pane,
+    pane_scroll: usize,
+    message: String,
+    /// 当前打开的页，按键时不重复解析
+    page: Option<(u32, io::Result<PageEnums>)>,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum View {
+    /// 页列表，当前选中的页号
+    List { selected: u32 },
+    /// 具体页，当前选中的记录
+    Page { page_num: u32, selected: usize },
+}
+
+/// 右侧窗格显示的内容
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+enum Pane {
+    Record,
+    Header,
+    Hex,
+}
+
+impl Pane {
+    fn next(self) -> Pane {
+        match self {
+            Pane::Record => Pane::Header,
+            Pane::Header => Pane::Hex,
+            Pane::Hex => Pane::Record,
+        }
+    }
+}
+
+const HELP: &str = "↑↓选择 Enter进入 n/p兄弟页 b返回 Tab切换 [/]滚动 g跳转 q退出";
+
+impl<'a> Navigator<'a> {
+    pub fn new(tablespace: &'a TableSpace) -> io::Result<Self> {
+        let page_count = tablespace.fsp_page()?.fsp_header.size();
+        Ok(Self {
+            tablespace,
+            term: Term::stdout(),
+            page_count,
+            view: View::List { selected: 0 },
+            history: Vec::new(),
+            pane: Pane::Record,
+            pane_scroll: 0,
+            message: String::new(),
+            page: None,
+        })
+    }
+
+    pub fn run(&mut self) -> io::Result<()> {
+        self.term.hide_cursor()?;
+        let result = self.event_loop();
+        self.term.show_cursor()?;
+        self.term.clear_screen()?;
+        result
+    }
+
+    fn event_loop(&mut self) -> io::Result<()> {
+        loop {
+            self.load();
+            self.render()?;
+            let key = self.term.read_key()?;
+            self.message.clear();
+            match key {
+                Key::Char('q') | Key::Escape => return Ok(()),
+                Key::ArrowUp | Key::Char('k') => self.move_selection(-1),
+                Key::ArrowDown | Key::Char('j') => self.move_selection(1),
+                Key::PageUp => self.move_selection(-(self.list_height() as i64)),
+                Key::PageDown => self.move_selection(self.list_height() as i64),
+                Key::Enter | Key::ArrowRight => self.enter(),
+                Key::Backspace | Key::ArrowLeft | Key::Char('b') => self.back(),
+                Key::Char('n') => self.sibling(true),
+                Key::Char('p') => self.sibling(false),
+                Key::Tab => {
+                    self.pane = self.pane.next();
+                    self.pane_scroll = 0;
+                }
+                Key::Char('[') => self.pane_scroll = self.pane_scroll.saturating_sub(10),
+                Key::Char(']') => self.pane_scroll += 10,
+                Key::Char('g') => self.goto()?,
+                _ => {}
+            }
+        }
+    }
+
+    /// 解析当前视图的页，同一页只解析一次
+    fn load(&mut self) {
+        if let View::Page { page_num, .. } = self.view {
+            if self.page.as_ref().map(|(num, _)| *num) != Some(page_num) {
+                self.page = Some((page_num, self.tablespace.page(page_num)));
+            }
+        }
+    }
+
+    /// 当前视图的页，由 load 解析
+    fn current_page(&self) -> Option<&io::Result<PageEnums>> {
+        match self.view {
+            View::Page { page_num, .. } => self
+                .page
+                .as_ref()
+                .filter(|(num, _)| *num == page_num)
+                .map(|(_, page)| page),
+            View::List { .. } => None,
+        }
+    }
+
+    /// 打开新页，当前视图压入历史栈
+    fn open(&mut self, page_num: u32) {
+        if page_num >= self.page_count {
+            self.message = format!("页号{}超出范围(0..{})", page_num, self.page_count);
+            return;
+        }
+        self.history.push(self.view);
+        self.view = View::Page {
+            page_num,
+            selected: 0,
+        };
+        self.pane_scroll = 0;
+    }
+
+    fn back(&mut self) {
+        if let Some(view) = self.history.pop() {
+            self.view = view;
+            self.pane_scroll = 0;
+        }
+    }
+
+    fn enter(&mut self) {
+        match self.view {
+            View::List { selected } => self.open(selected),
+            View::Page { selected, .. } => match self.current_page() {
+                Some(Ok(page)) => match follow(page, selected) {
+                    Some(child) => self.open(child),
+                    None => self.message = String::from("该记录没有子页或溢出页"),
+                },
+                Some(Err(e)) => self.message = e.to_string(),
+                None => {}
+            },
+        }
+    }
+
+    /// 沿着FileHeader中的prev/next跳转
+    fn sibling(&mut self, next: bool) {
+        if let View::Page { page_num, .. } = self.view {
+            match self.tablespace.read_page(page_num) {
+                Ok(buf) => {
+                    let fil_header = FileHeader::new(buf.slice(..38));
+                    let sibling = if next {
+                        fil_header.next()
+                    } else {
+                        fil_header.prev()
+                    };
+                    if sibling == u32::MAX {
+                        self.message = String::from("没有兄弟页");
+                    } else {
+                        self.open(sibling);
+                    }
+                }
+                Err(e) => self.message = e.to_string(),
+            }
+        }
+    }
+
+    fn goto(&mut self) -> io::Result<()> {
+        self.term.show_cursor()?;
+        self.term.write_str("页号: ")?;
+        let line = self.term.read_line()?;
+        self.term.hide_cursor()?;
+        match line.trim().parse::<u32>() {
+            Ok(page_num) => self.open(page_num),
+            Err(_) => self.message = format!("无效的页号: {}", line.trim()),
+        }
+        Ok(())
+    }
+
+    fn move_selection(&mut self, delta: i64) {
+        match &mut self.view {
+            View::List { selected } => {
+                let max = self.page_count.saturating_sub(1) as i64;
+                *selected = (*selected as i64 + delta).clamp(0, max) as u32;
+            }
+            View::Page { .. } => {
+                let records = match self.current_page() {
+                    Some(Ok(page)) => page.index_page().map_or(0, |p| p.user_records.len()),
+                    _ => 0,
+                };
+                let View::Page { selected, .. } = &mut self.view else {
+                    return;
+                };
+                let max = records.saturating_sub(1) as i64;
+                *selected = (*selected as i64 + delta).clamp(0, max) as usize;
+                self.pane_scroll = 0;
+            }
+        }
+    }
+
+    fn list_height(&self) -> usize {
+        let (rows, _) = self.term.size();
+        (rows as usize).saturating_sub(3).max(1)
+    }
+
+    fn render(&self) -> io::Result<()> {
+        let (_, cols) = self.term.size();
+        let height = self.list_height();
+        let left_width = (cols as usize * 2 / 5).max(20);
+        let right_width = (cols as usize).saturating_sub(left_width + 3).max(10);
+        let (title, left, right) = match self.view {
+            View::List { selected } => self.render_list(selected, height),
+            View::Page { page_num, selected } => self.render_page(page_num, selected, height),
+        };
+        let mut out = String::new();
+        out.push_str(&format!("{}\n", style(title).green()));
+        let right = right.iter().skip(self.pane_scroll);
+        let mut right = right.take(height);
+        for index in 0..height {
+            let l = left.get(index).map(String::as_str).unwrap_or("");
+            let r = right.next().map(String::as_str).unwrap_or("");
+            out.push_str(&pad_str(l, left_width, Alignment::Left, Some("…")));
+            out.push_str(" │ ");
+            out.push_str(&pad_str(r, right_width, Alignment::Left, Some("…")));
+            out.push('\n');
+        }
+        if self.message.is_empty() {
+            out.push_str(&format!("{}", style(HELP).dim()));
+        } else {
+            out.push_str(&format!("{}", style(&self.message).red()));
+        }
+        self.term.clear_screen()?;
+        self.term.write_str(&out)
+    }
+
+    fn render_list(&self, selected: u32, height: usize) -> (String, Vec<String>, Vec<String>) {
+        let top = (selected as usize / height) * height;
+        let mut left = Vec::with_capacity(height);
+        for page_num in top..(top + height).min(self.page_count as usize) {
+            let line = match self.tablespace.read_page(page_num as u32) {
+                Ok(buf) => {
+                    let fil_header = FileHeader::new(buf.slice(..38));
+                    format!("{:>6} {:?}", page_num, fil_header.page_type())
+                }
+                Err(e) => format!("{:>6} {}", page_num, e),
+            };
+            left.push(highlight(line, page_num == selected as usize));
+        }
+        let right = match self.tablespace.read_page(selected) {
+            Ok(buf) => FileHeader::new(buf.slice(..38))
+                .to_string()
+                .lines()
+                .map(str::to_string)
+                .collect(),
+            Err(e) => vec![e.to_string()],
+        };
+        let title = format!("{} 共{}页", self.tablespace.idb_file_path, self.page_count);
+        (title, left, right)
+    }
+
+    fn render_page(
+        &self,
+        page_num: u32,
+        selected: usize,
+        height: usize,
+    ) -> (String, Vec<String>, Vec<String>) {
+        let page = match self.current_page() {
+            Some(Ok(page)) => page,
+            Some(Err(e)) => return (format!("page {}", page_num), vec![e.to_string()], vec![]),
+            None => return (format!("page {}", page_num), vec![], vec![]),
+        };
+        let fil_header = page.fil_header();
+        let title = format!(
+            "page {} {:?} prev:{} next:{} lsn:{} 历史:{}",
+            page_num,
+            fil_header.page_type(),
+            fil_header.prev() as i32,
+            fil_header.next() as i32,
+            fil_header.lsn(),
+            self.history.len()
+        );
+        let mut left = Vec::new();
+        if let Some(index_page) = page.index_page() {
+            let top = (selected / height) * height;
+            for (index, row) in index_page.user_records.iter().enumerate().skip(top) {
+                if left.len() == height {
+                    break;
+                }
+                let mut line = format!(
+                    "{:>4} heap:{} del:{}",
+                    index,
+                    row.heap_no(),
+                    row.delete_mask()
+                );
+                if let Some(child) = index_page.child_page_num(row) {
+                    line.push_str(&format!(" -> {}", child));
+                } else if overflow_page(page, index).is_some() {
+                    line.push_str(" overflow");
+                }
+                left.push(highlight(line, index == selected));
+            }
+            if left.is_empty() {
+                left.push(String::from("(无记录)"));
+            }
+        } else {
+            left.push(String::from("(非索引页)"));
+        }
+        let right = match self.pane {
+            Pane::Record => record_lines(page, selected),
+            Pane::Header => page.to_string().lines().map(str::to_string).collect(),
+            Pane::Hex => hex_lines(page.buf()),
+        };
+        (title, left, right)
+    }
+}
+
+fn highlight(line: String, selected: bool) -> String {
+    if selected {
+        style(line).reverse().to_string()
+    } else {
+        line
+    }
+}
+
+/// 选中记录可跳转的页：非叶子节点的子页，或者第一个溢出列的溢出页
+fn follow(page: &PageEnums, selected: usize) -> Option<u32> {
+    let index_page = page.index_page()?;
+    let row = index_page.user_records.get(selected)?;
+    index_page
+        .child_page_num(row)
+        .or_else(|| overflow_page(page, selected))
+}
+
+fn overflow_page(page: &PageEnums, selected: usize) -> Option<u32> {
+    let index_page = page.index_page()?;
+    let row = index_page.user_records.get(selected)?;
+    (0..row.col_info().len()).find_map(|index| row.col(index).1.map(|ptr| ptr.page_num()))
+}
+
+fn record_lines(page: &PageEnums, selected: usize) -> Vec<String> {
+    let index_page = match page.index_page() {
+        Some(index_page) => index_page,
+        None => return page.to_string().lines().map(str::to_string).collect(),
+    };
+    let row = match index_page.user_records.get(selected) {
+        Some(row) => row,
+        None => return Vec::new(),
+    };
+    let mut lines = Vec::new();
+    match row {
+        Row::New(row) => lines.push(format!("{:?}", row.header())),
+        Row::Old(row) => lines.push(format!("{:?}", row.header())),
+    }
+    for (index, col) in index_page.columns().iter().enumerate() {
+        if index >= row.col_info().len() {
+            break;
+        }
+        let (_, ptr) = row.col(index);
+        let mut line = format!("{}: {}", style(&col.name).yellow(), row.value(index));
+        if let Some(ptr) = ptr {
+            line.push_str(&format!(" (overflow {})", ptr));
+        }
+        lines.push(line);
+    }
+    lines.push(String::new());
+    lines.extend(row.to_string().lines().map(str::to_string));
+    lines
+}
+
+fn hex_lines(buf: &[u8]) -> Vec<String> {
+    buf.chunks(16)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let ascii: String = chunk
+                .iter()
+                .map(|x| {
+                    if x.is_ascii_graphic() {
+                        *x as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:06x} {:<47} {}", index * 16, hex_bytes(chunk), ascii)
+        })
+        .collect()
+}
+
+fn hex_bytes(chunk: &[u8]) -> String {
+    chunk
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect::<Vec<_>>()
+        .join(" ")
+}